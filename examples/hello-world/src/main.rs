@@ -1,29 +1,25 @@
-use std::{
-    collections::{BTreeMap, HashSet},
-    time::Duration,
-};
+use std::collections::{BTreeMap, HashSet};
 
 use http::StatusCode;
 use predawn::{
     app::{run_app, Hooks},
+    config::rate_limit::RateLimitConfig,
     controller,
     extract::{
         multipart::{JsonField, Multipart, Upload},
         Path, Query,
     },
     handler::{Handler, HandlerExt},
-    middleware::{TowerLayerCompatExt, Tracing},
+    middleware::{Compression, RateLimit, Tracing},
     openapi::{self, SecurityRequirement},
     payload::{Form, Json},
     response::Download,
-    response_error::ResponseError,
+    response_error::{RateLimitError, ResponseError},
     route::Router,
     SecurityScheme, Tag, ToParameters, ToSchema,
 };
 use rudi::{Context, Singleton};
 use serde::{Deserialize, Serialize};
-use tower::limit::RateLimitLayer;
-use tower_http::compression::CompressionLayer;
 
 struct App;
 
@@ -31,9 +27,7 @@ impl Hooks for App {
     async fn before_run<H: Handler>(mut cx: Context, router: H) -> (Context, impl Handler) {
         let t = cx.resolve::<Tracing>();
 
-        let router = router
-            .with(CompressionLayer::new().zstd(true).compat())
-            .with(t);
+        let router = router.with(Compression::new()).with(t);
 
         (cx, router)
     }
@@ -97,7 +91,7 @@ impl MyController {
     #[handler(paths = ["/no_arg"], methods = [GET], security = [{}, { MyScheme2: [] }])] // override the global security
     async fn no_arg(&self) {}
 
-    #[handler(methods = [GET, POST, PUT], middleware = add_middlewares, tags = [Hello])]
+    #[handler(methods = [GET, POST, PUT], middleware = add_middlewares, errors = [RateLimitError], tags = [Hello])]
     async fn hello(&self, name: String) -> Result<String, MyError> {
         Ok(format!("hello, {}", name))
     }
@@ -172,13 +166,15 @@ impl MyController {
     }
 }
 
-fn add_middlewares<H: Handler>(_: &mut Context, handler: H) -> impl Handler {
+fn add_middlewares<H: Handler>(cx: &mut Context, handler: H) -> impl Handler {
+    let rate_limit = RateLimit::from_config(&cx.resolve::<RateLimitConfig>());
+
     handler
         .before(|req| async {
             println!("before: {:?}", req);
             Ok(req)
         })
-        .with(RateLimitLayer::new(1, Duration::from_secs(3)).compat())
+        .with(rate_limit)
 }
 
 #[Singleton]
@@ -6,19 +6,33 @@ use http::{HeaderMap, Method, Uri, Version};
 use crate::{
     body::RequestBody,
     media_type::MultiRequestMediaType,
-    openapi::{self, Parameter, Schema},
+    openapi::{self, Parameter, Schema, SecurityRequirement},
     private::{ViaRequest, ViaRequestHead},
     request::{BodyLimit, Head, LocalAddr, OriginalUri, RemoteAddr},
 };
 
+pub type SecuritySchemes = BTreeMap<&'static str, (&'static str, openapi::SecurityScheme)>;
+
 pub trait ApiRequestHead {
     fn parameters(schemas: &mut BTreeMap<String, Schema>) -> Option<Vec<Parameter>>;
+
+    /// The security requirements which the request must satisfy one of,
+    /// the schemes are registered in `security_schemes` by their type names.
+    fn security_requirements(_: &mut SecuritySchemes) -> Option<Vec<SecurityRequirement>> {
+        None
+    }
 }
 
 pub trait ApiRequest<M = ViaRequest> {
     fn parameters(schemas: &mut BTreeMap<String, Schema>) -> Option<Vec<Parameter>>;
 
     fn request_body(schemas: &mut BTreeMap<String, Schema>) -> Option<openapi::RequestBody>;
+
+    /// The security requirements which the request must satisfy one of,
+    /// the schemes are registered in `security_schemes` by their type names.
+    fn security_requirements(_: &mut SecuritySchemes) -> Option<Vec<SecurityRequirement>> {
+        None
+    }
 }
 
 impl<T> ApiRequest<ViaRequestHead> for T
@@ -32,6 +46,12 @@ where
     fn request_body(_: &mut BTreeMap<String, Schema>) -> Option<openapi::RequestBody> {
         None
     }
+
+    fn security_requirements(
+        security_schemes: &mut SecuritySchemes,
+    ) -> Option<Vec<SecurityRequirement>> {
+        T::security_requirements(security_schemes)
+    }
 }
 
 impl ApiRequest for RequestBody {
@@ -114,6 +134,21 @@ macro_rules! optional_parameters {
 
             Some(parameters)
         }
+
+        fn security_requirements(
+            security_schemes: &mut SecuritySchemes,
+        ) -> Option<Vec<SecurityRequirement>> {
+            let mut security_requirements = <$ty>::security_requirements(security_schemes)?;
+
+            // an absent credential is also accepted
+            let anonymous = SecurityRequirement::default();
+
+            if !security_requirements.contains(&anonymous) {
+                security_requirements.push(anonymous);
+            }
+
+            Some(security_requirements)
+        }
     };
 }
 
@@ -138,6 +173,12 @@ where
     fn parameters(schemas: &mut BTreeMap<String, Schema>) -> Option<Vec<Parameter>> {
         T::parameters(schemas)
     }
+
+    fn security_requirements(
+        security_schemes: &mut SecuritySchemes,
+    ) -> Option<Vec<SecurityRequirement>> {
+        T::security_requirements(security_schemes)
+    }
 }
 
 impl<T, E> ApiRequest for Result<T, E>
@@ -151,4 +192,10 @@ where
     fn request_body(schemas: &mut BTreeMap<String, Schema>) -> Option<openapi::RequestBody> {
         T::request_body(schemas)
     }
+
+    fn security_requirements(
+        security_schemes: &mut SecuritySchemes,
+    ) -> Option<Vec<SecurityRequirement>> {
+        T::security_requirements(security_schemes)
+    }
 }
@@ -13,7 +13,81 @@ use hyper::body::{Frame, Incoming};
 
 use crate::error::BoxError;
 
-pub type RequestBody = Limited<Incoming>;
+pub type RequestBody = Limited<IncomingBody>;
+
+/// The body of a request before its [`BodyLimit`](crate::request::BodyLimit) is applied.
+///
+/// It is the [`Incoming`] body of the connection, unless a middleware replaced it,
+/// e.g. to decompress it.
+#[derive(Debug)]
+pub struct IncomingBody(IncomingBodyKind);
+
+#[derive(Debug)]
+enum IncomingBodyKind {
+    Incoming(Incoming),
+    Boxed(UnsyncBoxBody<Bytes, BoxError>),
+}
+
+impl IncomingBody {
+    pub fn new<B>(body: B) -> Self
+    where
+        B: http_body::Body<Data = Bytes> + Send + 'static,
+        B::Error: Into<BoxError>,
+    {
+        Self(IncomingBodyKind::Boxed(
+            body.map_err(Into::into).boxed_unsync(),
+        ))
+    }
+
+    pub fn from_stream<S>(stream: S) -> Self
+    where
+        S: TryStream + Send + 'static,
+        S::Ok: Into<Bytes>,
+        S::Error: Into<BoxError>,
+    {
+        Self::new(StreamBody::new(
+            stream.map_ok(|data| Frame::data(data.into())),
+        ))
+    }
+}
+
+impl From<Incoming> for IncomingBody {
+    fn from(incoming: Incoming) -> Self {
+        Self(IncomingBodyKind::Incoming(incoming))
+    }
+}
+
+impl http_body::Body for IncomingBody {
+    type Data = Bytes;
+    type Error = BoxError;
+
+    #[inline]
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        match &mut self.0 {
+            IncomingBodyKind::Incoming(body) => Pin::new(body).poll_frame(cx).map_err(Into::into),
+            IncomingBodyKind::Boxed(body) => Pin::new(body).poll_frame(cx),
+        }
+    }
+
+    #[inline]
+    fn is_end_stream(&self) -> bool {
+        match &self.0 {
+            IncomingBodyKind::Incoming(body) => body.is_end_stream(),
+            IncomingBodyKind::Boxed(body) => body.is_end_stream(),
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> SizeHint {
+        match &self.0 {
+            IncomingBodyKind::Incoming(body) => body.size_hint(),
+            IncomingBodyKind::Boxed(body) => body.size_hint(),
+        }
+    }
+}
 
 #[derive(Debug)]
 pub struct ResponseBody(UnsyncBoxBody<Bytes, BoxError>);
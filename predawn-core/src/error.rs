@@ -30,6 +30,7 @@ impl Error {
         self.inner.downcast_ref::<T>()
     }
 
+    #[allow(clippy::result_large_err)]
     pub fn downcast<T>(self) -> Result<(Response, T, Box<[&'static str]>), Self>
     where
         T: StdError + 'static,
@@ -58,6 +59,11 @@ impl Error {
         self.response
     }
 
+    /// The response of the error, e.g. for middlewares adding headers to error responses.
+    pub fn response_mut(&mut self) -> &mut Response {
+        &mut self.response
+    }
+
     pub fn wrappers(&self) -> &[&'static str] {
         &self.wrappers
     }
@@ -8,14 +8,17 @@ use http::{
 use http_body_util::Limited;
 use hyper::body::Incoming;
 
-use crate::{body::RequestBody, impl_deref, impl_display};
+use crate::{
+    body::{IncomingBody, RequestBody},
+    impl_deref, impl_display,
+};
 
 pub const DEFAULT_BODY_LIMIT: usize = 2_097_152; // 2 mb
 
 #[derive(Debug)]
 pub struct Request {
     pub head: Head,
-    pub body: Incoming,
+    pub body: IncomingBody,
 }
 
 impl Request {
@@ -48,7 +51,7 @@ impl Request {
                 remote_addr: RemoteAddr(remote_addr),
                 original_uri: OriginalUri(uri),
             },
-            body,
+            body: body.into(),
         }
     }
 
@@ -154,7 +157,7 @@ pub struct OriginalUri(pub Uri);
 impl_deref!(OriginalUri : Uri);
 impl_display!(OriginalUri);
 
-impl From<Request> for http::Request<Incoming> {
+impl From<Request> for http::Request<IncomingBody> {
     fn from(request: Request) -> Self {
         let Request {
             head:
@@ -189,10 +192,10 @@ impl From<Request> for http::Request<Incoming> {
     }
 }
 
-impl TryFrom<http::Request<Incoming>> for Request {
+impl TryFrom<http::Request<IncomingBody>> for Request {
     type Error = ConvertRequestError;
 
-    fn try_from(request: http::Request<Incoming>) -> Result<Self, Self::Error> {
+    fn try_from(request: http::Request<IncomingBody>) -> Result<Self, Self::Error> {
         let (
             Parts {
                 method,
@@ -1,10 +1,10 @@
 use from_attr::{AttrsValue, FromAttr, Map};
-use proc_macro2::{Span, TokenStream};
+use proc_macro2::{Delimiter, Punct, Spacing, Span, TokenStream, TokenTree};
 use quote::{format_ident, quote};
-use quote_use::quote_use;
+use quote_use::{quote_spanned_use, quote_use};
 use syn::{
-    parse_quote, spanned::Spanned, Expr, FnArg, ImplItem, ImplItemFn, ItemImpl, Label, PatType,
-    Path, Receiver, ReturnType, Type,
+    parse_quote, spanned::Spanned, Attribute, Expr, FnArg, ImplItem, ImplItemFn, ItemImpl, Label,
+    LitStr, Meta, PatType, Path, Receiver, ReturnType, Type,
 };
 
 use crate::{
@@ -12,13 +12,33 @@ use crate::{
     util,
 };
 
+pub(crate) struct ControllerAttr {
+    attr: ControllerArgs,
+    external_docs: Option<ExternalDocsAttr>,
+}
+
+impl ControllerAttr {
+    pub(crate) fn from_tokens(tokens: TokenStream) -> syn::Result<Self> {
+        let (tokens, external_docs) = take_external_docs(tokens)?;
+
+        Ok(Self {
+            attr: ControllerArgs::from_tokens(tokens)?,
+            external_docs,
+        })
+    }
+}
+
 #[derive(FromAttr)]
 #[attribute(idents = [controller])]
-pub(crate) struct ControllerAttr {
+struct ControllerArgs {
     paths: Vec<Expr>,
     middleware: Option<Path>,
+    errors: Vec<Type>,
     tags: Vec<Type>,
-    security: Vec<Map<Type, Vec<String>>>,
+    security: Vec<Map<Type, Vec<LitStr>>>,
+    requires: Vec<LitStr>,
+    deprecated: bool,
+    hidden: bool,
 }
 
 #[derive(FromAttr)]
@@ -27,8 +47,109 @@ struct MethodAttr {
     paths: Vec<Expr>,
     methods: Vec<Method>,
     middleware: Option<Path>,
+    errors: Vec<Type>,
     tags: Vec<Type>,
-    security: Vec<Map<Type, Vec<String>>>,
+    security: Vec<Map<Type, Vec<LitStr>>>,
+    requires: Vec<LitStr>,
+    operation_id: Option<String>,
+    deprecated: bool,
+    hidden: bool,
+}
+
+#[derive(FromAttr)]
+#[attribute(idents = [external_docs])]
+struct ExternalDocsAttr {
+    url: String,
+    description: Option<String>,
+}
+
+impl ExternalDocsAttr {
+    fn generate(&self) -> TokenStream {
+        let ExternalDocsAttr { url, description } = self;
+
+        let url = util::generate_lit_str(url);
+
+        let description = match description {
+            Some(description) => util::generate_optional_lit_str(description)
+                .unwrap_or_else(|| quote!(::core::option::Option::None)),
+            None => quote!(::core::option::Option::None),
+        };
+
+        quote_use! {
+            # use core::option::Option::Some;
+            # use core::default::Default;
+            # use predawn::openapi::ExternalDocumentation;
+
+            operation.external_docs = Some(ExternalDocumentation {
+                description: #description,
+                url: #url,
+                extensions: Default::default(),
+            });
+        }
+    }
+}
+
+/// `from_attr` only parses `name = value` arguments,
+/// so `external_docs(url = .., description = ..)` is taken out of the arguments and parsed on its own.
+fn take_external_docs(tokens: TokenStream) -> syn::Result<(TokenStream, Option<ExternalDocsAttr>)> {
+    let mut rest = Vec::new();
+    let mut external_docs = None;
+
+    let mut arguments = vec![Vec::new()];
+
+    for token in tokens {
+        match &token {
+            TokenTree::Punct(punct) if punct.as_char() == ',' => arguments.push(Vec::new()),
+            _ => arguments.last_mut().unwrap().push(token),
+        }
+    }
+
+    for argument in arguments {
+        match argument.as_slice() {
+            [TokenTree::Ident(ident), TokenTree::Group(group)]
+                if ident == "external_docs" && group.delimiter() == Delimiter::Parenthesis =>
+            {
+                if external_docs.is_some() {
+                    return Err(syn::Error::new(
+                        ident.span(),
+                        "duplicate `external_docs` argument",
+                    ));
+                }
+
+                external_docs = Some(ExternalDocsAttr::from_tokens(group.stream())?);
+            }
+            [] => {}
+            _ => {
+                rest.extend(argument);
+                rest.push(TokenTree::Punct(Punct::new(',', Spacing::Alone)));
+            }
+        }
+    }
+
+    Ok((rest.into_iter().collect(), external_docs))
+}
+
+fn take_handler_external_docs(attrs: &mut [Attribute]) -> syn::Result<Option<ExternalDocsAttr>> {
+    let mut external_docs = None;
+
+    for attr in attrs {
+        if !attr.path().is_ident("handler") {
+            continue;
+        }
+
+        let Meta::List(meta_list) = &mut attr.meta else {
+            continue;
+        };
+
+        let (tokens, docs) = take_external_docs(meta_list.tokens.clone())?;
+        meta_list.tokens = tokens;
+
+        if docs.is_some() {
+            external_docs = docs;
+        }
+    }
+
+    Ok(external_docs)
 }
 
 fn default_paths() -> Vec<Expr> {
@@ -47,16 +168,34 @@ pub(crate) fn generate(
     }
 
     let ControllerAttr {
-        paths,
+        attr:
+            ControllerArgs {
+                paths,
+                middleware,
+                errors,
+                tags,
+                security,
+                requires,
+                deprecated,
+                hidden,
+            },
+        external_docs,
+    } = controller_attr;
+
+    let controller = ControllerMetadata {
+        paths: if !paths.is_empty() {
+            paths
+        } else {
+            default_paths()
+        },
         middleware,
+        errors,
         tags,
         security,
-    } = controller_attr;
-
-    let paths = if !paths.is_empty() {
-        paths
-    } else {
-        default_paths()
+        requires,
+        deprecated,
+        hidden,
+        external_docs,
     };
 
     let self_ty = &item_impl.self_ty;
@@ -70,6 +209,14 @@ pub(crate) fn generate(
             _ => return,
         };
 
+        let external_docs = match take_handler_external_docs(&mut f.attrs) {
+            Ok(external_docs) => external_docs,
+            Err(e) => {
+                errors.push(e);
+                return;
+            }
+        };
+
         let method_attr = match MethodAttr::remove_attributes(&mut f.attrs) {
             Ok(Some(AttrsValue {
                 value: method_attr, ..
@@ -81,15 +228,7 @@ pub(crate) fn generate(
             }
         };
 
-        match generate_single_fn_impl(
-            &paths,
-            middleware.as_ref(),
-            &tags,
-            &security,
-            self_ty,
-            f,
-            method_attr,
-        ) {
+        match generate_single_fn_impl(&controller, self_ty, f, method_attr, external_docs) {
             Ok(insert_routes_impl) => insert_routes_impls.push(insert_routes_impl),
             Err(e) => errors.push(e),
         }
@@ -156,15 +295,56 @@ pub(crate) fn generate(
     Ok(expand)
 }
 
-fn generate_single_fn_impl<'a>(
-    controller_paths: &'a [Expr],
-    controller_middeleware: Option<&'a Path>,
-    controller_tags: &'a [Type],
-    controller_security: &'a [Map<Type, Vec<String>>],
-    self_ty: &'a Type,
-    f: &'a ImplItemFn,
+/// Fails to compile if `scope` is not declared by the security scheme `ty`.
+fn generate_scope_check(ty: &Type, scope: &LitStr) -> TokenStream {
+    let msg = format!(
+        "scope `{}` is not declared by the security scheme `{}`",
+        scope.value(),
+        quote!(#ty).to_string().replace(' ', ""),
+    );
+
+    quote_spanned_use! { scope.span() =>
+        # use predawn::SecurityScheme;
+        # use predawn::openapi::is_scope_declared;
+
+        const _: () = if !is_scope_declared(<#ty as SecurityScheme>::SCOPES, #scope) {
+            panic!(#msg)
+        };
+    }
+}
+
+struct ControllerMetadata {
+    paths: Vec<Expr>,
+    middleware: Option<Path>,
+    /// The errors of the middlewares, which are documented as responses.
+    errors: Vec<Type>,
+    tags: Vec<Type>,
+    security: Vec<Map<Type, Vec<LitStr>>>,
+    requires: Vec<LitStr>,
+    deprecated: bool,
+    hidden: bool,
+    external_docs: Option<ExternalDocsAttr>,
+}
+
+fn generate_single_fn_impl(
+    controller: &ControllerMetadata,
+    self_ty: &Type,
+    f: &ImplItemFn,
     method_attr: MethodAttr,
+    method_external_docs: Option<ExternalDocsAttr>,
 ) -> syn::Result<TokenStream> {
+    let ControllerMetadata {
+        paths: controller_paths,
+        middleware: controller_middeleware,
+        errors: controller_errors,
+        tags: controller_tags,
+        security: controller_security,
+        requires: controller_requires,
+        deprecated: controller_deprecated,
+        hidden: controller_hidden,
+        external_docs: controller_external_docs,
+    } = controller;
+
     if f.sig.asyncness.is_none() {
         return Err(syn::Error::new(f.sig.span(), "the method must be async"));
     }
@@ -173,10 +353,18 @@ fn generate_single_fn_impl<'a>(
         paths,
         methods,
         middleware: method_middleware,
+        errors: method_errors,
         tags: method_tags,
         security: method_security,
+        requires: method_requires,
+        operation_id,
+        deprecated: method_deprecated,
+        hidden: method_hidden,
     } = method_attr;
 
+    let hidden = *controller_hidden || method_hidden;
+    let has_method_security = !method_security.is_empty();
+
     let method_paths = if !paths.is_empty() {
         paths
     } else {
@@ -189,12 +377,37 @@ fn generate_single_fn_impl<'a>(
         ENUM_METHODS.to_vec()
     };
 
+    // every endpoint of the method gets a distinct `operationId`, the generated one is
+    // suffixed with the method and the index of the path when there are several of them
+    let multiple_methods = methods.len() > 1;
+    let multiple_paths = controller_paths.len() * method_paths.len() > 1;
+
+    if operation_id.is_some() && (multiple_methods || multiple_paths) {
+        return Err(syn::Error::new(
+            f.sig.ident.span(),
+            "`operation_id` must be unique, it can only be set on a handler with exactly one \
+             method and one path",
+        ));
+    }
+
     let security = if !method_security.is_empty() {
         &method_security
     } else {
         controller_security
     };
 
+    // the permissions of the controller and of the method are all required
+    let mut requires: Vec<&LitStr> = Vec::new();
+
+    controller_requires
+        .iter()
+        .chain(method_requires.iter())
+        .for_each(|permission| {
+            if !requires.iter().any(|p| p.value() == permission.value()) {
+                requires.push(permission);
+            }
+        });
+
     let fn_name = &f.sig.ident;
 
     let mut args = f.sig.inputs.iter();
@@ -220,6 +433,7 @@ fn generate_single_fn_impl<'a>(
 
     let mut heads_from_request_head = Vec::new();
     let mut heads_parameters = Vec::new();
+    let mut heads_security_requirements = Vec::new();
     let mut heads_error_responses = Vec::new();
 
     args.filter_map(|arg| match arg {
@@ -248,6 +462,15 @@ fn generate_single_fn_impl<'a>(
             }
         };
 
+        let security_requirements = quote_use! {
+            # use predawn::api_request::ApiRequestHead;
+            # use predawn::openapi::merge_security_requirements;
+
+            if let Some(new) = <#ty as ApiRequestHead>::security_requirements(security_schemes) {
+                security_requirements = Some(merge_security_requirements(security_requirements, new));
+            }
+        };
+
         let error_responses = quote_use! {
             # use predawn::from_request::FromRequestHead;
             # use predawn::response_error::ResponseError;
@@ -261,11 +484,13 @@ fn generate_single_fn_impl<'a>(
 
         heads_from_request_head.push(from_request_head);
         heads_parameters.push(parameters);
+        heads_security_requirements.push(security_requirements);
         heads_error_responses.push(error_responses);
     });
 
     let last_from_request;
     let last_parameters;
+    let last_security_requirements;
     let last_request_body;
     let last_error_responses;
 
@@ -296,6 +521,15 @@ fn generate_single_fn_impl<'a>(
             operation.request_body = transform_request_body(<#ty as ApiRequest<_>>::request_body(schemas));
         };
 
+        last_security_requirements = quote_use! {
+            # use predawn::api_request::ApiRequest;
+            # use predawn::openapi::merge_security_requirements;
+
+            if let Some(new) = <#ty as ApiRequest<_>>::security_requirements(security_schemes) {
+                security_requirements = Some(merge_security_requirements(security_requirements, new));
+            }
+        };
+
         last_error_responses = quote_use! {
             # use predawn::from_request::FromRequest;
             # use predawn::response_error::ResponseError;
@@ -309,6 +543,7 @@ fn generate_single_fn_impl<'a>(
     } else {
         last_from_request = TokenStream::new();
         last_parameters = TokenStream::new();
+        last_security_requirements = TokenStream::new();
         last_request_body = TokenStream::new();
         last_error_responses = TokenStream::new();
     }
@@ -343,7 +578,7 @@ fn generate_single_fn_impl<'a>(
         #last_from_request
     };
 
-    let add_controller_middleware = controller_middeleware.map(|middleware| {
+    let add_controller_middleware = controller_middeleware.as_ref().map(|middleware| {
         quote_use! {
             # use predawn::handler::assert_handler;
 
@@ -375,6 +610,35 @@ fn generate_single_fn_impl<'a>(
         }
     });
 
+    let add_deprecated = (*controller_deprecated || method_deprecated).then(|| {
+        quote! {
+            operation.deprecated = true;
+        }
+    });
+
+    let add_external_docs = method_external_docs
+        .as_ref()
+        .or(controller_external_docs.as_ref())
+        .map(ExternalDocsAttr::generate);
+
+    let add_operation_id = match &operation_id {
+        Some(operation_id) => {
+            let operation_id = util::generate_lit_str(operation_id);
+
+            quote! {
+                operation.operation_id = ::core::option::Option::Some(#operation_id);
+            }
+        }
+        None => quote_use! {
+            # use core::stringify;
+            # use std::format;
+            # use core::option::Option::Some;
+            # use std::any::type_name;
+
+            operation.operation_id = Some(format!("{}::{}", type_name::<#self_ty>(), stringify!(#fn_name)));
+        },
+    };
+
     let add_tags = if controller_tags.is_empty() && method_tags.is_empty() {
         TokenStream::new()
     } else {
@@ -413,11 +677,19 @@ fn generate_single_fn_impl<'a>(
     } else {
         let push_security = security.iter().map(|Map(map)| {
             let insert_security_requirement = map.iter().map(|(ty, scopes)| {
+                // the required permissions are added to the scopes of the scheme
+                let check_scopes = scopes
+                    .iter()
+                    .chain(requires.iter().copied())
+                    .map(|scope| generate_scope_check(ty, scope));
+
                 quote_use! {
                     # use core::any::type_name;
                     # use std::string::ToString;
                     # use predawn::SecurityScheme;
 
+                    #(#check_scopes)*
+
                     let scheme_type_name = type_name::<#ty>();
                     let scheme_name = <#ty as SecurityScheme>::NAME;
 
@@ -456,6 +728,85 @@ fn generate_single_fn_impl<'a>(
         }
     };
 
+    let (resolve_authorizer, authorize, add_required_scopes, forbidden_responses) = if requires
+        .is_empty()
+    {
+        Default::default()
+    } else {
+        let handler = format!(
+            "{}::{}",
+            quote!(#self_ty).to_string().replace(' ', ""),
+            fn_name,
+        );
+
+        let missing_authorizer = format!(
+            "`{handler}` requires permissions, but no `DynAuthorizer` is registered in the context"
+        );
+
+        let resolve_authorizer = quote_use! {
+            # use core::option::Option::{Some, None};
+            # use predawn::authorization::DynAuthorizer;
+
+            let authorizer = match cx.resolve_option::<DynAuthorizer>() {
+                Some(authorizer) => authorizer,
+                None => panic!(#missing_authorizer),
+            };
+        };
+
+        let authorize = quote_use! {
+            # use core::convert::From;
+            # use core::result::Result::Err;
+            # use predawn::authorization::Authorizer;
+            # use predawn::error::Error;
+            # use predawn::response_error::ForbiddenError;
+
+            const PERMISSIONS: &[&str] = &[#(#requires),*];
+
+            if !Authorizer::authorize(&authorizer, &head, PERMISSIONS).await {
+                return Err(Error::from(ForbiddenError { permissions: PERMISSIONS }));
+            }
+        };
+
+        let add_required_scopes = quote_use! {
+            # use predawn::openapi::add_required_scopes;
+
+            add_required_scopes(#handler, &mut operation.security, security_schemes, &[#(#requires),*]);
+        };
+
+        let forbidden_responses = quote_use! {
+            # use predawn::response_error::{ForbiddenError, ResponseError};
+            # use predawn::openapi::merge_responses;
+
+            merge_responses(&mut responses, <ForbiddenError as ResponseError>::responses(schemas));
+        };
+
+        (
+            resolve_authorizer,
+            authorize,
+            add_required_scopes,
+            forbidden_responses,
+        )
+    };
+
+    let middleware_error_responses =
+        controller_errors
+            .iter()
+            .chain(method_errors.iter())
+            .map(|ty| {
+                quote_use! {
+                    # use predawn::response_error::ResponseError;
+                    # use predawn::openapi::merge_responses;
+
+                    merge_responses(&mut responses, <#ty as ResponseError>::responses(schemas));
+                }
+            });
+
+    let clone_authorizer = (!requires.is_empty()).then(|| {
+        quote! {
+            let authorizer = ::std::clone::Clone::clone(&authorizer);
+        }
+    });
+
     let create_handler = quote_use! {
         # use std::sync::Arc;
         # use predawn::handler::{DynHandler, handler_fn};
@@ -463,13 +814,18 @@ fn generate_single_fn_impl<'a>(
         let #fn_name = {
             let this = this.clone();
 
+            #resolve_authorizer
+
             let handler = handler_fn(move |req| {
                 let this = this.clone();
+                #clone_authorizer
 
                 async move {
                     #[allow(unused_variables)]
                     let (head, body) = req.split();
 
+                    #authorize
+
                     #extract_from_request
 
                     let response = this.#fn_name(#(#arg_idents,)*).await;
@@ -485,10 +841,28 @@ fn generate_single_fn_impl<'a>(
         };
     };
 
+    // the security of the method overrides the one of the extractors,
+    // which overrides the one of the controller
+    let apply_security_requirements = if has_method_security {
+        TokenStream::new()
+    } else {
+        quote_use! {
+            # use core::option::Option::{self, Some, None};
+            # use std::vec::Vec;
+            # use predawn::openapi::SecurityRequirement;
+
+            let mut security_requirements: Option<Vec<SecurityRequirement>> = None;
+
+            #(#heads_security_requirements)*
+            #last_security_requirements
+
+            if let Some(security_requirements) = security_requirements {
+                operation.security = Some(security_requirements);
+            }
+        }
+    };
+
     let create_operation = quote_use! {
-        # use core::stringify;
-        # use std::format;
-        # use std::any::type_name;
         # use std::collections::BTreeMap;
         # use predawn::openapi::Operation;
         # use predawn::openapi::transform_responses;
@@ -497,20 +871,28 @@ fn generate_single_fn_impl<'a>(
 
         #add_summary
         #add_description
+        #add_deprecated
+        #add_external_docs
 
         #add_tags
 
         #add_security
 
-        operation.operation_id = Some(format!("{}::{}", type_name::<#self_ty>(), stringify!(#fn_name)));
+        #add_operation_id
 
         #last_request_body
 
         #(#heads_parameters)*
         #last_parameters
 
+        #apply_security_requirements
+        #add_required_scopes
+
         let mut responses = BTreeMap::new();
 
+        #forbidden_responses
+        #(#middleware_error_responses)*
+
         #(#heads_error_responses)*
         #last_error_responses
         #return_error_responses
@@ -521,41 +903,92 @@ fn generate_single_fn_impl<'a>(
 
     let mut insert_fn_into_multi_path = Vec::new();
 
-    controller_paths.iter().for_each(|controller_path| {
-        method_paths.iter().for_each(|method_path| {
-            let extract_single_path_map = quote_use! {
-                # use core::convert::AsRef;
-                # use std::clone::Clone;
-                # use predawn::normalized_path::NormalizedPath;
-
-                let path = NormalizedPath::join(
-                    NormalizedPath::new(AsRef::<str>::as_ref(#controller_path)),
-                    NormalizedPath::new(AsRef::<str>::as_ref(#method_path)),
-                );
-
-                let handlers = route_table.entry(Clone::clone(&path)).or_default();
-                let operations = paths.entry(Clone::clone(&path)).or_default();
-            };
-
-            let insert_fn_into_multi_method = methods.iter().map(|method| {
-                let uppercase_method = method.as_uppercase_ident();
-
-                quote_use! {
-                    # use predawn::__internal::http::Method;
-
-                    handlers.push((Method::#uppercase_method, #fn_name.clone()));
-                    operations.push((Method::#uppercase_method, operation.clone()));
-                }
-            });
-
-            let insert_fn_into_single_path = quote! {
-                #extract_single_path_map
-                #(#insert_fn_into_multi_method)*
-            };
+    controller_paths
+        .iter()
+        .enumerate()
+        .for_each(|(controller_path_idx, controller_path)| {
+            method_paths
+                .iter()
+                .enumerate()
+                .for_each(|(method_path_idx, method_path)| {
+                    let path_idx = controller_path_idx * method_paths.len() + method_path_idx;
+
+                    let extract_single_path_map = quote_use! {
+                        # use core::convert::AsRef;
+                        # use std::clone::Clone;
+                        # use predawn::normalized_path::NormalizedPath;
+
+                        let path = NormalizedPath::join(
+                            NormalizedPath::new(AsRef::<str>::as_ref(#controller_path)),
+                            NormalizedPath::new(AsRef::<str>::as_ref(#method_path)),
+                        );
 
-            insert_fn_into_multi_path.push(insert_fn_into_single_path);
+                        let handlers = route_table.entry(Clone::clone(&path)).or_default();
+                    };
+
+                    let extract_single_path_map = if hidden {
+                        extract_single_path_map
+                    } else {
+                        quote_use! {
+                            # use std::clone::Clone;
+
+                            #extract_single_path_map
+                            let operations = paths.entry(Clone::clone(&path)).or_default();
+                        }
+                    };
+
+                    let insert_fn_into_multi_method = methods.iter().map(|method| {
+                        let uppercase_method = method.as_uppercase_ident();
+
+                        let mut suffix = String::new();
+
+                        if multiple_methods {
+                            suffix.push('_');
+                            suffix.push_str(&method.to_string().to_lowercase());
+                        }
+
+                        if multiple_paths {
+                            suffix.push_str(&format!("_{path_idx}"));
+                        }
+
+                        let push_operation = if hidden {
+                            TokenStream::new()
+                        } else if suffix.is_empty() {
+                            quote_use! {
+                                # use predawn::__internal::http::Method;
+
+                                operations.push((Method::#uppercase_method, operation.clone()));
+                            }
+                        } else {
+                            quote_use! {
+                                # use predawn::__internal::http::Method;
+
+                                {
+                                    let mut operation = operation.clone();
+                                    operation.operation_id = operation.operation_id.map(|id| id + #suffix);
+                                    operations.push((Method::#uppercase_method, operation));
+                                }
+                            }
+                        };
+
+                        quote_use! {
+                            # use predawn::__internal::http::Method;
+
+                            handlers.push((Method::#uppercase_method, #fn_name.clone()));
+                            #push_operation
+                        }
+                    });
+
+                    let insert_fn_into_single_path = quote! {
+                        #extract_single_path_map
+                        #(#insert_fn_into_multi_method)*
+                    };
+
+                    insert_fn_into_multi_path.push(insert_fn_into_single_path);
+                });
         });
-    });
+
+    let create_operation = (!hidden).then_some(create_operation);
 
     let label: Label = syn::parse_str(&format!("'{}:", fn_name))?;
 
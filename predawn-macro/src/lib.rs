@@ -4,6 +4,7 @@ mod multi_request_media_type;
 mod multi_response;
 mod multi_response_media_type;
 mod multipart;
+mod schema_attr;
 mod security_scheme;
 mod serde_attr;
 mod single_response;
@@ -11,11 +12,12 @@ mod tag;
 mod to_parameters;
 mod to_schema;
 mod util;
+mod validate;
 
-use from_attr::FromAttr;
 use proc_macro::TokenStream;
 use syn::{parse_macro_input, DeriveInput, ItemImpl};
 
+#[doc = include_str!("docs/controller.md")]
 #[proc_macro_attribute]
 pub fn controller(attr: TokenStream, item: TokenStream) -> TokenStream {
     let attr = match controller::ControllerAttr::from_tokens(attr.into()) {
@@ -38,6 +40,16 @@ pub fn to_schema(input: TokenStream) -> TokenStream {
         .into()
 }
 
+#[doc = include_str!("docs/validate.md")]
+#[proc_macro_derive(Validate, attributes(schema, validate))]
+pub fn validate(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    validate::generate(input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
 #[proc_macro_derive(ToParameters, attributes(parameters))]
 pub fn to_parameters(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
@@ -108,7 +120,7 @@ pub fn tag(input: TokenStream) -> TokenStream {
 }
 
 #[doc = include_str!("docs/security_scheme.md")]
-#[proc_macro_derive(SecurityScheme, attributes(api_key, http))]
+#[proc_macro_derive(SecurityScheme, attributes(api_key, http, oauth2, openid_connect))]
 pub fn security_scheme(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
 
@@ -3,7 +3,10 @@ use quote::quote;
 use quote_use::quote_use;
 use syn::{DeriveInput, Field, Ident};
 
-use crate::{serde_attr::SerdeAttr, util};
+use crate::{
+    serde_attr::{Direction, SerdeAttr},
+    util,
+};
 
 pub(crate) fn generate(input: DeriveInput) -> syn::Result<TokenStream> {
     let DeriveInput {
@@ -16,6 +19,8 @@ pub(crate) fn generate(input: DeriveInput) -> syn::Result<TokenStream> {
 
     let named = util::extract_named_struct_fields(data, "Multipart")?;
 
+    let container = SerdeAttr::new(&attrs)?;
+
     let mut struct_field_idents = Vec::new();
     let mut define_vars = Vec::new();
     let mut parse_fields = Vec::new();
@@ -24,7 +29,7 @@ pub(crate) fn generate(input: DeriveInput) -> syn::Result<TokenStream> {
 
     named
         .into_iter()
-        .for_each(|field| match generate_single_field(field) {
+        .for_each(|field| match generate_single_field(field, &container) {
             Ok((struct_field, define_var, parse_field, extract_var)) => {
                 struct_field_idents.push(struct_field);
                 define_vars.push(define_var);
@@ -133,16 +138,21 @@ pub(crate) fn generate(input: DeriveInput) -> syn::Result<TokenStream> {
 
 fn generate_single_field(
     field: Field,
+    container: &SerdeAttr,
 ) -> syn::Result<(Ident, TokenStream, TokenStream, TokenStream)> {
     let Field {
         attrs, ident, ty, ..
     } = field;
 
-    let SerdeAttr { rename } = SerdeAttr::new(&attrs)?;
+    let serde_attr = SerdeAttr::new(&attrs)?;
 
     let struct_field_ident = ident.expect("unreachable: named field must have an identifier");
 
-    let multipart_field = rename.unwrap_or_else(|| struct_field_ident.to_string());
+    let multipart_field = serde_attr.field_name(
+        &struct_field_ident,
+        &container.rename_all,
+        Direction::Deserialize,
+    );
 
     let define_var = quote_use! {
         # use core::default::Default;
@@ -0,0 +1,177 @@
+use from_attr::{AttrsValue, FromAttr};
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use quote_use::quote_use;
+use syn::{Attribute, Expr};
+
+#[derive(FromAttr, Default)]
+#[attribute(idents = [schema])]
+pub(crate) struct SchemaAttr {
+    pub(crate) example: Option<Expr>,
+    pub(crate) default: Option<Expr>,
+    pub(crate) deprecated: bool,
+    pub(crate) format: Option<String>,
+    pub(crate) min_length: Option<usize>,
+    pub(crate) max_length: Option<usize>,
+    pub(crate) minimum: Option<Expr>,
+    pub(crate) maximum: Option<Expr>,
+    pub(crate) pattern: Option<String>,
+    pub(crate) read_only: bool,
+    pub(crate) write_only: bool,
+    pub(crate) nullable: bool,
+}
+
+impl SchemaAttr {
+    pub(crate) fn new(attrs: &[Attribute]) -> syn::Result<Self> {
+        match Self::from_attributes(attrs) {
+            Ok(Some(AttrsValue { attrs, value })) => {
+                // the pattern is compiled by `regex` when validating, reject it before that
+                if let Some(pattern) = &value.pattern {
+                    if let Err(e) = regex_syntax::Parser::new().parse(pattern) {
+                        return Err(syn::Error::new_spanned(
+                            attrs[0],
+                            format!("invalid `pattern`: {e}"),
+                        ));
+                    }
+                }
+
+                Ok(value)
+            }
+            Ok(None) => Ok(Default::default()),
+            Err(AttrsValue { value: e, .. }) => Err(e),
+        }
+    }
+
+    /// Generates the statements which write the attribute into `schema`,
+    /// returns `None` if there is nothing to write.
+    pub(crate) fn generate(self, span: Span) -> syn::Result<Option<TokenStream>> {
+        let constraints = self.generate_constraints();
+
+        let SchemaAttr {
+            example,
+            default,
+            deprecated,
+            min_length,
+            max_length,
+            read_only,
+            write_only,
+            nullable,
+            ..
+        } = self;
+
+        if read_only && write_only {
+            return Err(syn::Error::new(
+                span,
+                "`read_only` and `write_only` can not be used together",
+            ));
+        }
+
+        if let (Some(min_length), Some(max_length)) = (min_length, max_length) {
+            if min_length > max_length {
+                return Err(syn::Error::new(
+                    span,
+                    "`min_length` must be less than or equal to `max_length`",
+                ));
+            }
+        }
+
+        let mut statements = Vec::new();
+
+        let to_value = |expr: Expr, what: &str| {
+            let msg = format!("failed to serialize the {what} of the schema");
+
+            quote! {
+                ::core::option::Option::Some(
+                    ::predawn::__internal::serde_json::to_value(#expr).expect(#msg)
+                )
+            }
+        };
+
+        if let Some(example) = example {
+            let example = to_value(example, "example");
+            statements.push(quote! { schema.schema_data.example = #example; });
+        }
+
+        if let Some(default) = default {
+            let default = to_value(default, "default value");
+            statements.push(quote! { schema.schema_data.default = #default; });
+        }
+
+        if deprecated {
+            statements.push(quote! { schema.schema_data.deprecated = true; });
+        }
+
+        if read_only {
+            statements.push(quote! { schema.schema_data.read_only = true; });
+        }
+
+        if write_only {
+            statements.push(quote! { schema.schema_data.write_only = true; });
+        }
+
+        if nullable {
+            statements.push(quote! { schema.schema_data.nullable = true; });
+        }
+
+        if let Some(constraints) = constraints {
+            statements.push(quote! { #constraints.apply(&mut schema); });
+        }
+
+        if statements.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(quote! { #(#statements)* }))
+    }
+
+    /// Generates a `Constraints` expression from the validation keywords,
+    /// which are both documented and enforced, returns `None` if there is none.
+    pub(crate) fn generate_constraints(&self) -> Option<TokenStream> {
+        let SchemaAttr {
+            format,
+            min_length,
+            max_length,
+            minimum,
+            maximum,
+            pattern,
+            ..
+        } = self;
+
+        if format.is_none()
+            && pattern.is_none()
+            && min_length.is_none()
+            && max_length.is_none()
+            && minimum.is_none()
+            && maximum.is_none()
+        {
+            return None;
+        }
+
+        let some = |value: Option<TokenStream>| match value {
+            Some(value) => quote!(::core::option::Option::Some(#value)),
+            None => quote!(::core::option::Option::None),
+        };
+
+        let format = some(format.as_ref().map(|format| quote!(#format)));
+        let pattern = some(pattern.as_ref().map(|pattern| quote!(#pattern)));
+        let min_length = some(min_length.map(|min_length| quote!(#min_length)));
+        let max_length = some(max_length.map(|max_length| quote!(#max_length)));
+        let minimum = some(minimum.as_ref().map(|minimum| quote!((#minimum) as f64)));
+        let maximum = some(maximum.as_ref().map(|maximum| quote!((#maximum) as f64)));
+
+        let expand = quote_use! {
+            # use predawn::openapi::Constraints;
+
+            Constraints {
+                format: #format,
+                pattern: #pattern,
+                min_length: #min_length,
+                max_length: #max_length,
+                minimum: #minimum,
+                maximum: #maximum,
+            }
+        };
+
+        Some(expand)
+    }
+}
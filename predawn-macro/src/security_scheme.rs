@@ -1,9 +1,12 @@
-use from_attr::{AttrsValue, FromAttr, FromIdent};
+use from_attr::{AttributeIdent, AttrsValue, FromAttr, FromIdent, ParseMeta};
 use http::HeaderName;
-use proc_macro2::TokenStream;
+use proc_macro2::{Span, TokenStream};
 use quote::quote;
 use quote_use::quote_use;
-use syn::{parse_quote, spanned::Spanned, Attribute, DeriveInput, Ident, LitStr};
+use syn::{
+    ext::IdentExt, meta::ParseNestedMeta, parenthesized, parse_quote, spanned::Spanned, Attribute,
+    DeriveInput, Ident, LitStr, Token,
+};
 
 use crate::util;
 
@@ -66,6 +69,176 @@ impl AuthScheme {
     }
 }
 
+struct OAuth2Attr {
+    rename: Option<String>,
+    flows: Vec<(FlowKind, FlowAttr)>,
+}
+
+#[derive(Default)]
+struct OAuth2Parser {
+    rename: Option<LitStr>,
+    flows: Vec<(FlowKind, FlowAttr)>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum FlowKind {
+    Implicit,
+    Password,
+    ClientCredentials,
+    AuthorizationCode,
+}
+
+impl FlowKind {
+    const ALL: [(&'static str, FlowKind); 4] = [
+        ("implicit", FlowKind::Implicit),
+        ("password", FlowKind::Password),
+        ("client_credentials", FlowKind::ClientCredentials),
+        ("authorization_code", FlowKind::AuthorizationCode),
+    ];
+
+    fn needs_authorization_url(self) -> bool {
+        matches!(self, FlowKind::Implicit | FlowKind::AuthorizationCode)
+    }
+
+    fn needs_token_url(self) -> bool {
+        !matches!(self, FlowKind::Implicit)
+    }
+}
+
+#[derive(Default)]
+struct FlowAttr {
+    authorization_url: Option<LitStr>,
+    token_url: Option<LitStr>,
+    refresh_url: Option<LitStr>,
+    scopes: Vec<(LitStr, LitStr)>,
+}
+
+impl AttributeIdent for OAuth2Attr {
+    const IDENTS: &'static [&'static str] = &["oauth2"];
+}
+
+impl FromAttr for OAuth2Attr {
+    type Parser = OAuth2Parser;
+
+    fn from_parser(parser: Self::Parser, spans: &[Span]) -> syn::Result<Self> {
+        let OAuth2Parser { rename, flows } = parser;
+
+        if flows.is_empty() {
+            let span = spans.first().copied().unwrap_or_else(Span::call_site);
+            return Err(syn::Error::new(span, "missing `flows(..)` argument"));
+        }
+
+        Ok(Self {
+            rename: rename.map(|rename| rename.value()),
+            flows,
+        })
+    }
+}
+
+impl ParseMeta for OAuth2Parser {
+    fn is_unit() -> bool {
+        false
+    }
+
+    fn parse_meta(&mut self, meta: ParseNestedMeta) -> syn::Result<()> {
+        if meta.path.is_ident("rename") {
+            self.rename = Some(meta.value()?.parse()?);
+            return Ok(());
+        }
+
+        if meta.path.is_ident("flows") {
+            return meta.parse_nested_meta(|meta| {
+                let Some((_, kind)) = FlowKind::ALL
+                    .into_iter()
+                    .find(|(ident, _)| meta.path.is_ident(ident))
+                else {
+                    return Err(meta.error(
+                        "expected `implicit`, `password`, `client_credentials` or `authorization_code`",
+                    ));
+                };
+
+                if self.flows.iter().any(|(k, _)| *k == kind) {
+                    return Err(meta.error("duplicate flow"));
+                }
+
+                let span = meta.path.span();
+                let flow = parse_flow(meta, kind, span)?;
+                self.flows.push((kind, flow));
+                Ok(())
+            });
+        }
+
+        Err(meta.error("expected `rename` or `flows`"))
+    }
+}
+
+fn parse_flow(meta: ParseNestedMeta, kind: FlowKind, span: Span) -> syn::Result<FlowAttr> {
+    let mut flow = FlowAttr::default();
+
+    meta.parse_nested_meta(|meta| {
+        if meta.path.is_ident("authorization_url") && kind.needs_authorization_url() {
+            flow.authorization_url = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("token_url") && kind.needs_token_url() {
+            flow.token_url = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("refresh_url") {
+            flow.refresh_url = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("scopes") {
+            // the names of scopes are either identifiers or string literals,
+            // because scopes such as `read:user` are not valid identifiers
+            let content;
+            parenthesized!(content in meta.input);
+
+            while !content.is_empty() {
+                let name = if content.peek(LitStr) {
+                    content.parse::<LitStr>()?
+                } else {
+                    let ident: Ident = content.call(IdentExt::parse_any)?;
+                    LitStr::new(&ident.to_string(), ident.span())
+                };
+
+                if flow.scopes.iter().any(|(n, _)| n.value() == name.value()) {
+                    return Err(syn::Error::new(name.span(), "duplicate scope"));
+                }
+
+                content.parse::<Token![=]>()?;
+                let description = content.parse::<LitStr>()?;
+
+                flow.scopes.push((name, description));
+
+                if content.is_empty() {
+                    break;
+                }
+
+                content.parse::<Token![,]>()?;
+            }
+        } else {
+            return Err(meta.error("unexpected argument for this flow"));
+        }
+
+        Ok(())
+    })?;
+
+    if kind.needs_authorization_url() && flow.authorization_url.is_none() {
+        return Err(syn::Error::new(
+            span,
+            "missing `authorization_url` argument",
+        ));
+    }
+
+    if kind.needs_token_url() && flow.token_url.is_none() {
+        return Err(syn::Error::new(span, "missing `token_url` argument"));
+    }
+
+    Ok(flow)
+}
+
+#[derive(FromAttr)]
+#[attribute(idents = [openid_connect])]
+struct OpenIdConnectAttr {
+    rename: Option<String>,
+    url: String,
+}
+
 pub(crate) fn generate(input: DeriveInput) -> syn::Result<TokenStream> {
     let DeriveInput {
         attrs: ty_attrs,
@@ -107,11 +280,13 @@ pub(crate) fn generate(input: DeriveInput) -> syn::Result<TokenStream> {
 
     expand_scheme!(ApiKeyAttr, generate_api_key);
     expand_scheme!(HttpAttr, generate_http);
+    expand_scheme!(OAuth2Attr, generate_oauth2);
+    expand_scheme!(OpenIdConnectAttr, generate_openid_connect);
 
-    if path_and_expand.is_none() {
+    if path_and_expand.is_none() && errors.is_empty() {
         errors.push(syn::Error::new(
             ident.span(),
-            "missing `#[api_key(..)]` or `#[http(..)]` attribute",
+            "missing `#[api_key(..)]`, `#[http(..)]`, `#[oauth2(..)]` or `#[openid_connect(..)]` attribute",
         ));
     }
 
@@ -225,3 +400,156 @@ fn generate_http(attrs: &[Attribute], ident: &Ident, http: HttpAttr) -> syn::Res
 
     Ok(expand)
 }
+
+fn generate_oauth2(
+    attrs: &[Attribute],
+    ident: &Ident,
+    oauth2: OAuth2Attr,
+) -> syn::Result<TokenStream> {
+    let OAuth2Attr { rename, flows } = oauth2;
+
+    let ident_str = rename.unwrap_or_else(|| ident.to_string());
+
+    let mut declared_scopes = Vec::new();
+
+    let mut generate_flow = |kind: FlowKind| {
+        let Some((_, flow)) = flows.iter().find(|(k, _)| *k == kind) else {
+            return quote!(::core::option::Option::None);
+        };
+
+        let FlowAttr {
+            authorization_url,
+            token_url,
+            refresh_url,
+            scopes,
+        } = flow;
+
+        let authorization_url = authorization_url
+            .iter()
+            .map(|url| quote!(authorization_url: ::std::string::ToString::to_string(#url),));
+
+        let token_url = token_url
+            .iter()
+            .map(|url| quote!(token_url: ::std::string::ToString::to_string(#url),));
+
+        let refresh_url = match refresh_url {
+            Some(url) => {
+                quote!(::core::option::Option::Some(::std::string::ToString::to_string(#url)))
+            }
+            None => quote!(::core::option::Option::None),
+        };
+
+        let insert_scopes = scopes.iter().map(|(name, description)| {
+            if !declared_scopes.iter().any(|n: &String| *n == name.value()) {
+                declared_scopes.push(name.value());
+            }
+
+            quote! {
+                scopes.insert(
+                    ::std::string::ToString::to_string(#name),
+                    ::std::string::ToString::to_string(#description),
+                );
+            }
+        });
+
+        let flow_ty = match kind {
+            FlowKind::Implicit => quote!(ImplicitOAuth2Flow),
+            FlowKind::Password => quote!(PasswordOAuth2Flow),
+            FlowKind::ClientCredentials => quote!(ClientCredentialsOAuth2Flow),
+            FlowKind::AuthorizationCode => quote!(AuthorizationCodeOAuth2Flow),
+        };
+
+        quote_use! {
+            # use core::default::Default;
+            # use predawn::openapi;
+            # use predawn::__internal::indexmap::IndexMap;
+
+            ::core::option::Option::Some({
+                #[allow(unused_mut)]
+                let mut scopes = IndexMap::new();
+                #(#insert_scopes)*
+
+                openapi::#flow_ty {
+                    #(#authorization_url)*
+                    #(#token_url)*
+                    refresh_url: #refresh_url,
+                    scopes,
+                    extensions: Default::default(),
+                }
+            })
+        }
+    };
+
+    let implicit = generate_flow(FlowKind::Implicit);
+    let password = generate_flow(FlowKind::Password);
+    let client_credentials = generate_flow(FlowKind::ClientCredentials);
+    let authorization_code = generate_flow(FlowKind::AuthorizationCode);
+
+    let description = util::extract_description(attrs);
+    let description = util::generate_optional_lit_str(&description)
+        .unwrap_or_else(|| quote!(::core::option::Option::None));
+
+    let expand = quote_use! {
+        # use core::default::Default;
+        # use core::option::Option;
+        # use predawn::SecurityScheme;
+        # use predawn::openapi::{self, OAuth2Flows};
+
+        impl SecurityScheme for #ident {
+            const NAME: &'static str = #ident_str;
+
+            const SCOPES: Option<&'static [&'static str]> = Option::Some(&[#(#declared_scopes),*]);
+
+            fn create() -> openapi::SecurityScheme {
+                openapi::SecurityScheme::OAuth2 {
+                    flows: OAuth2Flows {
+                        implicit: #implicit,
+                        password: #password,
+                        client_credentials: #client_credentials,
+                        authorization_code: #authorization_code,
+                        extensions: Default::default(),
+                    },
+                    description: #description,
+                    extensions: Default::default(),
+                }
+            }
+        }
+    };
+
+    Ok(expand)
+}
+
+fn generate_openid_connect(
+    attrs: &[Attribute],
+    ident: &Ident,
+    openid_connect: OpenIdConnectAttr,
+) -> syn::Result<TokenStream> {
+    let OpenIdConnectAttr { rename, url } = openid_connect;
+
+    let ident_str = rename.unwrap_or_else(|| ident.to_string());
+
+    let description = util::extract_description(attrs);
+    let description = util::generate_optional_lit_str(&description)
+        .unwrap_or_else(|| quote!(::core::option::Option::None));
+
+    let expand = quote_use! {
+        # use core::default::Default;
+        # use std::string::ToString;
+        # use predawn::SecurityScheme;
+        # use predawn::openapi;
+
+        impl SecurityScheme for #ident {
+            const NAME: &'static str = #ident_str;
+
+            fn create() -> openapi::SecurityScheme {
+                openapi::SecurityScheme::OpenIDConnect {
+                    open_id_connect_url: ToString::to_string(#url),
+                    description: #description,
+                    extensions: Default::default(),
+                }
+            }
+        }
+    };
+
+    Ok(expand)
+}
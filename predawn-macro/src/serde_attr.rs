@@ -1,12 +1,100 @@
-use syn::{punctuated::Punctuated, Attribute, Expr, ExprLit, Lit, Meta, MetaNameValue, Token};
+use syn::{
+    ext::IdentExt, punctuated::Punctuated, Attribute, Expr, ExprLit, Ident, Lit, LitStr, Meta,
+    MetaList, MetaNameValue, Token,
+};
 
+#[derive(Default)]
 pub(crate) struct SerdeAttr {
-    pub(crate) rename: Option<String>,
+    pub(crate) rename: SerDe<String>,
+    pub(crate) rename_all: SerDe<RenameRule>,
+    pub(crate) rename_all_fields: SerDe<RenameRule>,
+    pub(crate) alias: Vec<String>,
+    pub(crate) tag: Option<String>,
+    pub(crate) content: Option<String>,
+    pub(crate) untagged: bool,
+    pub(crate) transparent: bool,
+    pub(crate) flatten: bool,
+    pub(crate) default: bool,
+    pub(crate) skip: bool,
+    pub(crate) skip_serializing: bool,
+    pub(crate) skip_deserializing: bool,
+    pub(crate) skip_serializing_if: bool,
+}
+
+/// Which side of serde an attribute applies to.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Direction {
+    Serialize,
+    Deserialize,
+}
+
+/// An attribute which may differ between serializing and deserializing,
+/// e.g. `#[serde(rename(serialize = "a", deserialize = "b"))]`.
+#[derive(Clone, Copy)]
+pub(crate) struct SerDe<T> {
+    pub(crate) serialize: Option<T>,
+    pub(crate) deserialize: Option<T>,
+}
+
+impl<T> Default for SerDe<T> {
+    fn default() -> Self {
+        Self {
+            serialize: None,
+            deserialize: None,
+        }
+    }
+}
+
+impl<T: Clone> SerDe<T> {
+    fn both(value: T) -> Self {
+        Self {
+            serialize: Some(value.clone()),
+            deserialize: Some(value),
+        }
+    }
+
+    pub(crate) fn get(&self, direction: Direction) -> Option<&T> {
+        match direction {
+            Direction::Serialize => self.serialize.as_ref(),
+            Direction::Deserialize => self.deserialize.as_ref(),
+        }
+    }
+
+    pub(crate) fn or(self, other: Self) -> Self {
+        Self {
+            serialize: self.serialize.or(other.serialize),
+            deserialize: self.deserialize.or(other.deserialize),
+        }
+    }
+
+    /// Parses `serialize = "..", deserialize = ".."`, either of them may be omitted.
+    fn parse_list<F>(meta_list: &MetaList, parse: F) -> syn::Result<Self>
+    where
+        F: Fn(&LitStr) -> syn::Result<T>,
+    {
+        let mut ser_de = Self::default();
+
+        meta_list.parse_nested_meta(|meta| {
+            let lit_str = meta.value()?.parse::<LitStr>()?;
+
+            if meta.path.is_ident("serialize") {
+                ser_de.serialize = Some(parse(&lit_str)?);
+            } else if meta.path.is_ident("deserialize") {
+                ser_de.deserialize = Some(parse(&lit_str)?);
+            } else {
+                return Err(meta.error("expected `serialize` or `deserialize`"));
+            }
+
+            Ok(())
+        })?;
+
+        Ok(ser_de)
+    }
 }
 
 impl SerdeAttr {
     pub(crate) fn new(attrs: &[Attribute]) -> syn::Result<Self> {
-        let mut rename = None;
+        let mut serde_attr = Self::default();
 
         for attr in attrs {
             if !attr.path().is_ident("serde") {
@@ -23,23 +111,347 @@ impl SerdeAttr {
             for meta in nested {
                 match meta {
                     Meta::NameValue(MetaNameValue { path, value, .. }) => {
+                        let Expr::Lit(ExprLit {
+                            lit: Lit::Str(lit_str),
+                            ..
+                        }) = value
+                        else {
+                            continue;
+                        };
+
                         if path.is_ident("rename") {
-                            match value {
-                                Expr::Lit(ExprLit {
-                                    lit: Lit::Str(lit_str),
-                                    ..
-                                }) => {
-                                    rename = Some(lit_str.value());
-                                }
-                                _ => continue,
-                            }
+                            serde_attr.rename = SerDe::both(lit_str.value());
+                        } else if path.is_ident("rename_all") {
+                            serde_attr.rename_all = SerDe::both(RenameRule::parse(&lit_str)?);
+                        } else if path.is_ident("rename_all_fields") {
+                            serde_attr.rename_all_fields =
+                                SerDe::both(RenameRule::parse(&lit_str)?);
+                        } else if path.is_ident("alias") {
+                            serde_attr.alias.push(lit_str.value());
+                        } else if path.is_ident("tag") {
+                            serde_attr.tag = Some(lit_str.value());
+                        } else if path.is_ident("content") {
+                            serde_attr.content = Some(lit_str.value());
+                        } else if path.is_ident("default") {
+                            serde_attr.default = true;
+                        } else if path.is_ident("skip_serializing_if") {
+                            serde_attr.skip_serializing_if = true;
                         }
                     }
-                    _ => continue,
+                    Meta::Path(path) => {
+                        if path.is_ident("untagged") {
+                            serde_attr.untagged = true;
+                        } else if path.is_ident("transparent") {
+                            serde_attr.transparent = true;
+                        } else if path.is_ident("flatten") {
+                            serde_attr.flatten = true;
+                        } else if path.is_ident("default") {
+                            serde_attr.default = true;
+                        } else if path.is_ident("skip") {
+                            serde_attr.skip = true;
+                        } else if path.is_ident("skip_serializing") {
+                            serde_attr.skip_serializing = true;
+                        } else if path.is_ident("skip_deserializing") {
+                            serde_attr.skip_deserializing = true;
+                        }
+                    }
+                    Meta::List(meta_list) => {
+                        let path = &meta_list.path;
+
+                        if path.is_ident("rename") {
+                            serde_attr.rename =
+                                SerDe::parse_list(&meta_list, |lit_str| Ok(lit_str.value()))?;
+                        } else if path.is_ident("rename_all") {
+                            serde_attr.rename_all =
+                                SerDe::parse_list(&meta_list, RenameRule::parse)?;
+                        } else if path.is_ident("rename_all_fields") {
+                            serde_attr.rename_all_fields =
+                                SerDe::parse_list(&meta_list, RenameRule::parse)?;
+                        }
+                    }
+                }
+            }
+        }
+
+        if serde_attr.skip_serializing && serde_attr.skip_deserializing {
+            serde_attr.skip = true;
+        }
+
+        Ok(serde_attr)
+    }
+
+    /// The serialized or deserialized name of a field, `rename_all` is the rule of its container.
+    pub(crate) fn field_name(
+        &self,
+        ident: &Ident,
+        rename_all: &SerDe<RenameRule>,
+        direction: Direction,
+    ) -> String {
+        if let Some(rename) = self.rename.get(direction) {
+            return rename.clone();
+        }
+
+        let ident = ident.unraw().to_string();
+
+        match rename_all.get(direction) {
+            Some(rule) => rule.apply_to_field(&ident),
+            None => ident,
+        }
+    }
+
+    /// Whether the field may be absent from the serialized or the deserialized data.
+    pub(crate) fn optional(&self) -> bool {
+        self.default || self.skip_serializing || self.skip_deserializing || self.skip_serializing_if
+    }
+}
+
+/// The casing rules of `#[serde(rename_all = "...")]`, the same as serde's.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum RenameRule {
+    Lower,
+    Upper,
+    Pascal,
+    Camel,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+    ScreamingKebab,
+}
+
+impl RenameRule {
+    const RULES: [(&'static str, RenameRule); 8] = [
+        ("lowercase", RenameRule::Lower),
+        ("UPPERCASE", RenameRule::Upper),
+        ("PascalCase", RenameRule::Pascal),
+        ("camelCase", RenameRule::Camel),
+        ("snake_case", RenameRule::Snake),
+        ("SCREAMING_SNAKE_CASE", RenameRule::ScreamingSnake),
+        ("kebab-case", RenameRule::Kebab),
+        ("SCREAMING-KEBAB-CASE", RenameRule::ScreamingKebab),
+    ];
+
+    fn parse(lit_str: &LitStr) -> syn::Result<Self> {
+        let value = lit_str.value();
+
+        Self::RULES
+            .iter()
+            .find(|(name, _)| *name == value)
+            .map(|(_, rule)| *rule)
+            .ok_or_else(|| {
+                let expected = Self::RULES
+                    .iter()
+                    .map(|(name, _)| format!("\"{name}\""))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                syn::Error::new(
+                    lit_str.span(),
+                    format!("unknown rename rule, expected one of {expected}"),
+                )
+            })
+    }
+
+    /// Applies the rule to a variant name, which is assumed to be `PascalCase`.
+    pub(crate) fn apply_to_variant(self, variant: &str) -> String {
+        match self {
+            RenameRule::Pascal => variant.to_string(),
+            RenameRule::Lower => variant.to_ascii_lowercase(),
+            RenameRule::Upper => variant.to_ascii_uppercase(),
+            RenameRule::Camel => {
+                let mut chars = variant.chars();
+
+                match chars.next() {
+                    Some(first) => first.to_ascii_lowercase().to_string() + chars.as_str(),
+                    None => String::new(),
+                }
+            }
+            RenameRule::Snake => {
+                let mut snake = String::new();
+
+                for (i, ch) in variant.char_indices() {
+                    if i > 0 && ch.is_uppercase() {
+                        snake.push('_');
+                    }
+                    snake.push(ch.to_ascii_lowercase());
                 }
+
+                snake
             }
+            RenameRule::ScreamingSnake => RenameRule::Snake
+                .apply_to_variant(variant)
+                .to_ascii_uppercase(),
+            RenameRule::Kebab => RenameRule::Snake
+                .apply_to_variant(variant)
+                .replace('_', "-"),
+            RenameRule::ScreamingKebab => RenameRule::ScreamingSnake
+                .apply_to_variant(variant)
+                .replace('_', "-"),
         }
+    }
 
-        Ok(Self { rename })
+    /// Applies the rule to a field name, which is assumed to be `snake_case`.
+    pub(crate) fn apply_to_field(self, field: &str) -> String {
+        match self {
+            RenameRule::Lower | RenameRule::Snake => field.to_string(),
+            RenameRule::Upper | RenameRule::ScreamingSnake => field.to_ascii_uppercase(),
+            RenameRule::Pascal => {
+                let mut pascal = String::new();
+                let mut capitalize = true;
+
+                for ch in field.chars() {
+                    if ch == '_' {
+                        capitalize = true;
+                    } else if capitalize {
+                        pascal.push(ch.to_ascii_uppercase());
+                        capitalize = false;
+                    } else {
+                        pascal.push(ch);
+                    }
+                }
+
+                pascal
+            }
+            RenameRule::Camel => {
+                let pascal = RenameRule::Pascal.apply_to_field(field);
+                RenameRule::Camel.apply_to_variant(&pascal)
+            }
+            RenameRule::Kebab => field.replace('_', "-"),
+            RenameRule::ScreamingKebab => field.to_ascii_uppercase().replace('_', "-"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::parse_quote;
+
+    use super::*;
+
+    #[test]
+    fn test_serde_attr() {
+        let ident: Ident = parse_quote!(user_name);
+        let rename_all = SerDe::default();
+
+        let attrs: Vec<Attribute> = parse_quote! {
+            #[serde(rename(serialize = "userName", deserialize = "user"))]
+            #[serde(skip_serializing_if = "Option::is_none")]
+        };
+
+        let serde_attr = SerdeAttr::new(&attrs).unwrap();
+
+        assert_eq!(
+            serde_attr.field_name(&ident, &rename_all, Direction::Serialize),
+            "userName"
+        );
+        assert_eq!(
+            serde_attr.field_name(&ident, &rename_all, Direction::Deserialize),
+            "user"
+        );
+        assert!(!serde_attr.skip && !serde_attr.skip_serializing);
+        assert!(serde_attr.optional());
+
+        let attrs: Vec<Attribute> = parse_quote! {
+            #[serde(rename(deserialize = "user"))]
+        };
+
+        let serde_attr = SerdeAttr::new(&attrs).unwrap();
+        let rename_all = SerDe {
+            serialize: Some(RenameRule::Camel),
+            deserialize: None,
+        };
+
+        assert_eq!(
+            serde_attr.field_name(&ident, &rename_all, Direction::Serialize),
+            "userName"
+        );
+        assert_eq!(
+            serde_attr.field_name(&ident, &rename_all, Direction::Deserialize),
+            "user"
+        );
+        assert!(!serde_attr.optional());
+
+        let attrs: Vec<Attribute> = parse_quote! {
+            #[serde(rename_all(serialize = "camelCase", deserialize = "kebab-case"))]
+        };
+
+        let serde_attr = SerdeAttr::new(&attrs).unwrap();
+
+        assert_eq!(
+            serde_attr.rename_all.get(Direction::Deserialize),
+            Some(&RenameRule::Kebab)
+        );
+
+        let attrs: Vec<Attribute> = parse_quote! {
+            #[serde(rename(other = "user"))]
+        };
+
+        assert!(SerdeAttr::new(&attrs).is_err());
+    }
+
+    #[test]
+    fn test_rename_rule() {
+        let cases = [
+            (
+                RenameRule::Lower,
+                "OutOfStock",
+                "out_of_stock",
+                "outofstock",
+                "out_of_stock",
+            ),
+            (
+                RenameRule::Upper,
+                "OutOfStock",
+                "out_of_stock",
+                "OUTOFSTOCK",
+                "OUT_OF_STOCK",
+            ),
+            (
+                RenameRule::Pascal,
+                "OutOfStock",
+                "out_of_stock",
+                "OutOfStock",
+                "OutOfStock",
+            ),
+            (
+                RenameRule::Camel,
+                "OutOfStock",
+                "out_of_stock",
+                "outOfStock",
+                "outOfStock",
+            ),
+            (
+                RenameRule::Snake,
+                "OutOfStock",
+                "out_of_stock",
+                "out_of_stock",
+                "out_of_stock",
+            ),
+            (
+                RenameRule::ScreamingSnake,
+                "OutOfStock",
+                "out_of_stock",
+                "OUT_OF_STOCK",
+                "OUT_OF_STOCK",
+            ),
+            (
+                RenameRule::Kebab,
+                "OutOfStock",
+                "out_of_stock",
+                "out-of-stock",
+                "out-of-stock",
+            ),
+            (
+                RenameRule::ScreamingKebab,
+                "OutOfStock",
+                "out_of_stock",
+                "OUT-OF-STOCK",
+                "OUT-OF-STOCK",
+            ),
+        ];
+
+        for (rule, variant, field, renamed_variant, renamed_field) in cases {
+            assert_eq!(rule.apply_to_variant(variant), renamed_variant);
+            assert_eq!(rule.apply_to_field(field), renamed_field);
+        }
     }
 }
@@ -3,16 +3,22 @@ use quote::quote;
 use quote_use::quote_use;
 use syn::{DeriveInput, Field};
 
-use crate::{serde_attr::SerdeAttr, util};
+use crate::{
+    serde_attr::{Direction, SerdeAttr},
+    util,
+};
 
 pub(crate) fn generate(input: DeriveInput) -> syn::Result<TokenStream> {
     let DeriveInput {
+        attrs,
         ident,
         generics,
         data,
         ..
     } = input;
 
+    let container = SerdeAttr::new(&attrs)?;
+
     let named = util::extract_named_struct_fields(data, "ToParameters")?;
 
     let mut parameter_impls = Vec::new();
@@ -20,7 +26,7 @@ pub(crate) fn generate(input: DeriveInput) -> syn::Result<TokenStream> {
 
     named
         .into_iter()
-        .for_each(|field| match generate_single_field(field) {
+        .for_each(|field| match generate_single_field(field, &container) {
             Ok(parameter) => {
                 parameter_impls.push(parameter);
             }
@@ -44,10 +50,11 @@ pub(crate) fn generate(input: DeriveInput) -> syn::Result<TokenStream> {
 
         impl #impl_generics ToParameters for #ident #ty_generics #where_clause {
             fn parameters(schemas: &mut BTreeMap<String, Schema>) -> Vec<ParameterData> {
-                [
-                    #(#parameter_impls)*
-                ]
-                .to_vec()
+                let mut parameters = Vec::new();
+
+                #(#parameter_impls)*
+
+                parameters
             }
         }
     };
@@ -55,18 +62,57 @@ pub(crate) fn generate(input: DeriveInput) -> syn::Result<TokenStream> {
     Ok(expand)
 }
 
-fn generate_single_field(field: Field) -> syn::Result<TokenStream> {
+fn generate_single_field(field: Field, container: &SerdeAttr) -> syn::Result<TokenStream> {
     let Field {
         attrs, ident, ty, ..
     } = field;
 
-    let SerdeAttr { rename } = SerdeAttr::new(&attrs)?;
+    let serde_attr = SerdeAttr::new(&attrs)?;
+
+    if serde_attr.skip {
+        return Ok(TokenStream::new());
+    }
+
+    let optional = container.default || serde_attr.optional();
+
+    if serde_attr.flatten {
+        let make_optional = optional.then(|| {
+            quote! {
+                .map(|mut parameter| {
+                    parameter.required = false;
+                    parameter
+                })
+            }
+        });
+
+        let expand = quote_use! {
+            # use std::iter::IntoIterator;
+            # use predawn::ToParameters;
+
+            parameters.extend(
+                IntoIterator::into_iter(<#ty as ToParameters>::parameters(schemas))
+                    #make_optional
+            );
+        };
+
+        return Ok(expand);
+    }
+
+    let ident = serde_attr.field_name(
+        &ident.expect("unreachable: named field must have an identifier"),
+        &container.rename_all,
+        Direction::Deserialize,
+    );
 
-    let ident = rename.unwrap_or_else(|| {
-        ident
-            .expect("unreachable: named field must have an identifier")
-            .to_string()
-    });
+    let required = if optional {
+        quote!(false)
+    } else {
+        quote_use! {
+            # use predawn::ToSchema;
+
+            <#ty as ToSchema>::REQUIRED
+        }
+    };
 
     let description = util::extract_description(&attrs);
     let description = util::generate_optional_lit_str(&description)
@@ -78,17 +124,17 @@ fn generate_single_field(field: Field) -> syn::Result<TokenStream> {
         # use predawn::ToSchema;
         # use predawn::openapi::{ParameterData, ParameterSchemaOrContent};
 
-        ParameterData {
+        parameters.push(ParameterData {
             name: ToString::to_string(#ident),
             description: #description,
-            required: <#ty as ToSchema>::REQUIRED,
+            required: #required,
             deprecated: Default::default(),
             format: ParameterSchemaOrContent::Schema(<#ty as ToSchema>::schema_ref(schemas)),
             example: Default::default(),
             examples: Default::default(),
             explode: Default::default(),
             extensions: Default::default(),
-        },
+        });
     };
 
     Ok(expand)
@@ -1,9 +1,20 @@
-use proc_macro2::TokenStream;
+use proc_macro2::{Span, TokenStream};
 use quote::quote;
 use quote_use::quote_use;
-use syn::{DeriveInput, Field, Generics};
+use syn::{
+    ext::IdentExt, punctuated::Punctuated, spanned::Spanned, Data, DataEnum, DataStruct, DataUnion,
+    DeriveInput, Field, Fields, FieldsNamed, FieldsUnnamed, Generics, Ident, Token, Variant,
+};
 
-use crate::{serde_attr::SerdeAttr, util};
+use crate::{
+    schema_attr::SchemaAttr,
+    serde_attr::{Direction, RenameRule, SerDe, SerdeAttr},
+    util,
+};
+
+/// The maximum number of fields of tuple structs and tuple variants,
+/// the same as the tuples which implement `ToSchema`.
+const MAX_TUPLE_FIELDS: usize = 12;
 
 pub(crate) fn generate(input: DeriveInput) -> syn::Result<TokenStream> {
     let DeriveInput {
@@ -14,13 +25,105 @@ pub(crate) fn generate(input: DeriveInput) -> syn::Result<TokenStream> {
         ..
     } = input;
 
-    let named = util::extract_named_struct_fields(data, "ToSchema")?;
+    let container = SerdeAttr::new(&attrs)?;
+    let apply_schema_attr = SchemaAttr::new(&attrs)?.generate(ident.span())?;
+
+    let generate_schema = match data {
+        Data::Struct(DataStruct {
+            struct_token,
+            fields,
+            ..
+        }) if container.transparent => generate_transparent(fields, struct_token.span)?,
+        Data::Struct(DataStruct {
+            struct_token,
+            fields,
+            ..
+        }) => match fields {
+            Fields::Named(FieldsNamed { named, .. }) => {
+                let rules = FieldRules {
+                    rename_all: container.rename_all,
+                    default: container.default,
+                };
+
+                generate_object(named, TokenStream::new(), rules)?
+            }
+            Fields::Unnamed(FieldsUnnamed { unnamed, .. }) => {
+                let ty = generate_unnamed_type(unnamed)?;
+
+                quote_use! {
+                    # use predawn::ToSchema;
+
+                    <#ty as ToSchema>::schema(schemas)
+                }
+            }
+            Fields::Unit => {
+                return Err(syn::Error::new(
+                    struct_token.span,
+                    "`ToSchema` can not be derived for unit structs",
+                ))
+            }
+        },
+        Data::Enum(DataEnum {
+            brace_token,
+            variants,
+            ..
+        }) => {
+            if variants.is_empty() {
+                return Err(syn::Error::new(
+                    brace_token.span.join(),
+                    "must have at least one variant",
+                ));
+            }
+
+            generate_enum(&container, &ident, variants)?
+        }
+        Data::Union(DataUnion { union_token, .. }) => {
+            return Err(syn::Error::new(
+                union_token.span,
+                "`ToSchema` can only be derived for structs and enums",
+            ))
+        }
+    };
+
+    let schema_title = generate_schema_title(&ident.to_string(), &generics);
+
+    let description = util::extract_description(&attrs);
+    let add_description = util::generate_optional_lit_str(&description).map(|description| {
+        quote! {
+            schema.schema_data.description = #description;
+        }
+    });
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
+    let expand = quote_use! {
+        # use std::collections::BTreeMap;
+        # use predawn::ToSchema;
+        # use predawn::openapi::Schema;
+
+        impl #impl_generics ToSchema for #ident #ty_generics #where_clause {
+            fn schema(schemas: &mut BTreeMap<String, Schema>) -> Schema {
+                let mut schema = #generate_schema;
+
+                let title = #schema_title;
+                schema.schema_data.title = Some(title);
+
+                #add_description
+                #apply_schema_attr
+
+                schema
+            }
+        }
+    };
+
+    Ok(expand)
+}
+
+fn combine_errors<T>(results: impl Iterator<Item = syn::Result<T>>) -> syn::Result<Vec<T>> {
     let mut errors = Vec::new();
 
-    let properties = named
-        .into_iter()
-        .filter_map(|field| match generate_single_field(field) {
+    let tokens = results
+        .filter_map(|result| match result {
             Ok(o) => Some(o),
             Err(e) => {
                 errors.push(e);
@@ -29,66 +132,628 @@ pub(crate) fn generate(input: DeriveInput) -> syn::Result<TokenStream> {
         })
         .collect::<Vec<_>>();
 
-    if let Some(e) = errors.into_iter().reduce(|mut a, b| {
+    match errors.into_iter().reduce(|mut a, b| {
         a.combine(b);
         a
     }) {
-        return Err(e);
+        Some(e) => Err(e),
+        None => Ok(tokens),
     }
+}
 
-    let schema_title = generate_schema_title(&ident.to_string(), &generics);
+/// How the fields of a struct or a struct variant are named, and whether all of them have defaults.
+#[derive(Clone, Copy, Default)]
+struct FieldRules {
+    rename_all: SerDe<RenameRule>,
+    default: bool,
+}
 
-    let description = util::extract_description(&attrs);
-    let add_description = util::generate_optional_lit_str(&description).map(|description| {
-        quote! {
-            data.description = #description;
+/// Generates an object schema from named fields, `prelude` can add properties before the fields.
+fn generate_object(
+    named: Punctuated<Field, Token![,]>,
+    prelude: TokenStream,
+    rules: FieldRules,
+) -> syn::Result<TokenStream> {
+    let mut aliases = Vec::new();
+
+    let properties = combine_errors(
+        named
+            .into_iter()
+            .map(|field| generate_single_field(field, rules, &mut aliases)),
+    )?;
+
+    let add_aliases = (!aliases.is_empty()).then(|| {
+        let (names, aliases): (Vec<_>, Vec<_>) = aliases.into_iter().unzip();
+
+        quote_use! {
+            # use std::string::ToString;
+
+            schema.schema_data.extensions.insert(
+                ToString::to_string("x-aliases"),
+                ::predawn::__internal::serde_json::json!({ #(#names: [#(#aliases),*]),* }),
+            );
         }
     });
 
-    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
-
     let expand = quote_use! {
         # use core::default::Default;
-        # use std::collections::BTreeMap;
+        # use std::vec::Vec;
+        # use predawn::openapi::{ObjectType, object_schema};
+
+        {
+            let mut ty = ObjectType::default();
+
+            #[allow(unused_mut)]
+            let mut flattened = Vec::new();
+
+            #prelude
+            #(#properties)*
+
+            #[allow(unused_mut)]
+            let mut schema = object_schema(ty, flattened);
+
+            #add_aliases
+
+            schema
+        }
+    };
+
+    Ok(expand)
+}
+
+/// A transparent struct has the schema of its only field that is not skipped.
+fn generate_transparent(fields: Fields, span: Span) -> syn::Result<TokenStream> {
+    let fields = combine_errors(fields.into_iter().map(|field| {
+        let SerdeAttr { skip, .. } = SerdeAttr::new(&field.attrs)?;
+        Ok((!skip).then_some(field))
+    }))?;
+
+    let mut fields = fields.into_iter().flatten();
+
+    let (Some(field), None) = (fields.next(), fields.next()) else {
+        return Err(syn::Error::new(
+            span,
+            "`#[serde(transparent)]` requires exactly one field that is not skipped",
+        ));
+    };
+
+    let ty = field.ty;
+
+    let expand = quote_use! {
         # use predawn::ToSchema;
-        # use predawn::openapi::{Schema, ObjectType, SchemaData, SchemaKind, Type};
 
-        impl #impl_generics ToSchema for #ident #ty_generics #where_clause {
-            fn schema(schemas: &mut BTreeMap<String, Schema>) -> Schema {
-                let mut data = SchemaData::default();
+        <#ty as ToSchema>::schema(schemas)
+    };
 
-                let title = #schema_title;
-                data.title = Some(title);
+    Ok(expand)
+}
 
-                #add_description
+/// A single unnamed field is transparent, several unnamed fields are a tuple.
+fn generate_unnamed_type(unnamed: Punctuated<Field, Token![,]>) -> syn::Result<TokenStream> {
+    if unnamed.len() > MAX_TUPLE_FIELDS {
+        return Err(syn::Error::new(
+            unnamed.span(),
+            format!("must have at most {MAX_TUPLE_FIELDS} fields"),
+        ));
+    }
+
+    let mut types = unnamed
+        .into_iter()
+        .map(|field| field.ty)
+        .collect::<Vec<_>>();
+
+    if types.len() == 1 {
+        let ty = types.pop().expect("unreachable: must have one field");
+        return Ok(quote! { #ty });
+    }
+
+    Ok(quote! { (#(#types,)*) })
+}
+
+enum Tagging {
+    External,
+    Internal { tag: String },
+    Adjacent { tag: String, content: String },
+    Untagged,
+}
+
+impl Tagging {
+    fn new(container: &SerdeAttr, ident: &Ident) -> syn::Result<Self> {
+        let SerdeAttr {
+            tag,
+            content,
+            untagged,
+            ..
+        } = container;
+
+        let tagging = match (tag.clone(), content.clone(), *untagged) {
+            (None, None, true) => Tagging::Untagged,
+            (None, None, false) => Tagging::External,
+            (Some(tag), None, false) => Tagging::Internal { tag },
+            (Some(tag), Some(content), false) => Tagging::Adjacent { tag, content },
+            (None, Some(_), false) => {
+                return Err(syn::Error::new(
+                    ident.span(),
+                    "`#[serde(content = \"...\")]` must be used with `#[serde(tag = \"...\")]`",
+                ))
+            }
+            (_, _, true) => {
+                return Err(syn::Error::new(
+                    ident.span(),
+                    "`#[serde(untagged)]` can not be used with `tag` or `content`",
+                ))
+            }
+        };
+
+        Ok(tagging)
+    }
 
-                let mut ty = ObjectType::default();
+    fn tag(&self) -> Option<&str> {
+        match self {
+            Tagging::Internal { tag } | Tagging::Adjacent { tag, .. } => Some(tag),
+            Tagging::External | Tagging::Untagged => None,
+        }
+    }
+}
 
-                #(#properties)*
+fn generate_enum(
+    container: &SerdeAttr,
+    ident: &Ident,
+    variants: Punctuated<Variant, Token![,]>,
+) -> syn::Result<TokenStream> {
+    let tagging = Tagging::new(container, ident)?;
+
+    let variants = combine_errors(variants.into_iter().map(|variant| {
+        let serde_attr = SerdeAttr::new(&variant.attrs)?;
+        Ok((!serde_attr.skip).then_some((variant, serde_attr)))
+    }))?
+    .into_iter()
+    .flatten()
+    .collect::<Vec<_>>();
+
+    if matches!(tagging, Tagging::External)
+        && variants
+            .iter()
+            .all(|(variant, _)| matches!(variant.fields, Fields::Unit))
+    {
+        let names = variants
+            .iter()
+            .map(|(variant, serde_attr)| variant_name(variant, serde_attr, container))
+            .collect::<Vec<_>>();
+
+        let expand = quote_use! {
+            # use core::default::Default;
+            # use std::string::ToString;
+            # use predawn::openapi::{Schema, SchemaKind, StringType, Type};
+
+            {
+                let mut ty = StringType::default();
+
+                #(
+                    ty.enumeration.push(Some(ToString::to_string(#names)));
+                )*
 
                 Schema {
-                    schema_data: data,
-                    schema_kind: SchemaKind::Type(Type::Object(ty)),
+                    schema_data: Default::default(),
+                    schema_kind: SchemaKind::Type(Type::String(ty)),
+                }
+            }
+        };
+
+        return Ok(expand);
+    }
+
+    let push_variants =
+        combine_errors(variants.into_iter().map(|(variant, serde_attr)| {
+            generate_variant(&tagging, container, variant, serde_attr)
+        }))?;
+
+    let add_discriminator = tagging.tag().map(|tag| {
+        quote_use! {
+            # use core::default::Default;
+            # use std::string::ToString;
+            # use predawn::openapi::Discriminator;
+
+            schema.schema_data.discriminator = Some(Discriminator {
+                property_name: ToString::to_string(#tag),
+                mapping,
+                extensions: Default::default(),
+            });
+        }
+    });
+
+    let expand = quote_use! {
+        # use core::default::Default;
+        # use std::vec::Vec;
+        # use predawn::__internal::indexmap::IndexMap;
+        # use predawn::openapi::{Schema, SchemaKind};
+
+        {
+            let mut one_of = Vec::new();
+
+            #[allow(unused_mut)]
+            let mut mapping = IndexMap::<String, String>::new();
+
+            #(#push_variants)*
+
+            #[allow(unused_mut)]
+            let mut schema = Schema {
+                schema_data: Default::default(),
+                schema_kind: SchemaKind::OneOf { one_of },
+            };
+
+            #add_discriminator
+
+            schema
+        }
+    };
+
+    Ok(expand)
+}
+
+fn variant_name(variant: &Variant, serde_attr: &SerdeAttr, container: &SerdeAttr) -> String {
+    if let Some(rename) = serde_attr.rename.get(Direction::Serialize) {
+        return rename.clone();
+    }
+
+    let ident = variant.ident.unraw().to_string();
+
+    match container.rename_all.get(Direction::Serialize) {
+        Some(rule) => rule.apply_to_variant(&ident),
+        None => ident,
+    }
+}
+
+/// Generates the statements which push the schema of a variant into `one_of`.
+///
+/// The variants of internally and adjacently tagged enums are added to `schemas`,
+/// so that they can be referenced by the discriminator mapping.
+fn generate_variant(
+    tagging: &Tagging,
+    container: &SerdeAttr,
+    variant: Variant,
+    serde_attr: SerdeAttr,
+) -> syn::Result<TokenStream> {
+    let name = variant_name(&variant, &serde_attr, container);
+
+    let rules = FieldRules {
+        rename_all: serde_attr.rename_all.or(container.rename_all_fields),
+        default: false,
+    };
+
+    let Variant {
+        attrs,
+        ident,
+        fields,
+        ..
+    } = variant;
+
+    let ident = ident.to_string();
+
+    let description = util::extract_description(&attrs);
+    let annotate = {
+        let add_description = util::generate_optional_lit_str(&description).map(|description| {
+            quote! {
+                schema.schema_data.description = #description;
+            }
+        });
+
+        quote_use! {
+            # use std::string::ToString;
+
+            schema.schema_data.title = Some(ToString::to_string(#ident));
+            #add_description
+        }
+    };
+
+    let span = fields.span();
+
+    let variant_schema = match tagging {
+        Tagging::External => match fields {
+            Fields::Unit => string_enum(&name),
+            fields => {
+                let content = generate_variant_content(fields, true, rules)?;
+                let property = generate_property(&name, content);
+
+                generate_object_with(property)
+            }
+        },
+        Tagging::Internal { tag } => {
+            let tag_property = generate_property(tag, string_enum_box(&name));
+
+            match fields {
+                Fields::Unit => generate_object_with(tag_property),
+                Fields::Named(FieldsNamed { named, .. }) => {
+                    generate_object(named, tag_property, rules)?
+                }
+                Fields::Unnamed(FieldsUnnamed { unnamed, .. }) if unnamed.len() == 1 => {
+                    let ty = generate_unnamed_type(unnamed)?;
+                    let tag_object = generate_object_with(tag_property);
+
+                    quote_use! {
+                        # use core::default::Default;
+                        # use std::vec;
+                        # use predawn::ToSchema;
+                        # use predawn::openapi::{ReferenceOr, Schema, SchemaKind};
+
+                        Schema {
+                            schema_data: Default::default(),
+                            schema_kind: SchemaKind::AllOf {
+                                all_of: vec![
+                                    <#ty as ToSchema>::schema_ref(schemas),
+                                    ReferenceOr::Item(#tag_object),
+                                ],
+                            },
+                        }
+                    }
+                }
+                Fields::Unnamed(_) => {
+                    return Err(syn::Error::new(
+                        span,
+                        "internally tagged enums can not contain tuple variants",
+                    ))
+                }
+            }
+        }
+        Tagging::Adjacent { tag, content } => {
+            let tag_property = generate_property(tag, string_enum_box(&name));
+
+            match fields {
+                Fields::Unit => generate_object_with(tag_property),
+                fields => {
+                    let content =
+                        generate_property(content, generate_variant_content(fields, true, rules)?);
+
+                    generate_object_with(quote! {
+                        #tag_property
+                        #content
+                    })
                 }
             }
         }
+        Tagging::Untagged => {
+            let schema = match fields {
+                Fields::Unit => {
+                    quote_use! {
+                        # use core::default::Default;
+                        # use std::vec;
+                        # use predawn::__internal::serde_json::Value;
+                        # use predawn::openapi::{AnySchema, Schema, SchemaData, SchemaKind};
+
+                        Schema {
+                            schema_data: SchemaData {
+                                nullable: true,
+                                ..Default::default()
+                            },
+                            schema_kind: SchemaKind::Any(AnySchema {
+                                enumeration: vec![Value::Null],
+                                ..Default::default()
+                            }),
+                        }
+                    }
+                }
+                Fields::Unnamed(FieldsUnnamed { unnamed, .. }) if unnamed.len() == 1 => {
+                    let ty = generate_unnamed_type(unnamed)?;
+
+                    return Ok(quote_use! {
+                        # use predawn::ToSchema;
+
+                        one_of.push(<#ty as ToSchema>::schema_ref(schemas));
+                    });
+                }
+                fields => generate_variant_content(fields, false, rules)?,
+            };
+
+            return Ok(quote_use! {
+                # use predawn::openapi::ReferenceOr;
+
+                {
+                    let mut schema = #schema;
+                    #annotate
+                    one_of.push(ReferenceOr::Item(schema));
+                }
+            });
+        }
+    };
+
+    let expand = match tagging.tag() {
+        None => quote_use! {
+            # use predawn::openapi::ReferenceOr;
+
+            {
+                let mut schema = #variant_schema;
+                #annotate
+                one_of.push(ReferenceOr::Item(schema));
+            }
+        },
+        Some(_) => quote_use! {
+            # use std::format;
+            # use std::string::ToString;
+            # use predawn::ToSchema;
+            # use predawn::openapi::ReferenceOr;
+
+            {
+                let mut schema = #variant_schema;
+                #annotate
+
+                let name = format!("{}.{}", <Self as ToSchema>::name(), #ident);
+                let reference = format!("#/components/schemas/{}", name);
+
+                schemas.insert(name, schema);
+                mapping.insert(ToString::to_string(#name), reference.clone());
+                one_of.push(ReferenceOr::Reference { reference });
+            }
+        },
     };
 
     Ok(expand)
 }
 
-fn generate_single_field(field: Field) -> syn::Result<TokenStream> {
+/// Generates the schema of the content of a non-unit variant,
+/// a `ReferenceOr<Box<Schema>>` if `boxed` is `true`, otherwise a `Schema`.
+fn generate_variant_content(
+    fields: Fields,
+    boxed: bool,
+    rules: FieldRules,
+) -> syn::Result<TokenStream> {
+    let schema = match fields {
+        Fields::Named(FieldsNamed { named, .. }) => {
+            generate_object(named, TokenStream::new(), rules)?
+        }
+        Fields::Unnamed(FieldsUnnamed { unnamed, .. }) => {
+            let single = unnamed.len() == 1;
+            let ty = generate_unnamed_type(unnamed)?;
+
+            if single && boxed {
+                return Ok(quote_use! {
+                    # use predawn::ToSchema;
+
+                    <#ty as ToSchema>::schema_ref_box(schemas)
+                });
+            }
+
+            quote_use! {
+                # use predawn::ToSchema;
+
+                <#ty as ToSchema>::schema(schemas)
+            }
+        }
+        Fields::Unit => {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "unreachable: unit variants have no content",
+            ))
+        }
+    };
+
+    if !boxed {
+        return Ok(schema);
+    }
+
+    let expand = quote_use! {
+        # use std::boxed::Box;
+        # use predawn::openapi::ReferenceOr;
+
+        ReferenceOr::Item(Box::new(#schema))
+    };
+
+    Ok(expand)
+}
+
+/// Generates the statements which add a required property to `ty`.
+fn generate_property(name: &str, schema: TokenStream) -> TokenStream {
+    quote_use! {
+        # use std::string::ToString;
+
+        ty.properties.insert(ToString::to_string(#name), #schema);
+        ty.required.push(ToString::to_string(#name));
+    }
+}
+
+fn generate_object_with(properties: TokenStream) -> TokenStream {
+    quote_use! {
+        # use core::default::Default;
+        # use predawn::openapi::{Schema, ObjectType, SchemaKind, Type};
+
+        {
+            let mut ty = ObjectType::default();
+
+            #properties
+
+            Schema {
+                schema_data: Default::default(),
+                schema_kind: SchemaKind::Type(Type::Object(ty)),
+            }
+        }
+    }
+}
+
+fn string_enum(value: &str) -> TokenStream {
+    quote_use! {
+        # use core::default::Default;
+        # use std::vec;
+        # use std::string::ToString;
+        # use predawn::openapi::{Schema, SchemaKind, StringType, Type};
+
+        Schema {
+            schema_data: Default::default(),
+            schema_kind: SchemaKind::Type(Type::String(StringType {
+                enumeration: vec![Some(ToString::to_string(#value))],
+                ..Default::default()
+            })),
+        }
+    }
+}
+
+fn string_enum_box(value: &str) -> TokenStream {
+    let schema = string_enum(value);
+
+    quote_use! {
+        # use std::boxed::Box;
+        # use predawn::openapi::ReferenceOr;
+
+        ReferenceOr::Item(Box::new(#schema))
+    }
+}
+
+fn generate_single_field(
+    field: Field,
+    rules: FieldRules,
+    aliases: &mut Vec<(String, Vec<String>)>,
+) -> syn::Result<TokenStream> {
     let Field {
         attrs, ident, ty, ..
     } = field;
 
-    let SerdeAttr { rename } = SerdeAttr::new(&attrs)?;
+    let serde_attr = SerdeAttr::new(&attrs)?;
 
-    let ident = rename.unwrap_or_else(|| {
-        ident
-            .expect("unreachable: named field must have an identifier")
-            .to_string()
-    });
+    if serde_attr.skip {
+        return Ok(TokenStream::new());
+    }
+
+    let optional = rules.default || serde_attr.optional();
+
+    if serde_attr.flatten {
+        let required = if optional {
+            quote!(false)
+        } else {
+            quote_use! {
+                # use predawn::ToSchema;
+
+                <#ty as ToSchema>::REQUIRED
+            }
+        };
+
+        let expand = quote_use! {
+            # use predawn::ToSchema;
+            # use predawn::openapi::flatten_schema;
+
+            flatten_schema(
+                &mut ty,
+                &mut flattened,
+                <#ty as ToSchema>::schema(schemas),
+                #required,
+            );
+        };
+
+        return Ok(expand);
+    }
+
+    let ident = ident.expect("unreachable: named field must have an identifier");
+
+    // the schema has the serialized name, the deserialized one is accepted as an alias
+    let deserialized = serde_attr.field_name(&ident, &rules.rename_all, Direction::Deserialize);
+    let ident = serde_attr.field_name(&ident, &rules.rename_all, Direction::Serialize);
+
+    let mut field_aliases = serde_attr.alias;
+
+    if deserialized != ident {
+        field_aliases.insert(0, deserialized);
+    }
+
+    if !field_aliases.is_empty() {
+        aliases.push((ident.clone(), field_aliases));
+    }
 
     let description = util::extract_description(&attrs);
     let add_description = util::generate_optional_lit_str(&description).map(|description| {
@@ -97,7 +762,9 @@ fn generate_single_field(field: Field) -> syn::Result<TokenStream> {
         }
     });
 
-    let generate_schema = if add_description.is_none() {
+    let apply_schema_attr = SchemaAttr::new(&attrs)?.generate(ty.span())?;
+
+    let generate_schema = if add_description.is_none() && apply_schema_attr.is_none() {
         quote_use! {
             # use predawn::ToSchema;
 
@@ -107,7 +774,6 @@ fn generate_single_field(field: Field) -> syn::Result<TokenStream> {
         let create = quote_use! {
             # use predawn::ToSchema;
 
-            // TODO: add default, example, flatten etc.
             let mut schema = <#ty as ToSchema>::schema(schemas);
         };
 
@@ -121,22 +787,31 @@ fn generate_single_field(field: Field) -> syn::Result<TokenStream> {
         quote! {{
             #create
             #add_description
+            #apply_schema_attr
             #finish
         }}
     };
 
+    let push_required = (!optional).then(|| {
+        quote_use! {
+            # use std::string::ToString;
+            # use predawn::ToSchema;
+
+            if <#ty as ToSchema>::REQUIRED {
+                ty.required.push(ToString::to_string(#ident));
+            }
+        }
+    });
+
     let expand = quote_use! {
         # use std::string::ToString;
-        # use predawn::ToSchema;
 
         let schema = #generate_schema;
 
         ty.properties
             .insert(ToString::to_string(#ident), schema);
 
-        if <#ty as ToSchema>::REQUIRED {
-            ty.required.push(ToString::to_string(#ident));
-        }
+        #push_required
     };
 
     Ok(expand)
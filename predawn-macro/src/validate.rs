@@ -0,0 +1,150 @@
+use from_attr::{AttrsValue, FromAttr};
+use proc_macro2::TokenStream;
+use quote::quote;
+use quote_use::quote_use;
+use syn::{Attribute, DeriveInput, Expr, Field};
+
+use crate::{
+    schema_attr::SchemaAttr,
+    serde_attr::{Direction, SerdeAttr},
+    util,
+};
+
+#[derive(FromAttr, Default)]
+#[attribute(idents = [validate])]
+struct ValidateAttr {
+    custom: Option<Expr>,
+    nested: bool,
+}
+
+impl ValidateAttr {
+    fn new(attrs: &[Attribute]) -> syn::Result<Self> {
+        match Self::from_attributes(attrs) {
+            Ok(Some(AttrsValue { value, .. })) => Ok(value),
+            Ok(None) => Ok(Default::default()),
+            Err(AttrsValue { value: e, .. }) => Err(e),
+        }
+    }
+}
+
+pub(crate) fn generate(input: DeriveInput) -> syn::Result<TokenStream> {
+    let DeriveInput {
+        attrs,
+        ident,
+        generics,
+        data,
+        ..
+    } = input;
+
+    let container = SerdeAttr::new(&attrs)?;
+    let ValidateAttr { custom, nested } = ValidateAttr::new(&attrs)?;
+
+    if nested {
+        return Err(syn::Error::new(
+            ident.span(),
+            "`nested` can only be used on fields",
+        ));
+    }
+
+    let named = util::extract_named_struct_fields(data, "Validate")?;
+
+    let mut field_impls = Vec::new();
+    let mut errors = Vec::new();
+
+    named
+        .into_iter()
+        .for_each(|field| match generate_single_field(field, &container) {
+            Ok(field_impl) => field_impls.push(field_impl),
+            Err(e) => errors.push(e),
+        });
+
+    if let Some(e) = errors.into_iter().reduce(|mut a, b| {
+        a.combine(b);
+        a
+    }) {
+        return Err(e);
+    }
+
+    let custom = custom.map(|custom| generate_custom(&custom, quote!(self)));
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let expand = quote_use! {
+        # use predawn::validate::{Validate, ValidationErrors};
+
+        impl #impl_generics Validate for #ident #ty_generics #where_clause {
+            #[allow(unused_variables)]
+            fn validate(&self, errors: &mut ValidationErrors) {
+                #(#field_impls)*
+                #custom
+            }
+        }
+    };
+
+    Ok(expand)
+}
+
+fn generate_single_field(field: Field, container: &SerdeAttr) -> syn::Result<TokenStream> {
+    let Field { attrs, ident, .. } = field;
+
+    let ident = ident.expect("named fields must have an ident");
+
+    let serde_attr = SerdeAttr::new(&attrs)?;
+
+    if serde_attr.skip || serde_attr.skip_deserializing {
+        return Ok(TokenStream::new());
+    }
+
+    let ValidateAttr { custom, nested } = ValidateAttr::new(&attrs)?;
+    let constraints = SchemaAttr::new(&attrs)?.generate_constraints();
+
+    let mut checks = Vec::new();
+
+    if let Some(constraints) = constraints {
+        checks.push(quote_use! {
+            # use predawn::validate::Constrained;
+
+            Constrained::check(&self.#ident, &#constraints, errors);
+        });
+    }
+
+    if let Some(custom) = custom {
+        checks.push(generate_custom(&custom, quote!(&self.#ident)));
+    }
+
+    if nested || serde_attr.flatten {
+        checks.push(quote_use! {
+            # use predawn::validate::Validate;
+
+            Validate::validate(&self.#ident, errors);
+        });
+    }
+
+    if checks.is_empty() {
+        return Ok(TokenStream::new());
+    }
+
+    if serde_attr.flatten {
+        return Ok(quote! { #(#checks)* });
+    }
+
+    let name = serde_attr.field_name(&ident, &container.rename_all, Direction::Deserialize);
+
+    let expand = quote! {
+        errors.field(#name, |errors| {
+            #(#checks)*
+        });
+    };
+
+    Ok(expand)
+}
+
+fn generate_custom(custom: &Expr, value: TokenStream) -> TokenStream {
+    quote_use! {
+        # use core::result::Result;
+
+        if let Result::Err(e) = (#custom)(#value) {
+            errors.add(e);
+        }
+    }
+}
@@ -10,6 +10,7 @@ mod seq;
 mod set;
 mod string;
 mod time;
+mod tuple;
 mod wrapper;
 
 use macro_v::macro_v;
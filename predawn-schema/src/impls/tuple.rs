@@ -0,0 +1,93 @@
+use std::collections::BTreeMap;
+
+use openapiv3::{ArrayType, ReferenceOr, Schema, SchemaData, SchemaKind, Type};
+
+use crate::ToSchema;
+
+/// OpenAPI 3.0 has no `prefixItems`, so the position of each item is kept in this extension,
+/// which is turned into `prefixItems` when an OpenAPI 3.1 document is generated.
+const PREFIX_ITEMS_EXTENSION: &str = "x-prefixItems";
+
+fn tuple_schema(title: String, prefix_items: Vec<ReferenceOr<Box<Schema>>>) -> Schema {
+    let mut items = Vec::<ReferenceOr<Box<Schema>>>::with_capacity(prefix_items.len());
+
+    for item in &prefix_items {
+        if !items.contains(item) {
+            items.push(item.clone());
+        }
+    }
+
+    let items = if items.len() == 1 {
+        items.pop()
+    } else {
+        let any_of = items
+            .into_iter()
+            .map(|item| match item {
+                ReferenceOr::Reference { reference } => ReferenceOr::Reference { reference },
+                ReferenceOr::Item(schema) => ReferenceOr::Item(*schema),
+            })
+            .collect();
+
+        Some(ReferenceOr::Item(Box::new(Schema {
+            schema_data: Default::default(),
+            schema_kind: SchemaKind::AnyOf { any_of },
+        })))
+    };
+
+    let ty = ArrayType {
+        items,
+        min_items: Some(prefix_items.len()),
+        max_items: Some(prefix_items.len()),
+        unique_items: false,
+    };
+
+    let mut schema_data = SchemaData {
+        title: Some(title),
+        ..Default::default()
+    };
+
+    schema_data.extensions.insert(
+        PREFIX_ITEMS_EXTENSION.to_string(),
+        serde_json::to_value(prefix_items).expect("failed to serialize schemas as JSON"),
+    );
+
+    Schema {
+        schema_data,
+        schema_kind: SchemaKind::Type(Type::Array(ty)),
+    }
+}
+
+macro_rules! tuple_impl {
+    ($($ty:ident)+) => {
+        impl<$($ty),+> ToSchema for ($($ty,)+)
+        where
+            $($ty: ToSchema),+
+        {
+            fn schema(schemas: &mut BTreeMap<String, Schema>) -> Schema {
+                let titles = [$(
+                    $ty::schema(schemas)
+                        .schema_data
+                        .title
+                        .unwrap_or_else(|| "Unknown".to_string())
+                ),+];
+
+                let title = format!("Tuple<{}>", titles.join(", "));
+
+                tuple_schema(title, vec![$($ty::schema_ref_box(schemas)),+])
+            }
+        }
+    };
+}
+
+tuple_impl!(T1);
+tuple_impl!(T1 T2);
+tuple_impl!(T1 T2 T3);
+tuple_impl!(T1 T2 T3 T4);
+tuple_impl!(T1 T2 T3 T4 T5);
+tuple_impl!(T1 T2 T3 T4 T5 T6);
+tuple_impl!(T1 T2 T3 T4 T5 T6 T7);
+tuple_impl!(T1 T2 T3 T4 T5 T6 T7 T8);
+tuple_impl!(T1 T2 T3 T4 T5 T6 T7 T8 T9);
+tuple_impl!(T1 T2 T3 T4 T5 T6 T7 T8 T9 T10);
+tuple_impl!(T1 T2 T3 T4 T5 T6 T7 T8 T9 T10 T11);
+tuple_impl!(T1 T2 T3 T4 T5 T6 T7 T8 T9 T10 T11 T12);
@@ -4,6 +4,7 @@ mod data_sources;
 mod error;
 mod function;
 mod middleware;
+mod session_store;
 mod transaction;
 
 pub(crate) const DEFAULT_DATA_SOURCE_NAME: &str = "default";
@@ -19,5 +20,6 @@ pub use self::{
     error::Error,
     function::{commit, current_txn, data_sources, default_txn, new_txn, rollback},
     middleware::{SeaOrmHandler, SeaOrmMiddleware},
+    session_store::SeaOrmSessionStore,
     transaction::Transaction,
 };
@@ -0,0 +1,183 @@
+use std::{
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use predawn::{
+    error::BoxError,
+    session::{SessionId, SessionRecord, SessionStore},
+};
+use sea_orm::{
+    sea_query::{Alias, ColumnDef, Expr, OnConflict, Query, Table},
+    ConnectionTrait, DatabaseConnection, DbErr,
+};
+
+const ID: &str = "id";
+const DATA: &str = "data";
+const CREATED_AT: &str = "created_at";
+const EXPIRES_AT: &str = "expires_at";
+
+/// Keeps the sessions in a table, whose `data` column is the JSON of the values and whose
+/// `created_at` and `expires_at` columns are Unix timestamps in seconds.
+///
+/// The table is created by [`SeaOrmSessionStore::create_table`], expired sessions are
+/// removed when they are loaded or by [`SeaOrmSessionStore::delete_expired`].
+#[derive(Debug, Clone)]
+pub struct SeaOrmSessionStore {
+    conn: DatabaseConnection,
+    table: Arc<str>,
+}
+
+impl SeaOrmSessionStore {
+    /// A store in the `sessions` table.
+    pub fn new(conn: DatabaseConnection) -> Self {
+        Self {
+            conn,
+            table: Arc::from("sessions"),
+        }
+    }
+
+    pub fn table_name<T: Into<Arc<str>>>(mut self, table: T) -> Self {
+        self.table = table.into();
+        self
+    }
+
+    fn table(&self) -> Alias {
+        Alias::new(&*self.table)
+    }
+
+    pub async fn create_table(&self) -> Result<(), DbErr> {
+        let stmt = Table::create()
+            .table(self.table())
+            .if_not_exists()
+            .col(
+                ColumnDef::new(Alias::new(ID))
+                    .string_len(64)
+                    .not_null()
+                    .primary_key(),
+            )
+            .col(ColumnDef::new(Alias::new(DATA)).text().not_null())
+            .col(
+                ColumnDef::new(Alias::new(CREATED_AT))
+                    .big_integer()
+                    .not_null(),
+            )
+            .col(
+                ColumnDef::new(Alias::new(EXPIRES_AT))
+                    .big_integer()
+                    .not_null(),
+            )
+            .to_owned();
+
+        let backend = self.conn.get_database_backend();
+        self.conn.execute(backend.build(&stmt)).await?;
+
+        Ok(())
+    }
+
+    /// Returns the number of the deleted sessions.
+    pub async fn delete_expired(&self) -> Result<u64, DbErr> {
+        let stmt = Query::delete()
+            .from_table(self.table())
+            .and_where(Expr::col(Alias::new(EXPIRES_AT)).lte(timestamp(SystemTime::now())))
+            .to_owned();
+
+        let backend = self.conn.get_database_backend();
+        let result = self.conn.execute(backend.build(&stmt)).await?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+fn timestamp(time: SystemTime) -> i64 {
+    time.duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs() as i64)
+}
+
+fn system_time(timestamp: i64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(timestamp.max(0) as u64)
+}
+
+impl SessionStore for SeaOrmSessionStore {
+    async fn load(&self, id: &SessionId) -> Result<Option<SessionRecord>, BoxError> {
+        let stmt = Query::select()
+            .columns([
+                Alias::new(DATA),
+                Alias::new(CREATED_AT),
+                Alias::new(EXPIRES_AT),
+            ])
+            .from(self.table())
+            .and_where(Expr::col(Alias::new(ID)).eq(id.as_str()))
+            .to_owned();
+
+        let backend = self.conn.get_database_backend();
+
+        let Some(row) = self.conn.query_one(backend.build(&stmt)).await? else {
+            return Ok(None);
+        };
+
+        let data = row.try_get::<String>("", DATA)?;
+        let created_at = row.try_get::<i64>("", CREATED_AT)?;
+        let expires_at = row.try_get::<i64>("", EXPIRES_AT)?;
+
+        let record = SessionRecord {
+            id: id.clone(),
+            data: serde_json::from_str(&data)?,
+            created_at: system_time(created_at),
+            expires_at: system_time(expires_at),
+        };
+
+        if record.is_expired() {
+            self.delete(id).await?;
+            return Ok(None);
+        }
+
+        Ok(Some(record))
+    }
+
+    async fn save(&self, record: &SessionRecord) -> Result<(), BoxError> {
+        let data = serde_json::to_string(&record.data)?;
+
+        let stmt = Query::insert()
+            .into_table(self.table())
+            .columns([
+                Alias::new(ID),
+                Alias::new(DATA),
+                Alias::new(CREATED_AT),
+                Alias::new(EXPIRES_AT),
+            ])
+            .values_panic([
+                record.id.as_str().into(),
+                data.into(),
+                timestamp(record.created_at).into(),
+                timestamp(record.expires_at).into(),
+            ])
+            .on_conflict(
+                OnConflict::column(Alias::new(ID))
+                    .update_columns([
+                        Alias::new(DATA),
+                        Alias::new(CREATED_AT),
+                        Alias::new(EXPIRES_AT),
+                    ])
+                    .to_owned(),
+            )
+            .to_owned();
+
+        let backend = self.conn.get_database_backend();
+        self.conn.execute(backend.build(&stmt)).await?;
+
+        Ok(())
+    }
+
+    async fn delete(&self, id: &SessionId) -> Result<(), BoxError> {
+        let stmt = Query::delete()
+            .from_table(self.table())
+            .and_where(Expr::col(Alias::new(ID)).eq(id.as_str()))
+            .to_owned();
+
+        let backend = self.conn.get_database_backend();
+        self.conn.execute(backend.build(&stmt)).await?;
+
+        Ok(())
+    }
+}
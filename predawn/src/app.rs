@@ -14,6 +14,7 @@ use tokio::net::TcpListener;
 use crate::{
     config::{logger::LoggerConfig, server::ServerConfig, Config},
     controller::Controller,
+    cookie::Key,
     environment::Environment,
     handler::{Handler, HandlerExt},
     plugin::Plugin,
@@ -63,6 +64,12 @@ pub trait Hooks {
         Default::default()
     }
 
+    /// Webhooks are emitted as `webhooks` in OpenAPI 3.1 and as the `x-webhooks` extension in 3.0.
+    fn openapi_webhooks(cx: &mut Context) -> BTreeMap<String, PathItem> {
+        let _cx = cx;
+        Default::default()
+    }
+
     fn after_routes(router: &Router) {
         let _router = router;
     }
@@ -132,6 +139,7 @@ pub async fn create_app<H: Hooks>(env: Environment) -> (Context, impl Handler) {
     let info = H::openapi_info(&mut cx);
     let servers = H::openapi_servers(&mut cx);
     let security = H::openapi_security_requirements(&mut cx);
+    let webhooks = H::openapi_webhooks(&mut cx);
 
     let schemas = schemas
         .into_iter()
@@ -139,6 +147,7 @@ pub async fn create_app<H: Hooks>(env: Environment) -> (Context, impl Handler) {
         .collect();
 
     let mut duplicate_endpoints = Vec::new();
+    let mut operation_ids: BTreeMap<String, Vec<(Method, String)>> = BTreeMap::new();
 
     let paths = paths
         .into_iter()
@@ -148,6 +157,13 @@ pub async fn create_app<H: Hooks>(env: Environment) -> (Context, impl Handler) {
             let mut path_item = PathItem::default();
 
             operations.into_iter().for_each(|(method, operation)| {
+                if let Some(operation_id) = &operation.operation_id {
+                    operation_ids
+                        .entry(operation_id.clone())
+                        .or_default()
+                        .push((method.clone(), path.clone()));
+                }
+
                 let duplicate = if method == Method::GET {
                     path_item.get.replace(operation)
                 } else if method == Method::PUT {
@@ -185,6 +201,15 @@ pub async fn create_app<H: Hooks>(env: Environment) -> (Context, impl Handler) {
         panic!("duplicate endpoints: {:?}", duplicate_endpoints);
     }
 
+    let duplicate_operation_ids = operation_ids
+        .into_iter()
+        .filter(|(_, endpoints)| endpoints.len() > 1)
+        .collect::<Vec<_>>();
+
+    if !duplicate_operation_ids.is_empty() {
+        panic!("duplicate operation ids: {:?}", duplicate_operation_ids);
+    }
+
     let mut tag_name_to_type_names: BTreeMap<_, Vec<_>> = BTreeMap::new();
 
     let tags = tags
@@ -281,6 +306,14 @@ pub async fn create_app<H: Hooks>(env: Environment) -> (Context, impl Handler) {
         extensions: Default::default(),
     };
 
+    let mut extensions = IndexMap::new();
+
+    if !webhooks.is_empty() {
+        let webhooks =
+            serde_json::to_value(webhooks).expect("failed to serialize OpenAPI webhooks");
+        extensions.insert("x-webhooks".to_string(), webhooks);
+    }
+
     let api = OpenAPI {
         openapi: "3.0.0".to_string(),
         info,
@@ -293,7 +326,7 @@ pub async fn create_app<H: Hooks>(env: Environment) -> (Context, impl Handler) {
         security,
         tags,
         external_docs: Default::default(),
-        extensions: Default::default(),
+        extensions,
     };
 
     cx.insert_singleton(api);
@@ -315,26 +348,49 @@ pub async fn create_app<H: Hooks>(env: Environment) -> (Context, impl Handler) {
     }
 
     for plugin in cx.resolve_by_type_async::<Arc<dyn Plugin>>().await {
+        let base_path = if plugin.is_application() {
+            root_path.clone()
+        } else {
+            full_non_application_root_path.clone()
+        };
+
         let (path, map) = plugin.create_route(&mut cx);
 
-        let path = full_non_application_root_path.clone().join(path);
+        let path = base_path.join(path);
 
         tracing::info!("registering plugin: {}", path);
 
-        let err_msg = format!("failed to insert path `{path}`");
+        // a catch-all parameter does not match an empty segment,
+        // so the parent path is registered with the same handlers
+        if let Some(parent) = path.strip_catch_all() {
+            if let Err(e) = router.insert(parent.clone(), MethodRouter::from(map.clone())) {
+                panic!("failed to register the plugin route `{parent}`: {e}");
+            }
+        }
 
-        if let Err(e) = router.insert(path, MethodRouter::from(map)) {
-            panic!("{}: {:?}", err_msg, e);
+        if let Err(e) = router.insert(path.clone(), MethodRouter::from(map)) {
+            panic!("failed to register the plugin route `{path}`: {e}");
         }
     }
 
     H::after_routes(&router);
 
+    let cookie_key = cx.resolve_option::<Key>();
+
     let (cx, router) = H::before_run(cx, router).await;
 
-    let router = router.before(move |mut req| async move {
-        req.head.body_limit = BodyLimit(request_body_limit);
-        Ok(req)
+    let router = router.before(move |mut req| {
+        let cookie_key = cookie_key.clone();
+
+        async move {
+            req.head.body_limit = BodyLimit(request_body_limit);
+
+            if let Some(key) = cookie_key {
+                req.head.extensions.insert(key);
+            }
+
+            Ok(req)
+        }
     });
 
     (cx, router)
@@ -0,0 +1,113 @@
+use std::{any::Any, future::Future, marker::PhantomData, sync::Arc};
+
+use futures_util::future::BoxFuture;
+use predawn_core::request::Head;
+
+use crate::{middleware::VerifiedClaims, SecurityScheme};
+
+/// Decides whether the principal of a request has the permissions required by
+/// `#[controller(requires = [..])]` and `#[handler(requires = [..])]`.
+///
+/// It is called before anything is extracted from the request,
+/// the request is rejected with `403 Forbidden` if it returns `false`.
+///
+/// Handlers with `requires` resolve a [`DynAuthorizer`] from the [`Context`](rudi::Context):
+///
+/// ```rust
+/// use predawn::{
+///     authorization::{Authorizer, DynAuthorizer},
+///     request::Head,
+/// };
+/// use rudi::Singleton;
+///
+/// #[derive(Clone)]
+/// #[Singleton(binds = [DynAuthorizer::new])]
+/// struct RoleAuthorizer;
+///
+/// impl Authorizer for RoleAuthorizer {
+///     async fn authorize(&self, head: &Head, permissions: &[&'static str]) -> bool {
+///         let roles = head
+///             .headers
+///             .get("x-roles")
+///             .and_then(|roles| roles.to_str().ok())
+///             .unwrap_or_default();
+///
+///         permissions
+///             .iter()
+///             .all(|permission| roles.split(',').any(|role| role.trim() == *permission))
+///     }
+/// }
+/// ```
+pub trait Authorizer: Send + Sync + 'static {
+    /// Whether the principal of the request has all of `permissions`.
+    fn authorize(
+        &self,
+        head: &Head,
+        permissions: &[&'static str],
+    ) -> impl Future<Output = bool> + Send;
+}
+
+#[derive(Clone)]
+pub struct DynAuthorizer {
+    inner: Arc<dyn Any + Send + Sync>,
+    #[allow(clippy::type_complexity)]
+    authorize: for<'a> fn(&'a DynAuthorizer, &'a Head, &'a [&'static str]) -> BoxFuture<'a, bool>,
+}
+
+impl DynAuthorizer {
+    pub fn new<A: Authorizer>(authorizer: A) -> Self {
+        Self {
+            inner: Arc::new(authorizer),
+            authorize: |this, head, permissions| {
+                let authorizer = this.inner.downcast_ref::<A>().unwrap();
+                Box::pin(authorizer.authorize(head, permissions))
+            },
+        }
+    }
+}
+
+impl Authorizer for DynAuthorizer {
+    async fn authorize(&self, head: &Head, permissions: &[&'static str]) -> bool {
+        (self.authorize)(self, head, permissions).await
+    }
+}
+
+/// Grants the permissions which are scopes of the token verified by
+/// [`JwtAuth<S>`](crate::middleware::JwtAuth), in its `scope` or `scp` claim.
+pub struct JwtScopeAuthorizer<S> {
+    _marker: PhantomData<fn() -> S>,
+}
+
+impl<S> JwtScopeAuthorizer<S> {
+    pub fn new() -> Self {
+        Self {
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<S> Default for JwtScopeAuthorizer<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S> Clone for JwtScopeAuthorizer<S> {
+    fn clone(&self) -> Self {
+        Self::new()
+    }
+}
+
+impl<S: SecurityScheme + 'static> Authorizer for JwtScopeAuthorizer<S> {
+    async fn authorize(&self, head: &Head, permissions: &[&'static str]) -> bool {
+        let Some(verified) = head.extensions.get::<VerifiedClaims<S>>() else {
+            return false;
+        };
+
+        let scopes = verified.scopes();
+
+        permissions
+            .iter()
+            .all(|permission| scopes.contains(permission))
+    }
+}
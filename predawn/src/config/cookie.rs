@@ -0,0 +1,25 @@
+use rudi::Singleton;
+use serde::{Deserialize, Serialize};
+
+use super::{Config, ConfigPrefix};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CookieConfig {
+    /// The master key of signed and private cookies, at least 32 bytes,
+    /// they cannot be extracted if it is not set.
+    pub key: Option<String>,
+}
+
+#[Singleton]
+impl From<&Config> for CookieConfig {
+    #[di]
+    #[track_caller]
+    fn from(#[di(ref)] config: &Config) -> Self {
+        config.get().expect("failed to load `CookieConfig`")
+    }
+}
+
+impl ConfigPrefix for CookieConfig {
+    const PREFIX: &'static str = "cookie";
+}
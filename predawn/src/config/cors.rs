@@ -0,0 +1,57 @@
+use rudi::Singleton;
+use serde::{Deserialize, Serialize};
+
+use super::{Config, ConfigPrefix};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CorsConfig {
+    /// The allowed origins, `*` allows any origin,
+    /// `*` in an origin matches any subdomain, e.g. `https://*.example.com`.
+    pub allow_origins: Vec<String>,
+    /// Regular expressions matching the allowed origins.
+    pub allow_origin_regex: Vec<String>,
+    /// The allowed methods, `*` allows the requested method.
+    #[serde(default = "default_any")]
+    pub allow_methods: Vec<String>,
+    /// The allowed request headers, `*` allows the requested headers.
+    #[serde(default = "default_any")]
+    pub allow_headers: Vec<String>,
+    /// Whether credentials (cookies, authorization headers) are allowed.
+    pub allow_credentials: bool,
+    /// How long in seconds the result of a preflight request may be cached.
+    pub max_age: Option<u64>,
+    /// The response headers exposed to scripts.
+    pub expose_headers: Vec<String>,
+}
+
+#[Singleton]
+impl From<&Config> for CorsConfig {
+    #[di]
+    #[track_caller]
+    fn from(#[di(ref)] config: &Config) -> Self {
+        config.get().expect("failed to load `CorsConfig`")
+    }
+}
+
+fn default_any() -> Vec<String> {
+    vec!["*".to_string()]
+}
+
+impl Default for CorsConfig {
+    fn default() -> Self {
+        Self {
+            allow_origins: Vec::new(),
+            allow_origin_regex: Vec::new(),
+            allow_methods: default_any(),
+            allow_headers: default_any(),
+            allow_credentials: false,
+            max_age: None,
+            expose_headers: Vec::new(),
+        }
+    }
+}
+
+impl ConfigPrefix for CorsConfig {
+    const PREFIX: &'static str = "cors";
+}
@@ -0,0 +1,79 @@
+use rudi::Singleton;
+use serde::{Deserialize, Serialize};
+
+use super::{Config, ConfigPrefix};
+use crate::cookie::SameSite;
+
+/// The settings of [`Csrf`](crate::middleware::Csrf).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CsrfConfig {
+    /// The name of the signed cookie with the token.
+    #[serde(default = "default_cookie_name")]
+    pub cookie_name: String,
+    #[serde(default = "default_cookie_path")]
+    pub cookie_path: String,
+    pub cookie_domain: Option<String>,
+    #[serde(default = "default_cookie_secure")]
+    pub cookie_secure: bool,
+    /// `strict`, `lax` or `none`.
+    #[serde(default = "default_cookie_same_site")]
+    pub cookie_same_site: SameSite,
+    /// The request header with the token.
+    #[serde(default = "default_header_name")]
+    pub header_name: String,
+    /// The field with the token in urlencoded and multipart forms.
+    #[serde(default = "default_form_field")]
+    pub form_field: String,
+}
+
+#[Singleton]
+impl From<&Config> for CsrfConfig {
+    #[di]
+    #[track_caller]
+    fn from(#[di(ref)] config: &Config) -> Self {
+        config.get().expect("failed to load `CsrfConfig`")
+    }
+}
+
+fn default_cookie_name() -> String {
+    "csrf".to_string()
+}
+
+fn default_cookie_path() -> String {
+    "/".to_string()
+}
+
+fn default_cookie_secure() -> bool {
+    true
+}
+
+fn default_cookie_same_site() -> SameSite {
+    SameSite::Lax
+}
+
+fn default_header_name() -> String {
+    "x-csrf-token".to_string()
+}
+
+fn default_form_field() -> String {
+    "csrf_token".to_string()
+}
+
+impl Default for CsrfConfig {
+    fn default() -> Self {
+        Self {
+            cookie_name: default_cookie_name(),
+            cookie_path: default_cookie_path(),
+            cookie_domain: None,
+            cookie_secure: default_cookie_secure(),
+            cookie_same_site: default_cookie_same_site(),
+            header_name: default_header_name(),
+            form_field: default_form_field(),
+        }
+    }
+}
+
+impl ConfigPrefix for CsrfConfig {
+    const PREFIX: &'static str = "csrf";
+}
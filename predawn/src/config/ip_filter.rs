@@ -0,0 +1,30 @@
+use rudi::Singleton;
+use serde::{Deserialize, Serialize};
+
+use super::{Config, ConfigPrefix};
+
+/// The lists of [`IpFilter`](crate::middleware::IpFilter), whose entries are IP ranges
+/// in CIDR notation, e.g. `10.0.0.0/8`, or single addresses.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IpFilterConfig {
+    /// The allowed clients, any client is allowed if it is empty.
+    pub allow: Vec<String>,
+    /// The denied clients, which are denied even if they are allowed.
+    pub deny: Vec<String>,
+    /// The proxies whose `X-Forwarded-For` header is trusted to carry the client IP.
+    pub trusted_proxies: Vec<String>,
+}
+
+#[Singleton]
+impl From<&Config> for IpFilterConfig {
+    #[di]
+    #[track_caller]
+    fn from(#[di(ref)] config: &Config) -> Self {
+        config.get().expect("failed to load `IpFilterConfig`")
+    }
+}
+
+impl ConfigPrefix for IpFilterConfig {
+    const PREFIX: &'static str = "ip_filter";
+}
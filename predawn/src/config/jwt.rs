@@ -0,0 +1,55 @@
+use std::path::PathBuf;
+
+use rudi::Singleton;
+use serde::{Deserialize, Serialize};
+
+use super::{Config, ConfigPrefix};
+use crate::middleware::Jwk;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JwtConfig {
+    /// The shared secret of `HS256` tokens.
+    pub secret: Option<String>,
+    /// Keys in the JSON Web Key format, e.g. `{ kty = "EC", crv = "P-256", x = "..", y = ".." }`.
+    pub keys: Vec<Jwk>,
+    /// A local JSON Web Key Set file, whose keys are added to `keys`.
+    pub jwks: Option<PathBuf>,
+    /// The expected `iss` claim, not checked if it is not set.
+    pub issuer: Option<String>,
+    /// The expected `aud` claim, a token must have one of them, not checked if it is empty.
+    pub audience: Vec<String>,
+    /// The leeway in seconds when checking `exp` and `nbf`.
+    #[serde(default = "default_leeway")]
+    pub leeway: u64,
+}
+
+#[Singleton]
+impl From<&Config> for JwtConfig {
+    #[di]
+    #[track_caller]
+    fn from(#[di(ref)] config: &Config) -> Self {
+        config.get().expect("failed to load `JwtConfig`")
+    }
+}
+
+fn default_leeway() -> u64 {
+    60
+}
+
+impl Default for JwtConfig {
+    fn default() -> Self {
+        Self {
+            secret: None,
+            keys: Vec::new(),
+            jwks: None,
+            issuer: None,
+            audience: Vec::new(),
+            leeway: default_leeway(),
+        }
+    }
+}
+
+impl ConfigPrefix for JwtConfig {
+    const PREFIX: &'static str = "jwt";
+}
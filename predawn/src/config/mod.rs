@@ -1,6 +1,15 @@
+pub mod cookie;
+pub mod cors;
+pub mod csrf;
+pub mod ip_filter;
+pub mod jwt;
 pub mod logger;
 pub mod openapi;
+pub mod rate_limit;
+pub mod security_headers;
 pub mod server;
+pub mod session;
+pub mod static_files;
 
 use std::{
     env,
@@ -2,23 +2,87 @@ use rudi::Singleton;
 use serde::{Deserialize, Serialize};
 
 use super::{Config, ConfigPrefix};
-use crate::normalized_path::NormalizedPath;
+use crate::{environment::Environment, normalized_path::NormalizedPath};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct OpenAPIConfig {
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    pub version: OpenAPIVersion,
+    /// The environments in which the OpenAPI routes are mounted,
+    /// `None` means all environments except `prod`.
+    pub environments: Option<Vec<Environment>>,
+    /// Protects the OpenAPI JSON and UI routes with HTTP basic authentication.
+    pub basic_auth: Option<BasicAuthConfig>,
     #[serde(default = "default_json_path")]
     pub json_path: NormalizedPath,
+    #[serde(default = "default_enabled")]
+    pub json_enabled: bool,
+    #[serde(default = "default_yaml_path")]
+    pub yaml_path: NormalizedPath,
+    #[serde(default = "default_enabled")]
+    pub yaml_enabled: bool,
+    /// Pretty-prints the JSON document.
+    pub pretty: bool,
     #[serde(default = "default_swagger_ui_path")]
     pub swagger_ui_path: NormalizedPath,
+    #[serde(default = "default_enabled")]
+    pub swagger_ui_enabled: bool,
     #[serde(default = "default_rapidoc_path")]
     pub rapidoc_path: NormalizedPath,
+    #[serde(default = "default_enabled")]
+    pub rapidoc_enabled: bool,
     #[serde(default = "default_scalar_path")]
     pub scalar_path: NormalizedPath,
+    #[serde(default = "default_enabled")]
+    pub scalar_enabled: bool,
     #[serde(default = "default_redoc_path")]
     pub redoc_path: NormalizedPath,
+    #[serde(default = "default_enabled")]
+    pub redoc_enabled: bool,
     #[serde(default = "default_openapi_explorer_path")]
     pub openapi_explorer_path: NormalizedPath,
+    #[serde(default = "default_enabled")]
+    pub openapi_explorer_enabled: bool,
+    pub ui_assets: UiAssets,
+    #[serde(default = "default_ui_assets_path")]
+    pub ui_assets_path: NormalizedPath,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OpenAPIVersion {
+    #[default]
+    #[serde(rename = "3.0")]
+    V3_0,
+    #[serde(rename = "3.1")]
+    V3_1,
+}
+
+/// Where the OpenAPI UI pages load their scripts and stylesheets from.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UiAssets {
+    /// Public CDNs, e.g. `unpkg.com`.
+    #[default]
+    Cdn,
+    /// Assets bundled into the binary, served under `ui_assets_path`,
+    /// requires the `embedded-ui` feature.
+    ///
+    /// Only Swagger UI is bundled, the other UIs are not registered, with a warning
+    /// if they are enabled. Load their assets from a custom base URL to serve them offline.
+    Embedded,
+    /// A custom base URL, which has the same layout as the embedded assets,
+    /// e.g. `<base>/swagger-ui/swagger-ui.css`, `<base>/rapidoc/rapidoc-min.js`.
+    Custom(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BasicAuthConfig {
+    pub username: String,
+    pub password: String,
+    #[serde(default = "default_realm")]
+    pub realm: String,
 }
 
 #[Singleton]
@@ -30,10 +94,34 @@ impl From<&Config> for OpenAPIConfig {
     }
 }
 
+impl OpenAPIConfig {
+    pub fn is_enabled(&self, env: &Environment) -> bool {
+        self.enabled
+            && self
+                .environments
+                .as_ref()
+                .map_or(*env != Environment::Prod, |environments| {
+                    environments.contains(env)
+                })
+    }
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_realm() -> String {
+    "OpenAPI".to_string()
+}
+
 fn default_json_path() -> NormalizedPath {
     "/openapi.json".into()
 }
 
+fn default_yaml_path() -> NormalizedPath {
+    "/openapi.yaml".into()
+}
+
 fn default_swagger_ui_path() -> NormalizedPath {
     "/swagger-ui".into()
 }
@@ -54,15 +142,34 @@ fn default_openapi_explorer_path() -> NormalizedPath {
     "/openapi-explorer".into()
 }
 
+fn default_ui_assets_path() -> NormalizedPath {
+    "/ui-assets".into()
+}
+
 impl Default for OpenAPIConfig {
     fn default() -> Self {
         Self {
+            enabled: default_enabled(),
+            version: Default::default(),
+            environments: None,
+            basic_auth: None,
             json_path: default_json_path(),
+            json_enabled: default_enabled(),
+            yaml_path: default_yaml_path(),
+            yaml_enabled: default_enabled(),
+            pretty: false,
             swagger_ui_path: default_swagger_ui_path(),
+            swagger_ui_enabled: default_enabled(),
             rapidoc_path: default_rapidoc_path(),
+            rapidoc_enabled: default_enabled(),
             scalar_path: default_scalar_path(),
+            scalar_enabled: default_enabled(),
             redoc_path: default_redoc_path(),
+            redoc_enabled: default_enabled(),
             openapi_explorer_path: default_openapi_explorer_path(),
+            openapi_explorer_enabled: default_enabled(),
+            ui_assets: Default::default(),
+            ui_assets_path: default_ui_assets_path(),
         }
     }
 }
@@ -0,0 +1,68 @@
+use rudi::Singleton;
+use serde::{Deserialize, Serialize};
+
+use super::{Config, ConfigPrefix};
+
+/// The settings of [`RateLimit`](crate::middleware::RateLimit).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RateLimitConfig {
+    /// The number of requests allowed in every `period`.
+    #[serde(default = "default_requests")]
+    pub requests: u32,
+    /// In seconds.
+    #[serde(default = "default_period")]
+    pub period: u64,
+    /// The number of requests allowed at once, `requests` by default.
+    pub burst: Option<u32>,
+    /// `remote_addr`, `header` or `route`.
+    pub key: RateLimitKey,
+    /// The request header whose value is the key, when `key` is `header`.
+    pub header_name: Option<String>,
+}
+
+/// What the requests are limited by, every key has its own limit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RateLimitKey {
+    /// The IP address of the client.
+    #[default]
+    RemoteAddr,
+    /// The value of a request header, e.g. an API key.
+    Header,
+    /// The route matched by the router, e.g. `/users/{id}`.
+    Route,
+}
+
+#[Singleton]
+impl From<&Config> for RateLimitConfig {
+    #[di]
+    #[track_caller]
+    fn from(#[di(ref)] config: &Config) -> Self {
+        config.get().expect("failed to load `RateLimitConfig`")
+    }
+}
+
+fn default_requests() -> u32 {
+    60
+}
+
+fn default_period() -> u64 {
+    60
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            requests: default_requests(),
+            period: default_period(),
+            burst: None,
+            key: Default::default(),
+            header_name: None,
+        }
+    }
+}
+
+impl ConfigPrefix for RateLimitConfig {
+    const PREFIX: &'static str = "rate_limit";
+}
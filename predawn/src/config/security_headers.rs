@@ -0,0 +1,82 @@
+use rudi::Singleton;
+use serde::{Deserialize, Serialize};
+
+use super::{Config, ConfigPrefix};
+
+/// The values of the headers set by [`SecurityHeaders`](crate::middleware::SecurityHeaders),
+/// an empty value disables the header.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SecurityHeadersConfig {
+    #[serde(default = "default_strict_transport_security")]
+    pub strict_transport_security: String,
+    /// `{nonce}` is replaced by a nonce which is generated for every request,
+    /// e.g. `script-src 'self' 'nonce-{nonce}'`.
+    #[serde(default = "default_content_security_policy")]
+    pub content_security_policy: String,
+    /// Whether the policy is sent as `Content-Security-Policy-Report-Only`.
+    pub content_security_policy_report_only: bool,
+    #[serde(default = "default_x_content_type_options")]
+    pub x_content_type_options: String,
+    #[serde(default = "default_x_frame_options")]
+    pub x_frame_options: String,
+    #[serde(default = "default_referrer_policy")]
+    pub referrer_policy: String,
+    #[serde(default = "default_permissions_policy")]
+    pub permissions_policy: String,
+}
+
+#[Singleton]
+impl From<&Config> for SecurityHeadersConfig {
+    #[di]
+    #[track_caller]
+    fn from(#[di(ref)] config: &Config) -> Self {
+        config
+            .get()
+            .expect("failed to load `SecurityHeadersConfig`")
+    }
+}
+
+fn default_strict_transport_security() -> String {
+    "max-age=31536000; includeSubDomains".to_string()
+}
+
+fn default_content_security_policy() -> String {
+    "default-src 'self'; script-src 'self' 'nonce-{nonce}'; style-src 'self' 'nonce-{nonce}'; \
+     object-src 'none'; base-uri 'self'; frame-ancestors 'none'"
+        .to_string()
+}
+
+fn default_x_content_type_options() -> String {
+    "nosniff".to_string()
+}
+
+fn default_x_frame_options() -> String {
+    "DENY".to_string()
+}
+
+fn default_referrer_policy() -> String {
+    "strict-origin-when-cross-origin".to_string()
+}
+
+fn default_permissions_policy() -> String {
+    "camera=(), microphone=(), geolocation=()".to_string()
+}
+
+impl Default for SecurityHeadersConfig {
+    fn default() -> Self {
+        Self {
+            strict_transport_security: default_strict_transport_security(),
+            content_security_policy: default_content_security_policy(),
+            content_security_policy_report_only: false,
+            x_content_type_options: default_x_content_type_options(),
+            x_frame_options: default_x_frame_options(),
+            referrer_policy: default_referrer_policy(),
+            permissions_policy: default_permissions_policy(),
+        }
+    }
+}
+
+impl ConfigPrefix for SecurityHeadersConfig {
+    const PREFIX: &'static str = "security_headers";
+}
@@ -0,0 +1,79 @@
+use rudi::Singleton;
+use serde::{Deserialize, Serialize};
+
+use super::{Config, ConfigPrefix};
+use crate::cookie::SameSite;
+
+/// The settings of [`SessionMiddleware`](crate::middleware::SessionMiddleware).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SessionConfig {
+    /// The name of the signed cookie with the session id.
+    #[serde(default = "default_cookie_name")]
+    pub cookie_name: String,
+    #[serde(default = "default_cookie_path")]
+    pub cookie_path: String,
+    pub cookie_domain: Option<String>,
+    #[serde(default = "default_cookie_secure")]
+    pub cookie_secure: bool,
+    /// `strict`, `lax` or `none`.
+    #[serde(default = "default_cookie_same_site")]
+    pub cookie_same_site: SameSite,
+    /// How long in seconds a session lives without being accessed.
+    #[serde(default = "default_idle_timeout")]
+    pub idle_timeout: u64,
+    /// How long in seconds a session lives at most since it was created.
+    #[serde(default = "default_absolute_timeout")]
+    pub absolute_timeout: u64,
+}
+
+#[Singleton]
+impl From<&Config> for SessionConfig {
+    #[di]
+    #[track_caller]
+    fn from(#[di(ref)] config: &Config) -> Self {
+        config.get().expect("failed to load `SessionConfig`")
+    }
+}
+
+fn default_cookie_name() -> String {
+    "session".to_string()
+}
+
+fn default_cookie_path() -> String {
+    "/".to_string()
+}
+
+fn default_cookie_secure() -> bool {
+    true
+}
+
+fn default_cookie_same_site() -> SameSite {
+    SameSite::Lax
+}
+
+fn default_idle_timeout() -> u64 {
+    30 * 60
+}
+
+fn default_absolute_timeout() -> u64 {
+    24 * 60 * 60
+}
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        Self {
+            cookie_name: default_cookie_name(),
+            cookie_path: default_cookie_path(),
+            cookie_domain: None,
+            cookie_secure: default_cookie_secure(),
+            cookie_same_site: default_cookie_same_site(),
+            idle_timeout: default_idle_timeout(),
+            absolute_timeout: default_absolute_timeout(),
+        }
+    }
+}
+
+impl ConfigPrefix for SessionConfig {
+    const PREFIX: &'static str = "session";
+}
@@ -0,0 +1,71 @@
+use std::path::PathBuf;
+
+use rudi::Singleton;
+use serde::{Deserialize, Serialize};
+
+use super::{Config, ConfigPrefix};
+use crate::normalized_path::NormalizedPath;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StaticFilesConfig {
+    /// The directory to serve, the plugin is not registered if it is not set.
+    pub dir: Option<PathBuf>,
+    /// The path the directory is served under, `/` serves it at the root, which conflicts
+    /// with a controller of `/`.
+    #[serde(default = "default_mount")]
+    pub mount: NormalizedPath,
+    /// Serves `index.html` for paths that do not match a file, for single page applications.
+    pub fallback: bool,
+    /// Looks for `.br` and `.gz` siblings of the requested file.
+    #[serde(default = "default_precompressed")]
+    pub precompressed: bool,
+    /// `Cache-Control` of regular files.
+    #[serde(default = "default_cache_control")]
+    pub cache_control: Option<String>,
+    /// `Cache-Control` of HTML files, including the `index.html` fallback.
+    #[serde(default = "default_html_cache_control")]
+    pub html_cache_control: Option<String>,
+}
+
+#[Singleton]
+impl From<&Config> for StaticFilesConfig {
+    #[di]
+    #[track_caller]
+    fn from(#[di(ref)] config: &Config) -> Self {
+        config.get().expect("failed to load `StaticFilesConfig`")
+    }
+}
+
+fn default_mount() -> NormalizedPath {
+    "/static".into()
+}
+
+fn default_precompressed() -> bool {
+    true
+}
+
+fn default_cache_control() -> Option<String> {
+    Some("public, max-age=3600".to_string())
+}
+
+fn default_html_cache_control() -> Option<String> {
+    Some("no-cache".to_string())
+}
+
+impl Default for StaticFilesConfig {
+    fn default() -> Self {
+        Self {
+            dir: None,
+            mount: default_mount(),
+            fallback: false,
+            precompressed: default_precompressed(),
+            cache_control: default_cache_control(),
+            html_cache_control: default_html_cache_control(),
+        }
+    }
+}
+
+impl ConfigPrefix for StaticFilesConfig {
+    const PREFIX: &'static str = "static";
+}
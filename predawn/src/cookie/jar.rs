@@ -0,0 +1,331 @@
+use std::{collections::BTreeMap, convert::Infallible};
+
+use http::{HeaderMap, StatusCode};
+use indexmap::IndexMap;
+use predawn_core::{
+    api_request::ApiRequestHead,
+    api_response::ApiResponse,
+    from_request::FromRequestHead,
+    into_response::IntoResponse,
+    openapi::{self, Parameter, Schema},
+    request::Head,
+    response::Response,
+};
+
+use super::{Key, SetCookie, WithCookies};
+use crate::{
+    extract::cookie::cookies,
+    response_error::{MissingCookieKeyError, SetCookieError},
+};
+
+/// The cookies of a request and the changes made to them.
+///
+/// The changes are emitted as `Set-Cookie` headers when the jar is returned,
+/// either alone or with [`CookieJar::response`].
+#[derive(Debug, Clone, Default)]
+pub struct CookieJar {
+    cookies: IndexMap<Box<str>, Box<str>>,
+    changes: Vec<SetCookie>,
+}
+
+impl CookieJar {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads the cookies of the `Cookie` headers, cookies which are not UTF-8 are ignored.
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let mut jar = Self::new();
+
+        for (name, value) in cookies(headers) {
+            let (Ok(name), Ok(value)) = (std::str::from_utf8(name), std::str::from_utf8(value))
+            else {
+                continue;
+            };
+
+            // the first cookie of the same name has the most specific path
+            jar.cookies.entry(name.into()).or_insert(value.into());
+        }
+
+        jar
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.cookies.get(name).map(|value| &**value)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.cookies.iter().map(|(name, value)| (&**name, &**value))
+    }
+
+    pub fn insert(mut self, cookie: SetCookie) -> Self {
+        self.cookies
+            .insert(cookie.name.clone(), cookie.value.clone());
+
+        self.changes.retain(|c| !c.is_replaced_by(&cookie));
+        self.changes.push(cookie);
+        self
+    }
+
+    /// Removes the cookie from the browser, its domain and path must be the same as
+    /// when it was added.
+    pub fn remove(mut self, cookie: SetCookie) -> Self {
+        self.cookies.shift_remove(cookie.name());
+
+        let cookie = cookie.into_removal();
+
+        self.changes.retain(|c| !c.is_replaced_by(&cookie));
+        self.changes.push(cookie);
+        self
+    }
+
+    /// The cookies added or removed, which are emitted as `Set-Cookie` headers.
+    pub fn changes(&self) -> &[SetCookie] {
+        &self.changes
+    }
+
+    /// Returns `data` with the changes as `Set-Cookie` headers.
+    pub fn response<T>(self, data: T) -> WithCookies<T> {
+        WithCookies {
+            data,
+            cookies: self.changes,
+        }
+    }
+}
+
+/// A [`CookieJar`] whose values are signed with the [`Key`], so they can be read
+/// but not tampered with by clients.
+///
+/// Cookies with an invalid signature are ignored.
+#[derive(Debug, Clone)]
+pub struct SignedCookieJar {
+    jar: CookieJar,
+    key: Key,
+}
+
+impl SignedCookieJar {
+    pub fn new(key: Key) -> Self {
+        Self {
+            jar: CookieJar::new(),
+            key,
+        }
+    }
+
+    pub fn from_headers(headers: &HeaderMap, key: Key) -> Self {
+        Self {
+            jar: CookieJar::from_headers(headers),
+            key,
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.key.verify(name, self.jar.get(name)?)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.jar
+            .iter()
+            .filter_map(|(name, value)| Some((name, self.key.verify(name, value)?)))
+    }
+
+    pub fn insert(mut self, cookie: SetCookie) -> Self {
+        let value = self.key.sign(cookie.name(), cookie.value());
+
+        self.jar = self.jar.insert(cookie.with_value(value));
+        self
+    }
+
+    /// Removes the cookie from the browser, its domain and path must be the same as
+    /// when it was added.
+    pub fn remove(mut self, cookie: SetCookie) -> Self {
+        self.jar = self.jar.remove(cookie);
+        self
+    }
+
+    /// The cookies added or removed with their signed values.
+    pub fn changes(&self) -> &[SetCookie] {
+        self.jar.changes()
+    }
+
+    /// Returns `data` with the changes as `Set-Cookie` headers.
+    pub fn response<T>(self, data: T) -> WithCookies<T> {
+        self.jar.response(data)
+    }
+}
+
+/// A [`CookieJar`] whose values are encrypted with the [`Key`], so they can be
+/// neither read nor tampered with by clients.
+///
+/// Cookies which cannot be decrypted are ignored.
+#[derive(Debug, Clone)]
+pub struct PrivateCookieJar {
+    jar: CookieJar,
+    key: Key,
+}
+
+impl PrivateCookieJar {
+    pub fn new(key: Key) -> Self {
+        Self {
+            jar: CookieJar::new(),
+            key,
+        }
+    }
+
+    pub fn from_headers(headers: &HeaderMap, key: Key) -> Self {
+        Self {
+            jar: CookieJar::from_headers(headers),
+            key,
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<String> {
+        self.key.decrypt(name, self.jar.get(name)?)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, String)> {
+        self.jar
+            .iter()
+            .filter_map(|(name, value)| Some((name, self.key.decrypt(name, value)?)))
+    }
+
+    pub fn insert(mut self, cookie: SetCookie) -> Self {
+        let value = self.key.encrypt(cookie.name(), cookie.value());
+
+        self.jar = self.jar.insert(cookie.with_value(value));
+        self
+    }
+
+    /// Removes the cookie from the browser, its domain and path must be the same as
+    /// when it was added.
+    pub fn remove(mut self, cookie: SetCookie) -> Self {
+        self.jar = self.jar.remove(cookie);
+        self
+    }
+
+    /// The cookies added or removed with their encrypted values.
+    pub fn changes(&self) -> &[SetCookie] {
+        self.jar.changes()
+    }
+
+    /// Returns `data` with the changes as `Set-Cookie` headers.
+    pub fn response<T>(self, data: T) -> WithCookies<T> {
+        self.jar.response(data)
+    }
+}
+
+impl<'a> FromRequestHead<'a> for CookieJar {
+    type Error = Infallible;
+
+    async fn from_request_head(head: &'a Head) -> Result<Self, Self::Error> {
+        Ok(CookieJar::from_headers(&head.headers))
+    }
+}
+
+impl<'a> FromRequestHead<'a> for SignedCookieJar {
+    type Error = MissingCookieKeyError;
+
+    async fn from_request_head(head: &'a Head) -> Result<Self, Self::Error> {
+        let key = head.extensions.get::<Key>().ok_or(MissingCookieKeyError)?;
+
+        Ok(SignedCookieJar::from_headers(&head.headers, key.clone()))
+    }
+}
+
+impl<'a> FromRequestHead<'a> for PrivateCookieJar {
+    type Error = MissingCookieKeyError;
+
+    async fn from_request_head(head: &'a Head) -> Result<Self, Self::Error> {
+        let key = head.extensions.get::<Key>().ok_or(MissingCookieKeyError)?;
+
+        Ok(PrivateCookieJar::from_headers(&head.headers, key.clone()))
+    }
+}
+
+macro_rules! impl_jar_response {
+    ($($ty:ty),+ $(,)?) => {
+        $(
+            impl ApiRequestHead for $ty {
+                fn parameters(_: &mut BTreeMap<String, Schema>) -> Option<Vec<Parameter>> {
+                    None
+                }
+            }
+
+            impl IntoResponse for $ty {
+                type Error = SetCookieError<Infallible>;
+
+                fn into_response(self) -> Result<Response, Self::Error> {
+                    self.response(()).into_response()
+                }
+            }
+
+            impl ApiResponse for $ty {
+                fn responses(
+                    schemas: &mut BTreeMap<String, Schema>,
+                ) -> Option<BTreeMap<StatusCode, openapi::Response>> {
+                    <WithCookies<()> as ApiResponse>::responses(schemas)
+                }
+            }
+        )+
+    };
+}
+
+impl_jar_response![CookieJar, SignedCookieJar, PrivateCookieJar];
+
+#[cfg(test)]
+mod tests {
+    use http::header::COOKIE;
+
+    use super::*;
+
+    fn headers(jar: &[SetCookie]) -> HeaderMap {
+        let cookie = jar
+            .iter()
+            .map(|c| format!("{}={}", c.name(), c.value()))
+            .collect::<Vec<_>>()
+            .join("; ");
+
+        let mut headers = HeaderMap::new();
+        headers.insert(COOKIE, cookie.try_into().unwrap());
+        headers
+    }
+
+    #[test]
+    fn signed() {
+        let key = Key::generate();
+
+        let jar = SignedCookieJar::new(key.clone()).insert(SetCookie::new("user", "alice"));
+        let value = jar.changes()[0].value().to_string();
+        assert!(value.ends_with("alice"));
+
+        let jar = SignedCookieJar::from_headers(&headers(jar.changes()), key.clone());
+        assert_eq!(jar.get("user"), Some("alice"));
+
+        let tampered = [SetCookie::new("user", value.replace("alice", "admin"))];
+        let jar = SignedCookieJar::from_headers(&headers(&tampered), key.clone());
+        assert_eq!(jar.get("user"), None);
+
+        let jar = SignedCookieJar::from_headers(&headers(&[SetCookie::new("user", "x")]), key);
+        assert_eq!(jar.get("user"), None);
+    }
+
+    #[test]
+    fn private() {
+        let key = Key::generate();
+
+        let jar = PrivateCookieJar::new(key.clone()).insert(SetCookie::new("user", "alice"));
+        let value = jar.changes()[0].value().to_string();
+        assert!(!value.contains("alice"));
+
+        let jar = PrivateCookieJar::from_headers(&headers(jar.changes()), key.clone());
+        assert_eq!(jar.get("user").as_deref(), Some("alice"));
+
+        // bound to the name of the cookie
+        let renamed = [SetCookie::new("admin", value.clone())];
+        let jar = PrivateCookieJar::from_headers(&headers(&renamed), key);
+        assert_eq!(jar.get("admin"), None);
+
+        let other = [SetCookie::new("user", value)];
+        let jar = PrivateCookieJar::from_headers(&headers(&other), Key::generate());
+        assert_eq!(jar.get("user"), None);
+    }
+}
@@ -0,0 +1,168 @@
+use std::{fmt, sync::Arc};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use ring::{
+    aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN},
+    hkdf::{Salt, HKDF_SHA256},
+    hmac::{self, HMAC_SHA256},
+    rand::{SecureRandom, SystemRandom},
+};
+use rudi::{Context, Singleton};
+
+use crate::config::{cookie::CookieConfig, Config};
+
+/// The minimum length in bytes of the master key.
+pub const MIN_KEY_LEN: usize = 32;
+
+/// The length of a base64 encoded `HMAC-SHA256` tag.
+const SIGNATURE_LEN: usize = 43;
+
+/// The key of [`SignedCookieJar`](super::SignedCookieJar) and
+/// [`PrivateCookieJar`](super::PrivateCookieJar).
+///
+/// The signing (`HMAC-SHA256`) and the encryption (`AES-256-GCM`) keys are derived from a
+/// master key with `HKDF-SHA256`. It is registered from `cookie.key` of the [`Config`],
+/// unless a `Key` is registered by the application.
+#[derive(Clone)]
+pub struct Key(Arc<KeyInner>);
+
+struct KeyInner {
+    signing: hmac::Key,
+    encryption: LessSafeKey,
+}
+
+impl Key {
+    /// Derives a key from `master`, which must be at least [`MIN_KEY_LEN`] bytes.
+    #[track_caller]
+    pub fn new(master: &[u8]) -> Self {
+        if master.len() < MIN_KEY_LEN {
+            panic!(
+                "the master key of cookies must be at least {} bytes, got {} bytes",
+                MIN_KEY_LEN,
+                master.len()
+            );
+        }
+
+        let prk = Salt::new(HKDF_SHA256, b"predawn-cookie").extract(master);
+
+        let signing = prk
+            .expand(&[b"signing"], HMAC_SHA256)
+            .expect("unreachable: the length is valid for HKDF-SHA256");
+
+        let encryption = prk
+            .expand(&[b"encryption"], &AES_256_GCM)
+            .expect("unreachable: the length is valid for HKDF-SHA256");
+
+        Self(Arc::new(KeyInner {
+            signing: hmac::Key::from(signing),
+            encryption: LessSafeKey::new(UnboundKey::from(encryption)),
+        }))
+    }
+
+    /// Generates a random key, cookies made with it are invalid after a restart.
+    pub fn generate() -> Self {
+        let mut master = [0u8; 64];
+
+        SystemRandom::new()
+            .fill(&mut master)
+            .expect("failed to generate a cookie key");
+
+        Self::new(&master)
+    }
+
+    /// Prefixes `value` with the signature of the cookie.
+    pub(crate) fn sign(&self, name: &str, value: &str) -> String {
+        let tag = hmac::sign(&self.0.signing, &signed_message(name, value));
+
+        let mut signed = URL_SAFE_NO_PAD.encode(tag.as_ref());
+        signed.push_str(value);
+        signed
+    }
+
+    /// Returns the value of a cookie made by [`Key::sign`] if the signature is valid.
+    pub(crate) fn verify<'a>(&self, name: &str, signed: &'a str) -> Option<&'a str> {
+        if !signed.is_char_boundary(SIGNATURE_LEN) {
+            return None;
+        }
+
+        let (tag, value) = signed.split_at(SIGNATURE_LEN);
+        let tag = URL_SAFE_NO_PAD.decode(tag).ok()?;
+
+        hmac::verify(&self.0.signing, &signed_message(name, value), &tag).ok()?;
+
+        Some(value)
+    }
+
+    /// Encrypts `value` with the name of the cookie as the associated data.
+    pub(crate) fn encrypt(&self, name: &str, value: &str) -> String {
+        let mut nonce = [0u8; NONCE_LEN];
+
+        SystemRandom::new()
+            .fill(&mut nonce)
+            .expect("failed to generate a cookie nonce");
+
+        let mut in_out = value.as_bytes().to_vec();
+
+        self.0
+            .encryption
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::from(name.as_bytes()),
+                &mut in_out,
+            )
+            .expect("failed to encrypt a cookie");
+
+        let mut sealed = Vec::with_capacity(NONCE_LEN + in_out.len());
+        sealed.extend_from_slice(&nonce);
+        sealed.extend_from_slice(&in_out);
+
+        URL_SAFE_NO_PAD.encode(sealed)
+    }
+
+    /// Returns the value of a cookie made by [`Key::encrypt`] if it is authentic.
+    pub(crate) fn decrypt(&self, name: &str, sealed: &str) -> Option<String> {
+        let sealed = URL_SAFE_NO_PAD.decode(sealed).ok()?;
+
+        if sealed.len() < NONCE_LEN + AES_256_GCM.tag_len() {
+            return None;
+        }
+
+        let (nonce, in_out) = sealed.split_at(NONCE_LEN);
+        let nonce = Nonce::try_assume_unique_for_key(nonce).ok()?;
+        let mut in_out = in_out.to_vec();
+
+        let value = self
+            .0
+            .encryption
+            .open_in_place(nonce, Aad::from(name.as_bytes()), &mut in_out)
+            .ok()?;
+
+        String::from_utf8(value.to_vec()).ok()
+    }
+}
+
+fn signed_message(name: &str, value: &str) -> Vec<u8> {
+    [name.as_bytes(), b"=", value.as_bytes()].concat()
+}
+
+impl fmt::Debug for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Key").finish_non_exhaustive()
+    }
+}
+
+fn condition(cx: &Context) -> bool {
+    !cx.contains_provider::<Key>()
+        && cx
+            .get_single_option::<Config>()
+            .is_some_and(|config| CookieConfig::from(config).key.is_some())
+}
+
+#[Singleton(condition = condition)]
+fn KeyRegister(#[di(ref)] config: &Config) -> Key {
+    let key = CookieConfig::from(config)
+        .key
+        .expect("unreachable: the condition checks that `cookie.key` is set");
+
+    Key::new(key.as_bytes())
+}
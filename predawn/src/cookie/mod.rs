@@ -0,0 +1,316 @@
+//! Cookies read from the `Cookie` headers of requests and written as `Set-Cookie` headers
+//! of responses.
+//!
+//! A jar is extracted by a handler, changed with [`CookieJar::insert`] and [`CookieJar::remove`],
+//! and returned with [`CookieJar::response`], which emits a `Set-Cookie` header for every
+//! change. [`SignedCookieJar`] and [`PrivateCookieJar`] sign and encrypt the values of the
+//! cookies with the [`Key`] registered from `cookie.key` of the [`Config`](crate::config::Config).
+
+mod jar;
+mod key;
+
+use std::{
+    collections::BTreeMap,
+    fmt,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use http::{header::SET_COOKIE, HeaderValue, StatusCode};
+use predawn_core::{
+    api_response::ApiResponse,
+    into_response::IntoResponse,
+    openapi::{self, Header, ParameterSchemaOrContent, ReferenceOr, Schema},
+    response::Response,
+};
+use predawn_schema::ToSchema;
+use serde::{Deserialize, Serialize};
+
+pub use self::{
+    jar::{CookieJar, PrivateCookieJar, SignedCookieJar},
+    key::{Key, MIN_KEY_LEN},
+};
+use crate::response_error::SetCookieError;
+
+/// The `SameSite` attribute of a cookie.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SameSite {
+    Strict,
+    Lax,
+    /// A cookie with `SameSite=None` is always `Secure`, otherwise browsers reject it.
+    None,
+}
+
+impl SameSite {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SameSite::Strict => "Strict",
+            SameSite::Lax => "Lax",
+            SameSite::None => "None",
+        }
+    }
+}
+
+impl fmt::Display for SameSite {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A cookie with the attributes of its `Set-Cookie` header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SetCookie {
+    name: Box<str>,
+    value: Box<str>,
+    max_age: Option<Duration>,
+    expires: Option<SystemTime>,
+    domain: Option<Box<str>>,
+    path: Option<Box<str>>,
+    secure: bool,
+    http_only: bool,
+    same_site: Option<SameSite>,
+}
+
+impl SetCookie {
+    pub fn new<N, V>(name: N, value: V) -> Self
+    where
+        N: Into<Box<str>>,
+        V: Into<Box<str>>,
+    {
+        Self {
+            name: name.into(),
+            value: value.into(),
+            max_age: None,
+            expires: None,
+            domain: None,
+            path: None,
+            secure: false,
+            http_only: false,
+            same_site: None,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    pub fn max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    pub fn expires(mut self, expires: SystemTime) -> Self {
+        self.expires = Some(expires);
+        self
+    }
+
+    pub fn domain<T: Into<Box<str>>>(mut self, domain: T) -> Self {
+        self.domain = Some(domain.into());
+        self
+    }
+
+    pub fn path<T: Into<Box<str>>>(mut self, path: T) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    pub fn secure(mut self, secure: bool) -> Self {
+        self.secure = secure;
+        self
+    }
+
+    pub fn http_only(mut self, http_only: bool) -> Self {
+        self.http_only = http_only;
+        self
+    }
+
+    pub fn same_site(mut self, same_site: SameSite) -> Self {
+        self.same_site = Some(same_site);
+        self
+    }
+
+    pub(crate) fn with_value(mut self, value: String) -> Self {
+        self.value = value.into();
+        self
+    }
+
+    /// Turns the cookie into one which removes it from the browser,
+    /// the domain and the path must be the same as when it was added.
+    pub(crate) fn into_removal(mut self) -> Self {
+        self.value = Box::from("");
+        self.max_age = Some(Duration::ZERO);
+        self.expires = Some(UNIX_EPOCH);
+        self
+    }
+
+    /// Whether `other` replaces this cookie in the browser.
+    pub(crate) fn is_replaced_by(&self, other: &SetCookie) -> bool {
+        self.name == other.name && self.domain == other.domain && self.path == other.path
+    }
+
+    pub(crate) fn is_valid(&self) -> bool {
+        let attribute = |value: &Option<Box<str>>| {
+            value
+                .as_deref()
+                .is_none_or(|value| value.bytes().all(is_attribute_octet))
+        };
+
+        !self.name.is_empty()
+            && self.name.bytes().all(is_token_octet)
+            && self.value.bytes().all(is_cookie_octet)
+            && attribute(&self.domain)
+            && attribute(&self.path)
+    }
+
+    pub(crate) fn to_header_value(&self) -> Option<HeaderValue> {
+        if !self.is_valid() {
+            return None;
+        }
+
+        HeaderValue::try_from(self.to_string()).ok()
+    }
+}
+
+impl fmt::Display for SetCookie {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}={}", self.name, self.value)?;
+
+        if let Some(max_age) = self.max_age {
+            write!(f, "; Max-Age={}", max_age.as_secs())?;
+        }
+
+        if let Some(expires) = self.expires {
+            write!(f, "; Expires={}", httpdate::fmt_http_date(expires))?;
+        }
+
+        if let Some(domain) = &self.domain {
+            write!(f, "; Domain={}", domain)?;
+        }
+
+        if let Some(path) = &self.path {
+            write!(f, "; Path={}", path)?;
+        }
+
+        if self.secure || self.same_site == Some(SameSite::None) {
+            f.write_str("; Secure")?;
+        }
+
+        if self.http_only {
+            f.write_str("; HttpOnly")?;
+        }
+
+        if let Some(same_site) = self.same_site {
+            write!(f, "; SameSite={}", same_site)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn is_token_octet(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b)
+}
+
+fn is_cookie_octet(b: u8) -> bool {
+    matches!(b, 0x21 | 0x23..=0x2B | 0x2D..=0x3A | 0x3C..=0x5B | 0x5D..=0x7E)
+}
+
+fn is_attribute_octet(b: u8) -> bool {
+    matches!(b, 0x20..=0x7E) && b != b';'
+}
+
+/// A response with the changes of a cookie jar as `Set-Cookie` headers,
+/// created by [`CookieJar::response`], [`SignedCookieJar::response`] and
+/// [`PrivateCookieJar::response`].
+#[derive(Debug)]
+pub struct WithCookies<T> {
+    data: T,
+    cookies: Vec<SetCookie>,
+}
+
+impl<T: IntoResponse> IntoResponse for WithCookies<T> {
+    type Error = SetCookieError<T::Error>;
+
+    fn into_response(self) -> Result<Response, Self::Error> {
+        let WithCookies { data, cookies } = self;
+
+        let mut response = data.into_response().map_err(SetCookieError::Inner)?;
+
+        let headers = response.headers_mut();
+
+        for cookie in cookies {
+            let Some(value) = cookie.to_header_value() else {
+                return Err(SetCookieError::InvalidCookie { name: cookie.name });
+            };
+
+            headers.append(SET_COOKIE, value);
+        }
+
+        Ok(response)
+    }
+}
+
+impl<T: ApiResponse> ApiResponse for WithCookies<T> {
+    fn responses(
+        schemas: &mut BTreeMap<String, Schema>,
+    ) -> Option<BTreeMap<StatusCode, openapi::Response>> {
+        let mut responses = T::responses(schemas)?;
+
+        for response in responses.values_mut() {
+            let header = Header {
+                description: Some("The cookies changed by the request.".to_string()),
+                style: Default::default(),
+                required: false,
+                deprecated: Default::default(),
+                format: ParameterSchemaOrContent::Schema(<String as ToSchema>::schema_ref(schemas)),
+                example: Default::default(),
+                examples: Default::default(),
+                extensions: Default::default(),
+            };
+
+            response
+                .headers
+                .insert(SET_COOKIE.to_string(), ReferenceOr::Item(header));
+        }
+
+        Some(responses)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_cookie() {
+        let cookie = SetCookie::new("id", "abc")
+            .max_age(Duration::from_secs(3600))
+            .domain("example.com")
+            .path("/")
+            .http_only(true)
+            .same_site(SameSite::None);
+
+        assert_eq!(
+            cookie.to_string(),
+            "id=abc; Max-Age=3600; Domain=example.com; Path=/; Secure; HttpOnly; SameSite=None"
+        );
+
+        assert_eq!(
+            cookie.clone().into_removal().to_string(),
+            "id=; Max-Age=0; Expires=Thu, 01 Jan 1970 00:00:00 GMT; Domain=example.com; Path=/; \
+             Secure; HttpOnly; SameSite=None"
+        );
+
+        assert!(cookie.to_header_value().is_some());
+        assert!(SetCookie::new("id", "a b").to_header_value().is_none());
+        assert!(SetCookie::new("i d", "ab").to_header_value().is_none());
+        assert!(SetCookie::new("id", "ab")
+            .path("/;a")
+            .to_header_value()
+            .is_none());
+    }
+}
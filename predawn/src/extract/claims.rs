@@ -0,0 +1,86 @@
+use std::{collections::BTreeMap, fmt, marker::PhantomData, ops::Deref};
+
+use http::HeaderValue;
+use predawn_core::{
+    api_request::{ApiRequestHead, SecuritySchemes},
+    from_request::FromRequestHead,
+    openapi::{Parameter, Schema, SecurityRequirement},
+    request::Head,
+};
+use serde::de::DeserializeOwned;
+
+use super::{is_bearer_scheme, register};
+use crate::{middleware::VerifiedClaims, response_error::ClaimsError, SecurityScheme};
+
+/// Extracts the claims of a token verified by [`JwtAuth<S>`](crate::middleware::JwtAuth),
+/// `S` is added to the security requirements of the operation.
+///
+/// The request is rejected with `401 Unauthorized` if it is not authenticated by `JwtAuth<S>`,
+/// and with `403 Forbidden` if the claims cannot be deserialized as `T`.
+pub struct Claims<T, S> {
+    claims: T,
+    _marker: PhantomData<fn() -> S>,
+}
+
+impl<T, S> Claims<T, S> {
+    pub fn into_inner(self) -> T {
+        self.claims
+    }
+}
+
+impl<T, S> Deref for Claims<T, S> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.claims
+    }
+}
+
+impl<T: fmt::Debug, S> fmt::Debug for Claims<T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Claims").field(&self.claims).finish()
+    }
+}
+
+impl<'a, T, S> FromRequestHead<'a> for Claims<T, S>
+where
+    T: DeserializeOwned,
+    S: SecurityScheme + 'static,
+{
+    type Error = ClaimsError;
+
+    async fn from_request_head(head: &'a Head) -> Result<Self, Self::Error> {
+        let Some(VerifiedClaims { claims, .. }) = head.extensions.get::<VerifiedClaims<S>>() else {
+            let challenge = format!("Bearer realm=\"{}\"", S::NAME);
+
+            return Err(ClaimsError::Missing {
+                challenge: HeaderValue::try_from(challenge)
+                    .expect("the name of a security scheme must be a valid header value"),
+            });
+        };
+
+        Ok(Self {
+            claims: T::deserialize(&**claims)?,
+            _marker: PhantomData,
+        })
+    }
+}
+
+impl<T, S: SecurityScheme> ApiRequestHead for Claims<T, S> {
+    fn parameters(_: &mut BTreeMap<String, Schema>) -> Option<Vec<Parameter>> {
+        None
+    }
+
+    fn security_requirements(
+        security_schemes: &mut SecuritySchemes,
+    ) -> Option<Vec<SecurityRequirement>> {
+        if !is_bearer_scheme::<S>() {
+            panic!(
+                "`Claims<_, {}>` requires an HTTP `bearer`, an OAuth2 or an OpenID Connect security scheme",
+                std::any::type_name::<S>()
+            );
+        }
+
+        Some(register::<S>(security_schemes))
+    }
+}
@@ -0,0 +1,72 @@
+use std::collections::BTreeMap;
+
+use http::{header::COOKIE, HeaderMap};
+use predawn_core::{
+    api_request::ApiRequestHead,
+    from_request::FromRequestHead,
+    impl_deref,
+    openapi::{Parameter, Schema},
+    request::Head,
+};
+use serde::de::DeserializeOwned;
+
+use crate::{response_error::CookieError, util, ToParameters};
+
+/// Deserializes the cookies of the `Cookie` headers into `T`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Cookie<T>(pub T);
+
+impl_deref!(Cookie);
+
+impl<'a, T> FromRequestHead<'a> for Cookie<T>
+where
+    T: DeserializeOwned,
+{
+    type Error = CookieError;
+
+    async fn from_request_head(head: &'a Head) -> Result<Self, Self::Error> {
+        let pairs = cookies(&head.headers);
+
+        match util::from_pairs(pairs) {
+            Ok(o) => Ok(Cookie(o)),
+            Err(e) => Err(CookieError(e)),
+        }
+    }
+}
+
+/// Splits the `Cookie` headers into the names and the values of the cookies.
+pub(crate) fn cookies(headers: &HeaderMap) -> impl Iterator<Item = (&[u8], &[u8])> {
+    headers
+        .get_all(COOKIE)
+        .into_iter()
+        .flat_map(|value| value.as_bytes().split(|b| *b == b';'))
+        .filter_map(|cookie| {
+            let cookie = cookie.trim_ascii();
+            let idx = cookie.iter().position(|b| *b == b'=')?;
+
+            let name = cookie[..idx].trim_ascii();
+            let value = cookie[idx + 1..].trim_ascii();
+
+            // a cookie value may be enclosed in double quotes
+            let value = value
+                .strip_prefix(b"\"")
+                .and_then(|value| value.strip_suffix(b"\""))
+                .unwrap_or(value);
+
+            Some((name, value))
+        })
+}
+
+impl<T: ToParameters> ApiRequestHead for Cookie<T> {
+    fn parameters(schemas: &mut BTreeMap<String, Schema>) -> Option<Vec<Parameter>> {
+        Some(
+            <T as ToParameters>::parameters(schemas)
+                .into_iter()
+                .map(|parameter_data| Parameter::Cookie {
+                    parameter_data,
+                    style: Default::default(),
+                })
+                .collect(),
+        )
+    }
+}
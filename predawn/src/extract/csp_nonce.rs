@@ -0,0 +1,52 @@
+use std::{collections::BTreeMap, fmt};
+
+use predawn_core::{
+    api_request::ApiRequestHead,
+    from_request::FromRequestHead,
+    openapi::{Parameter, Schema},
+    request::Head,
+};
+
+use crate::response_error::MissingCspNonceError;
+
+/// The nonce of the `Content-Security-Policy` of the current request, which is generated by
+/// [`SecurityHeaders`](crate::middleware::SecurityHeaders) if the policy contains `{nonce}`.
+///
+/// It is used in the `nonce` attribute of inline `<script>` and `<style>` elements.
+#[derive(Clone, PartialEq, Eq)]
+pub struct CspNonce(pub(crate) Box<str>);
+
+impl CspNonce {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Debug for CspNonce {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("CspNonce").field(&self.0).finish()
+    }
+}
+
+impl fmt::Display for CspNonce {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl<'a> FromRequestHead<'a> for CspNonce {
+    type Error = MissingCspNonceError;
+
+    async fn from_request_head(head: &'a Head) -> Result<Self, Self::Error> {
+        head.extensions
+            .get::<CspNonce>()
+            .cloned()
+            .ok_or(MissingCspNonceError)
+    }
+}
+
+impl ApiRequestHead for CspNonce {
+    fn parameters(_: &mut BTreeMap<String, Schema>) -> Option<Vec<Parameter>> {
+        None
+    }
+}
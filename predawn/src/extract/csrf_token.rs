@@ -0,0 +1,79 @@
+use std::{collections::BTreeMap, fmt, sync::Arc};
+
+use http::HeaderName;
+use predawn_core::{
+    api_request::ApiRequestHead,
+    from_request::FromRequestHead,
+    openapi::{Parameter, Schema},
+    request::Head,
+};
+
+use crate::response_error::MissingCsrfTokenError;
+
+/// The CSRF token of the current request, which is provided by
+/// [`Csrf`](crate::middleware::Csrf).
+///
+/// It is rendered into forms as a hidden field or sent by scripts in a header,
+/// it changes on every request but all of them stay valid as long as the cookie.
+#[derive(Clone)]
+pub struct CsrfToken {
+    pub(crate) token: Box<str>,
+    pub(crate) form_field: Arc<str>,
+    pub(crate) header_name: HeaderName,
+}
+
+impl CsrfToken {
+    pub fn as_str(&self) -> &str {
+        &self.token
+    }
+
+    /// The field of forms with the token.
+    pub fn form_field(&self) -> &str {
+        &self.form_field
+    }
+
+    /// The request header with the token.
+    pub fn header_name(&self) -> &HeaderName {
+        &self.header_name
+    }
+
+    /// A hidden `<input>` element with the token, to be rendered into a `<form>`.
+    pub fn hidden_input(&self) -> String {
+        format!(
+            r#"<input type="hidden" name="{}" value="{}">"#,
+            self.form_field, self.token
+        )
+    }
+}
+
+impl fmt::Debug for CsrfToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CsrfToken")
+            .field("form_field", &self.form_field)
+            .field("header_name", &self.header_name)
+            .finish_non_exhaustive()
+    }
+}
+
+impl fmt::Display for CsrfToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.token)
+    }
+}
+
+impl<'a> FromRequestHead<'a> for CsrfToken {
+    type Error = MissingCsrfTokenError;
+
+    async fn from_request_head(head: &'a Head) -> Result<Self, Self::Error> {
+        head.extensions
+            .get::<CsrfToken>()
+            .cloned()
+            .ok_or(MissingCsrfTokenError)
+    }
+}
+
+impl ApiRequestHead for CsrfToken {
+    fn parameters(_: &mut BTreeMap<String, Schema>) -> Option<Vec<Parameter>> {
+        None
+    }
+}
@@ -0,0 +1,54 @@
+use std::collections::BTreeMap;
+
+use predawn_core::{
+    api_request::ApiRequestHead,
+    from_request::FromRequestHead,
+    impl_deref,
+    openapi::{Parameter, Schema},
+    request::Head,
+};
+use serde::de::DeserializeOwned;
+
+use crate::{response_error::HeaderError, util, ToParameters};
+
+/// Deserializes the request headers into `T`.
+///
+/// Header names are lowercase, so the fields are usually renamed,
+/// e.g. `#[serde(rename_all = "kebab-case")]` matches `x_request_id` with `X-Request-Id`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Header<T>(pub T);
+
+impl_deref!(Header);
+
+impl<'a, T> FromRequestHead<'a> for Header<T>
+where
+    T: DeserializeOwned,
+{
+    type Error = HeaderError;
+
+    async fn from_request_head(head: &'a Head) -> Result<Self, Self::Error> {
+        let pairs = head
+            .headers
+            .iter()
+            .map(|(name, value)| (name.as_str().as_bytes(), value.as_bytes()));
+
+        match util::from_pairs(pairs) {
+            Ok(o) => Ok(Header(o)),
+            Err(e) => Err(HeaderError(e)),
+        }
+    }
+}
+
+impl<T: ToParameters> ApiRequestHead for Header<T> {
+    fn parameters(schemas: &mut BTreeMap<String, Schema>) -> Option<Vec<Parameter>> {
+        Some(
+            <T as ToParameters>::parameters(schemas)
+                .into_iter()
+                .map(|parameter_data| Parameter::Header {
+                    parameter_data,
+                    style: Default::default(),
+                })
+                .collect(),
+        )
+    }
+}
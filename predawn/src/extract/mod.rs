@@ -1,5 +1,25 @@
+mod claims;
+pub(crate) mod cookie;
+mod csp_nonce;
+mod csrf_token;
+mod header;
 pub mod multipart;
 mod path;
 mod query;
+mod security;
+mod typed_header;
+mod valid;
 
-pub use self::{path::Path, query::Query};
+pub(crate) use self::security::{authorization, is_bearer_scheme, register};
+pub use self::{
+    claims::Claims,
+    cookie::Cookie,
+    csp_nonce::CspNonce,
+    csrf_token::CsrfToken,
+    header::Header,
+    path::Path,
+    query::Query,
+    security::{ApiKey, Basic, Bearer},
+    typed_header::TypedHeader,
+    valid::Valid,
+};
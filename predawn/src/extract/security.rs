@@ -0,0 +1,303 @@
+use std::{any::type_name, collections::BTreeMap, fmt, marker::PhantomData};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use http::{header::AUTHORIZATION, HeaderValue};
+use percent_encoding::percent_decode;
+use predawn_core::{
+    api_request::{ApiRequestHead, SecuritySchemes},
+    from_request::FromRequestHead,
+    openapi::{self, APIKeyLocation, Parameter, Schema, SecurityRequirement},
+    request::Head,
+};
+
+use super::cookie::cookies;
+use crate::{
+    response_error::UnauthorizedError,
+    typed_header::{Authorization, HeaderType},
+    SecurityScheme,
+};
+
+pub(crate) fn register<S: SecurityScheme>(
+    security_schemes: &mut SecuritySchemes,
+) -> Vec<SecurityRequirement> {
+    security_schemes
+        .entry(type_name::<S>())
+        .or_insert_with(|| (S::NAME, S::create()));
+
+    let mut security_requirement = SecurityRequirement::default();
+    security_requirement.insert(S::NAME.to_string(), Vec::new());
+
+    vec![security_requirement]
+}
+
+fn unauthorized(challenge: String) -> UnauthorizedError {
+    UnauthorizedError {
+        challenge: HeaderValue::try_from(challenge)
+            .expect("the name of a security scheme must be a valid header value"),
+    }
+}
+
+pub(crate) fn authorization(head: &Head, scheme: &str) -> Option<Authorization> {
+    let authorization = Authorization::decode(head.headers.get_all(AUTHORIZATION).iter())?;
+
+    authorization.is_scheme(scheme).then_some(authorization)
+}
+
+/// Whether `S` is an HTTP `bearer`, an OAuth2 or an OpenID Connect security scheme.
+pub(crate) fn is_bearer_scheme<S: SecurityScheme>() -> bool {
+    match S::create() {
+        openapi::SecurityScheme::HTTP { scheme, .. } => scheme.eq_ignore_ascii_case("bearer"),
+        openapi::SecurityScheme::OAuth2 { .. } | openapi::SecurityScheme::OpenIDConnect { .. } => {
+            true
+        }
+        openapi::SecurityScheme::APIKey { .. } => false,
+    }
+}
+
+fn http_scheme<S: SecurityScheme>() -> Option<String> {
+    match S::create() {
+        openapi::SecurityScheme::HTTP { scheme, .. } => Some(scheme),
+        _ => None,
+    }
+}
+
+/// Extracts the token of the `Authorization: Bearer <token>` header.
+///
+/// `S` must be an HTTP `bearer`, an OAuth2 or an OpenID Connect security scheme,
+/// which is added to the security requirements of the operation.
+pub struct Bearer<S> {
+    token: String,
+    _marker: PhantomData<fn() -> S>,
+}
+
+impl<S> Bearer<S> {
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+
+    pub fn into_token(self) -> String {
+        self.token
+    }
+}
+
+impl<S> fmt::Debug for Bearer<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Bearer").finish_non_exhaustive()
+    }
+}
+
+impl<'a, S: SecurityScheme> FromRequestHead<'a> for Bearer<S> {
+    type Error = UnauthorizedError;
+
+    async fn from_request_head(head: &'a Head) -> Result<Self, Self::Error> {
+        let Some(authorization) = authorization(head, "Bearer") else {
+            return Err(unauthorized(format!("Bearer realm=\"{}\"", S::NAME)));
+        };
+
+        if authorization.credentials().is_empty() {
+            return Err(unauthorized(format!(
+                "Bearer realm=\"{}\", error=\"invalid_request\"",
+                S::NAME
+            )));
+        }
+
+        Ok(Self {
+            token: authorization.credentials().to_string(),
+            _marker: PhantomData,
+        })
+    }
+}
+
+impl<S: SecurityScheme> ApiRequestHead for Bearer<S> {
+    fn parameters(_: &mut BTreeMap<String, Schema>) -> Option<Vec<Parameter>> {
+        None
+    }
+
+    fn security_requirements(
+        security_schemes: &mut SecuritySchemes,
+    ) -> Option<Vec<SecurityRequirement>> {
+        if !is_bearer_scheme::<S>() {
+            panic!(
+                "`Bearer<{}>` requires an HTTP `bearer`, an OAuth2 or an OpenID Connect security scheme",
+                type_name::<S>()
+            );
+        }
+
+        Some(register::<S>(security_schemes))
+    }
+}
+
+/// Extracts the user id and the password of the `Authorization: Basic <credentials>` header.
+///
+/// `S` must be an HTTP `basic` security scheme,
+/// which is added to the security requirements of the operation.
+pub struct Basic<S> {
+    username: String,
+    password: String,
+    _marker: PhantomData<fn() -> S>,
+}
+
+impl<S> Basic<S> {
+    pub fn username(&self) -> &str {
+        &self.username
+    }
+
+    pub fn password(&self) -> &str {
+        &self.password
+    }
+}
+
+impl<S> fmt::Debug for Basic<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Basic")
+            .field("username", &self.username)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'a, S: SecurityScheme> FromRequestHead<'a> for Basic<S> {
+    type Error = UnauthorizedError;
+
+    async fn from_request_head(head: &'a Head) -> Result<Self, Self::Error> {
+        let challenge = || unauthorized(format!("Basic realm=\"{}\", charset=\"UTF-8\"", S::NAME));
+
+        let authorization = authorization(head, "Basic").ok_or_else(challenge)?;
+
+        let decoded = STANDARD
+            .decode(authorization.credentials())
+            .ok()
+            .and_then(|decoded| String::from_utf8(decoded).ok())
+            .ok_or_else(challenge)?;
+
+        let (username, password) = decoded.split_once(':').ok_or_else(challenge)?;
+
+        Ok(Self {
+            username: username.to_string(),
+            password: password.to_string(),
+            _marker: PhantomData,
+        })
+    }
+}
+
+impl<S: SecurityScheme> ApiRequestHead for Basic<S> {
+    fn parameters(_: &mut BTreeMap<String, Schema>) -> Option<Vec<Parameter>> {
+        None
+    }
+
+    fn security_requirements(
+        security_schemes: &mut SecuritySchemes,
+    ) -> Option<Vec<SecurityRequirement>> {
+        if !http_scheme::<S>().is_some_and(|scheme| scheme.eq_ignore_ascii_case("basic")) {
+            panic!(
+                "`Basic<{}>` requires an HTTP `basic` security scheme",
+                type_name::<S>()
+            );
+        }
+
+        Some(register::<S>(security_schemes))
+    }
+}
+
+/// Extracts an API key from the header, the query parameter or the cookie
+/// declared by `#[api_key(in = .., name = ..)]`.
+///
+/// `S` must be an API key security scheme, which is added to the security requirements of the operation.
+pub struct ApiKey<S> {
+    key: String,
+    _marker: PhantomData<fn() -> S>,
+}
+
+impl<S> ApiKey<S> {
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub fn into_key(self) -> String {
+        self.key
+    }
+}
+
+impl<S> fmt::Debug for ApiKey<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ApiKey").finish_non_exhaustive()
+    }
+}
+
+fn api_key_location<S: SecurityScheme>() -> (APIKeyLocation, String) {
+    match S::create() {
+        openapi::SecurityScheme::APIKey { location, name, .. } => (location, name),
+        _ => panic!(
+            "`ApiKey<{}>` requires an API key security scheme",
+            type_name::<S>()
+        ),
+    }
+}
+
+impl<'a, S: SecurityScheme> FromRequestHead<'a> for ApiKey<S> {
+    type Error = UnauthorizedError;
+
+    async fn from_request_head(head: &'a Head) -> Result<Self, Self::Error> {
+        let (location, name) = api_key_location::<S>();
+
+        let key = match location {
+            APIKeyLocation::Header => head
+                .headers
+                .get(&name)
+                .and_then(|value| value.to_str().ok())
+                .map(ToString::to_string),
+            APIKeyLocation::Query => head.uri.query().and_then(|query| {
+                query.split('&').find_map(|pair| {
+                    let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+
+                    let decode = |s: &str| {
+                        let s = s.replace('+', " ");
+                        percent_decode(s.as_bytes())
+                            .decode_utf8()
+                            .ok()
+                            .map(|s| s.into_owned())
+                    };
+
+                    (decode(key)? == name).then(|| decode(value)).flatten()
+                })
+            }),
+            APIKeyLocation::Cookie => cookies(&head.headers).find_map(|(key, value)| {
+                (key == name.as_bytes())
+                    .then(|| String::from_utf8(value.to_vec()).ok())
+                    .flatten()
+            }),
+        };
+
+        match key {
+            Some(key) if !key.is_empty() => Ok(Self {
+                key,
+                _marker: PhantomData,
+            }),
+            _ => {
+                let location = match location {
+                    APIKeyLocation::Header => "header",
+                    APIKeyLocation::Query => "query",
+                    APIKeyLocation::Cookie => "cookie",
+                };
+
+                Err(unauthorized(format!(
+                    "ApiKey realm=\"{}\", in=\"{location}\", name=\"{name}\"",
+                    S::NAME
+                )))
+            }
+        }
+    }
+}
+
+impl<S: SecurityScheme> ApiRequestHead for ApiKey<S> {
+    fn parameters(_: &mut BTreeMap<String, Schema>) -> Option<Vec<Parameter>> {
+        None
+    }
+
+    fn security_requirements(
+        security_schemes: &mut SecuritySchemes,
+    ) -> Option<Vec<SecurityRequirement>> {
+        api_key_location::<S>();
+
+        Some(register::<S>(security_schemes))
+    }
+}
@@ -0,0 +1,76 @@
+use std::collections::BTreeMap;
+
+use http::header::InvalidHeaderValue;
+use predawn_core::{
+    api_request::ApiRequestHead,
+    from_request::FromRequestHead,
+    impl_deref,
+    openapi::{Parameter, ParameterData, ParameterSchemaOrContent, Schema},
+    request::Head,
+};
+use predawn_schema::ToSchema;
+
+use crate::{response::ToHeaderValue, response_error::TypedHeaderError, typed_header::HeaderType};
+
+/// Extracts a standard header, such as [`IfNoneMatch`](crate::typed_header::IfNoneMatch).
+///
+/// A missing or an invalid header is rejected with `400 Bad Request`,
+/// use `Option<TypedHeader<H>>` for an optional header.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TypedHeader<H>(pub H);
+
+impl_deref!(TypedHeader);
+
+impl<'a, H: HeaderType> FromRequestHead<'a> for TypedHeader<H> {
+    type Error = TypedHeaderError;
+
+    async fn from_request_head(head: &'a Head) -> Result<Self, Self::Error> {
+        let mut values = head.headers.get_all(H::NAME).iter().peekable();
+
+        if values.peek().is_none() {
+            return Err(TypedHeaderError::Missing { name: H::NAME });
+        }
+
+        match H::decode(values) {
+            Some(header) => Ok(TypedHeader(header)),
+            None => Err(TypedHeaderError::Invalid { name: H::NAME }),
+        }
+    }
+}
+
+impl<H: HeaderType> ApiRequestHead for TypedHeader<H> {
+    fn parameters(schemas: &mut BTreeMap<String, Schema>) -> Option<Vec<Parameter>> {
+        Some(vec![Parameter::Header {
+            parameter_data: ParameterData {
+                name: H::NAME.to_string(),
+                description: None,
+                required: true,
+                deprecated: None,
+                format: ParameterSchemaOrContent::Schema(H::schema_ref(schemas)),
+                example: None,
+                examples: Default::default(),
+                explode: None,
+                extensions: Default::default(),
+            },
+            style: Default::default(),
+        }])
+    }
+}
+
+impl<H: ToSchema> ToSchema for TypedHeader<H> {
+    const REQUIRED: bool = H::REQUIRED;
+
+    fn name() -> String {
+        H::name()
+    }
+
+    fn schema(schemas: &mut BTreeMap<String, Schema>) -> Schema {
+        H::schema(schemas)
+    }
+}
+
+impl<H: HeaderType + std::fmt::Debug> ToHeaderValue for TypedHeader<H> {
+    fn to_header_value(&self) -> Option<Result<http::HeaderValue, InvalidHeaderValue>> {
+        Some(Ok(self.0.encode()))
+    }
+}
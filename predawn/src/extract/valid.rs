@@ -0,0 +1,96 @@
+use std::collections::BTreeMap;
+
+use futures_util::FutureExt;
+use predawn_core::{
+    api_request::{ApiRequest, ApiRequestHead, SecuritySchemes},
+    body::RequestBody,
+    from_request::{FromRequest, FromRequestHead},
+    impl_deref,
+    openapi::{self, Parameter, Schema, SecurityRequirement},
+    request::Head,
+};
+
+use crate::{
+    response_error::ValidError,
+    validate::{Validate, ValidationErrors},
+};
+
+/// Extracts `T` and then validates it, rejecting the request with `422 Unprocessable Entity`
+/// which lists the path of every invalid field.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Valid<T>(pub T);
+
+impl_deref!(Valid);
+
+fn validate<T: Validate, E>(value: T) -> Result<Valid<T>, ValidError<E>> {
+    let mut errors = ValidationErrors::new();
+    value.validate(&mut errors);
+
+    if errors.is_empty() {
+        Ok(Valid(value))
+    } else {
+        Err(ValidError::Invalid(errors))
+    }
+}
+
+impl<'a, T> FromRequestHead<'a> for Valid<T>
+where
+    T: FromRequestHead<'a> + Validate,
+{
+    type Error = ValidError<T::Error>;
+
+    async fn from_request_head(head: &'a Head) -> Result<Self, Self::Error> {
+        // TODO: remove boxed when https://github.com/rust-lang/rust/issues/100013 is resolved
+        let value = T::from_request_head(head)
+            .boxed()
+            .await
+            .map_err(ValidError::Inner)?;
+
+        validate(value)
+    }
+}
+
+impl<'a, T> FromRequest<'a> for Valid<T>
+where
+    T: FromRequest<'a> + Validate,
+{
+    type Error = ValidError<T::Error>;
+
+    async fn from_request(head: &'a Head, body: RequestBody) -> Result<Self, Self::Error> {
+        // TODO: remove boxed when https://github.com/rust-lang/rust/issues/100013 is resolved
+        let value = T::from_request(head, body)
+            .boxed()
+            .await
+            .map_err(ValidError::Inner)?;
+
+        validate(value)
+    }
+}
+
+impl<T: ApiRequestHead> ApiRequestHead for Valid<T> {
+    fn parameters(schemas: &mut BTreeMap<String, Schema>) -> Option<Vec<Parameter>> {
+        T::parameters(schemas)
+    }
+
+    fn security_requirements(
+        security_schemes: &mut SecuritySchemes,
+    ) -> Option<Vec<SecurityRequirement>> {
+        T::security_requirements(security_schemes)
+    }
+}
+
+impl<T: ApiRequest> ApiRequest for Valid<T> {
+    fn parameters(schemas: &mut BTreeMap<String, Schema>) -> Option<Vec<Parameter>> {
+        T::parameters(schemas)
+    }
+
+    fn request_body(schemas: &mut BTreeMap<String, Schema>) -> Option<openapi::RequestBody> {
+        T::request_body(schemas)
+    }
+
+    fn security_requirements(
+        security_schemes: &mut SecuritySchemes,
+    ) -> Option<Vec<SecurityRequirement>> {
+        T::security_requirements(security_schemes)
+    }
+}
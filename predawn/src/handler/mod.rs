@@ -41,6 +41,19 @@ impl DynHandler {
     }
 }
 
+impl DynHandler {
+    /// Whether the erased handler, or the one erased by a nested [`DynHandler`], is a `T`.
+    pub(crate) fn is<T: Any>(&self) -> bool {
+        if self.inner.is::<T>() {
+            return true;
+        }
+
+        self.inner
+            .downcast_ref::<DynHandler>()
+            .is_some_and(DynHandler::is::<T>)
+    }
+}
+
 impl Handler for DynHandler {
     async fn call(&self, req: Request) -> Result<Response, Error> {
         (self.call)(self, req).await
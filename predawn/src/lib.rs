@@ -3,9 +3,11 @@
 extern crate self as predawn;
 
 pub mod app;
+pub mod authorization;
 pub mod config;
 #[doc(hidden)]
 pub mod controller;
+pub mod cookie;
 pub mod environment;
 pub mod extract;
 pub mod handler;
@@ -21,9 +23,12 @@ pub mod response;
 pub mod response_error;
 pub mod route;
 pub mod server;
+pub mod session;
 pub mod test_client;
 mod traits;
+pub mod typed_header;
 pub(crate) mod util;
+pub mod validate;
 
 pub use predawn_core::{
     api_request, api_response, body, either, error, from_request, into_response,
@@ -35,14 +40,17 @@ pub use predawn_core::{
 #[cfg(feature = "macro")]
 pub use predawn_macro::{
     controller, MultiRequestMediaType, MultiResponse, MultiResponseMediaType, SecurityScheme,
-    SingleResponse, Tag, ToParameters, ToSchema,
+    SingleResponse, Tag, ToParameters, ToSchema, Validate,
 };
 #[cfg_attr(docsrs, doc(cfg(feature = "schemars")))]
 #[cfg(feature = "schemars")]
 pub use predawn_schema::schemars_transform;
 pub use predawn_schema::ToSchema;
 
-pub use self::traits::{SecurityScheme, Tag, ToParameters};
+pub use self::{
+    traits::{SecurityScheme, Tag, ToParameters},
+    validate::Validate,
+};
 
 #[doc(hidden)]
 pub mod __internal {
@@ -50,4 +58,5 @@ pub mod __internal {
     pub use indexmap;
     pub use paste;
     pub use rudi;
+    pub use serde_json;
 }
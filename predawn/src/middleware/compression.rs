@@ -0,0 +1,602 @@
+use std::{fmt, io, sync::Arc};
+
+use async_compression::{
+    tokio::bufread::{
+        BrotliDecoder, BrotliEncoder, DeflateDecoder, DeflateEncoder, GzipDecoder, GzipEncoder,
+        ZstdDecoder, ZstdEncoder,
+    },
+    Level,
+};
+use futures_util::TryStreamExt;
+use http::{
+    header::{
+        ACCEPT_ENCODING, ACCEPT_RANGES, CACHE_CONTROL, CONTENT_ENCODING, CONTENT_LENGTH,
+        CONTENT_RANGE, CONTENT_TYPE, VARY,
+    },
+    HeaderMap, HeaderValue, StatusCode,
+};
+use hyper::body::Body;
+use predawn_core::{
+    body::{DataStream, IncomingBody, ResponseBody},
+    error::Error,
+    request::Request,
+    response::Response,
+};
+use tokio_util::io::{ReaderStream, StreamReader};
+
+use super::Middleware;
+use crate::{handler::Handler, response_error::UnsupportedContentEncodingError, util};
+
+/// The content codings which are supported by [`Compression`] and [`Decompression`],
+/// in the order of preference.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Encoding {
+    Zstd,
+    Br,
+    Gzip,
+    Deflate,
+}
+
+impl Encoding {
+    const ALL: [Encoding; 4] = [
+        Encoding::Zstd,
+        Encoding::Br,
+        Encoding::Gzip,
+        Encoding::Deflate,
+    ];
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Encoding::Zstd => "zstd",
+            Encoding::Br => "br",
+            Encoding::Gzip => "gzip",
+            Encoding::Deflate => "deflate",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|encoding| s.eq_ignore_ascii_case(encoding.as_str()))
+            .or_else(|| s.eq_ignore_ascii_case("x-gzip").then_some(Encoding::Gzip))
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Encodings {
+    zstd: bool,
+    br: bool,
+    gzip: bool,
+    deflate: bool,
+}
+
+impl Default for Encodings {
+    fn default() -> Self {
+        Self {
+            zstd: true,
+            br: true,
+            gzip: true,
+            deflate: true,
+        }
+    }
+}
+
+impl Encodings {
+    fn contains(&self, encoding: Encoding) -> bool {
+        match encoding {
+            Encoding::Zstd => self.zstd,
+            Encoding::Br => self.br,
+            Encoding::Gzip => self.gzip,
+            Encoding::Deflate => self.deflate,
+        }
+    }
+
+    fn iter(&self) -> impl Iterator<Item = Encoding> + '_ {
+        Encoding::ALL
+            .into_iter()
+            .filter(|encoding| self.contains(*encoding))
+    }
+
+    /// Picks the encoding with the highest quality in `Accept-Encoding`,
+    /// ties are broken by the order of preference.
+    fn negotiate(&self, headers: &HeaderMap) -> Option<Encoding> {
+        let mut best: Option<(Encoding, f32)> = None;
+
+        for encoding in self.iter() {
+            let quality = util::header_quality(headers, ACCEPT_ENCODING, |name| {
+                if name == "*" {
+                    Some(0)
+                } else if Encoding::parse(name) == Some(encoding) {
+                    Some(1)
+                } else {
+                    None
+                }
+            })
+            .unwrap_or(0.0);
+
+            if quality > 0.0 && best.is_none_or(|(_, best)| quality > best) {
+                best = Some((encoding, quality));
+            }
+        }
+
+        best.map(|(encoding, _)| encoding)
+    }
+}
+
+fn io_stream<B>(
+    body: B,
+) -> StreamReader<impl futures_util::Stream<Item = io::Result<B::Data>>, B::Data>
+where
+    B: Body + Unpin,
+    B::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    StreamReader::new(DataStream::new(body).map_err(io::Error::other))
+}
+
+/// Compresses response bodies with `zstd`, `br`, `gzip` or `deflate`, whichever is accepted by
+/// the `Accept-Encoding` header of the request with the highest weight.
+///
+/// Bodies are compressed while they are streamed, responses are left untouched if they:
+///
+/// * are already encoded, partial, or have no body (`204 No Content`, `304 Not Modified`),
+/// * have `Cache-Control: no-transform`,
+/// * are smaller than the [minimum size](Compression::min_size), 32 bytes by default,
+/// * have a content type which is rejected by the [predicate](Compression::content_type_predicate),
+///   by default images (except SVG), audio, video, archives and `text/event-stream`.
+#[derive(Clone)]
+pub struct Compression {
+    encodings: Encodings,
+    min_size: u64,
+    predicate: Arc<dyn Fn(&str) -> bool + Send + Sync>,
+}
+
+impl fmt::Debug for Compression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Compression")
+            .field("encodings", &self.encodings)
+            .field("min_size", &self.min_size)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for Compression {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Compression {
+    pub fn new() -> Self {
+        Self {
+            encodings: Encodings::default(),
+            min_size: 32,
+            predicate: Arc::new(is_compressible),
+        }
+    }
+
+    pub fn zstd(mut self, enable: bool) -> Self {
+        self.encodings.zstd = enable;
+        self
+    }
+
+    pub fn br(mut self, enable: bool) -> Self {
+        self.encodings.br = enable;
+        self
+    }
+
+    pub fn gzip(mut self, enable: bool) -> Self {
+        self.encodings.gzip = enable;
+        self
+    }
+
+    pub fn deflate(mut self, enable: bool) -> Self {
+        self.encodings.deflate = enable;
+        self
+    }
+
+    /// Bodies whose size is known and smaller than `min_size` bytes are not compressed.
+    pub fn min_size(mut self, min_size: u64) -> Self {
+        self.min_size = min_size;
+        self
+    }
+
+    /// Decides whether a response is compressed by its `Content-Type`,
+    /// which is an empty string if the response has none.
+    pub fn content_type_predicate<F>(mut self, predicate: F) -> Self
+    where
+        F: Fn(&str) -> bool + Send + Sync + 'static,
+    {
+        self.predicate = Arc::new(predicate);
+        self
+    }
+
+    fn should_compress(&self, response: &Response) -> bool {
+        let status = response.status();
+
+        if status.is_informational()
+            || status == StatusCode::NO_CONTENT
+            || status == StatusCode::NOT_MODIFIED
+            || status == StatusCode::PARTIAL_CONTENT
+        {
+            return false;
+        }
+
+        let headers = response.headers();
+
+        if headers.contains_key(CONTENT_ENCODING) || headers.contains_key(CONTENT_RANGE) {
+            return false;
+        }
+
+        let no_transform = headers
+            .get_all(CACHE_CONTROL)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .any(|directive| directive.trim().eq_ignore_ascii_case("no-transform"));
+
+        if no_transform {
+            return false;
+        }
+
+        let content_type = headers
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default();
+
+        if !(self.predicate)(content_type) {
+            return false;
+        }
+
+        let size = response.body().size_hint().exact().or_else(|| {
+            headers
+                .get(CONTENT_LENGTH)
+                .and_then(|value| value.to_str().ok()?.parse().ok())
+        });
+
+        size.is_none_or(|size| size >= self.min_size)
+    }
+}
+
+/// The default content type predicate of [`Compression`].
+fn is_compressible(content_type: &str) -> bool {
+    let essence = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+
+    if essence == "image/svg+xml" {
+        return true;
+    }
+
+    let incompressible_types = ["image/", "audio/", "video/"];
+
+    let incompressible_essences = [
+        "text/event-stream",
+        "application/grpc",
+        "application/gzip",
+        "application/zip",
+        "application/zstd",
+        "application/x-7z-compressed",
+        "application/x-bzip2",
+        "application/x-rar-compressed",
+        "application/x-xz",
+        "font/woff",
+        "font/woff2",
+    ];
+
+    !incompressible_types
+        .iter()
+        .any(|prefix| essence.starts_with(prefix))
+        && !incompressible_essences.contains(&essence.as_str())
+}
+
+fn compress(body: ResponseBody, encoding: Encoding) -> ResponseBody {
+    let reader = io_stream(body);
+
+    match encoding {
+        Encoding::Zstd => ResponseBody::from_stream(ReaderStream::new(ZstdEncoder::new(reader))),
+        // the default quality of brotli (11) is too slow for dynamic responses
+        Encoding::Br => ResponseBody::from_stream(ReaderStream::new(BrotliEncoder::with_quality(
+            reader,
+            Level::Precise(4),
+        ))),
+        Encoding::Gzip => ResponseBody::from_stream(ReaderStream::new(GzipEncoder::new(reader))),
+        Encoding::Deflate => {
+            ResponseBody::from_stream(ReaderStream::new(DeflateEncoder::new(reader)))
+        }
+    }
+}
+
+impl<H: Handler> Middleware<H> for Compression {
+    type Output = CompressionHandler<H>;
+
+    fn transform(self, input: H) -> Self::Output {
+        CompressionHandler {
+            compression: self,
+            inner: input,
+        }
+    }
+}
+
+pub struct CompressionHandler<H> {
+    compression: Compression,
+    inner: H,
+}
+
+impl<H: Handler> Handler for CompressionHandler<H> {
+    async fn call(&self, req: Request) -> Result<Response, Error> {
+        let encoding = self.compression.encodings.negotiate(&req.head.headers);
+
+        let mut response = self.inner.call(req).await?;
+
+        if !self.compression.should_compress(&response) {
+            return Ok(response);
+        }
+
+        // caches must not serve this response to clients accepting other encodings,
+        // whether it's compressed for this one or not
+        let headers = response.headers_mut();
+
+        let varies = headers
+            .get_all(VARY)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .any(|value| value.trim().eq_ignore_ascii_case("accept-encoding"));
+
+        if !varies {
+            headers.append(VARY, HeaderValue::from_static("accept-encoding"));
+        }
+
+        let Some(encoding) = encoding else {
+            return Ok(response);
+        };
+
+        let headers = response.headers_mut();
+
+        headers.insert(
+            CONTENT_ENCODING,
+            HeaderValue::from_static(encoding.as_str()),
+        );
+        headers.remove(CONTENT_LENGTH);
+        headers.remove(ACCEPT_RANGES);
+
+        let body = std::mem::take(response.body_mut());
+        *response.body_mut() = compress(body, encoding);
+
+        Ok(response)
+    }
+}
+
+/// Decompresses request bodies encoded with `zstd`, `br`, `gzip` or `deflate`
+/// according to their `Content-Encoding` header.
+///
+/// The [`BodyLimit`](crate::request::BodyLimit) of the request applies to the decompressed body,
+/// so that small compressed payloads cannot expand beyond it. Requests with an unsupported
+/// `Content-Encoding` are rejected with `415 Unsupported Media Type`.
+#[derive(Debug, Clone, Default)]
+pub struct Decompression {
+    encodings: Encodings,
+}
+
+impl Decompression {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn zstd(mut self, enable: bool) -> Self {
+        self.encodings.zstd = enable;
+        self
+    }
+
+    pub fn br(mut self, enable: bool) -> Self {
+        self.encodings.br = enable;
+        self
+    }
+
+    pub fn gzip(mut self, enable: bool) -> Self {
+        self.encodings.gzip = enable;
+        self
+    }
+
+    pub fn deflate(mut self, enable: bool) -> Self {
+        self.encodings.deflate = enable;
+        self
+    }
+
+    fn accepted(&self) -> HeaderValue {
+        let accepted = self
+            .encodings
+            .iter()
+            .map(|encoding| encoding.as_str())
+            .chain(["identity"])
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        HeaderValue::try_from(accepted).expect("unreachable: encodings are valid header values")
+    }
+}
+
+fn decompress(body: IncomingBody, encoding: Encoding) -> IncomingBody {
+    let reader = io_stream(body);
+
+    match encoding {
+        Encoding::Zstd => IncomingBody::from_stream(ReaderStream::new(ZstdDecoder::new(reader))),
+        Encoding::Br => IncomingBody::from_stream(ReaderStream::new(BrotliDecoder::new(reader))),
+        Encoding::Gzip => {
+            let mut decoder = GzipDecoder::new(reader);
+            decoder.multiple_members(true);
+            IncomingBody::from_stream(ReaderStream::new(decoder))
+        }
+        Encoding::Deflate => {
+            IncomingBody::from_stream(ReaderStream::new(DeflateDecoder::new(reader)))
+        }
+    }
+}
+
+impl<H: Handler> Middleware<H> for Decompression {
+    type Output = DecompressionHandler<H>;
+
+    fn transform(self, input: H) -> Self::Output {
+        DecompressionHandler {
+            decompression: self,
+            inner: input,
+        }
+    }
+}
+
+pub struct DecompressionHandler<H> {
+    decompression: Decompression,
+    inner: H,
+}
+
+impl<H: Handler> Handler for DecompressionHandler<H> {
+    async fn call(&self, req: Request) -> Result<Response, Error> {
+        let Request { mut head, body } = req;
+
+        let Some(value) = head.headers.get(CONTENT_ENCODING) else {
+            return self.inner.call(Request { head, body }).await;
+        };
+
+        let value = String::from_utf8_lossy(value.as_bytes()).trim().to_string();
+
+        if value.eq_ignore_ascii_case("identity") {
+            head.headers.remove(CONTENT_ENCODING);
+            return self.inner.call(Request { head, body }).await;
+        }
+
+        let Some(encoding) = Encoding::parse(&value)
+            .filter(|encoding| self.decompression.encodings.contains(*encoding))
+        else {
+            return Err(UnsupportedContentEncodingError {
+                encoding: value,
+                accepted: self.decompression.accepted(),
+            }
+            .into());
+        };
+
+        head.headers.remove(CONTENT_ENCODING);
+        head.headers.remove(CONTENT_LENGTH);
+
+        let body = decompress(body, encoding);
+
+        self.inner.call(Request { head, body }).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http_body_util::BodyExt;
+
+    use super::*;
+
+    fn accept(value: &'static str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(ACCEPT_ENCODING, HeaderValue::from_static(value));
+        headers
+    }
+
+    #[test]
+    fn negotiate() {
+        let all = Encodings::default();
+
+        assert_eq!(all.negotiate(&HeaderMap::new()), None);
+        assert_eq!(
+            all.negotiate(&accept("gzip, deflate")),
+            Some(Encoding::Gzip)
+        );
+        assert_eq!(
+            all.negotiate(&accept("gzip, br, zstd")),
+            Some(Encoding::Zstd)
+        );
+        assert_eq!(
+            all.negotiate(&accept("gzip;q=1.0, br;q=0.5")),
+            Some(Encoding::Gzip)
+        );
+        assert_eq!(all.negotiate(&accept("*")), Some(Encoding::Zstd));
+        assert_eq!(all.negotiate(&accept("*, zstd;q=0")), Some(Encoding::Br));
+        assert_eq!(all.negotiate(&accept("identity")), None);
+        assert_eq!(all.negotiate(&accept("gzip;q=0")), None);
+
+        let gzip_only = Encodings {
+            zstd: false,
+            br: false,
+            gzip: true,
+            deflate: false,
+        };
+
+        assert_eq!(gzip_only.negotiate(&accept("zstd, br")), None);
+        assert_eq!(
+            gzip_only.negotiate(&accept("zstd, GZIP;q=0.1")),
+            Some(Encoding::Gzip)
+        );
+    }
+
+    #[test]
+    fn compressible() {
+        assert!(is_compressible("application/json"));
+        assert!(is_compressible("text/html; charset=utf-8"));
+        assert!(is_compressible("image/svg+xml"));
+        assert!(is_compressible(""));
+        assert!(!is_compressible("image/png"));
+        assert!(!is_compressible("text/event-stream"));
+        assert!(!is_compressible("application/zip"));
+    }
+
+    #[tokio::test]
+    async fn round_trip() {
+        let data = "predawn ".repeat(1024);
+
+        for encoding in Encoding::ALL {
+            let compressed = compress(data.clone().into(), encoding)
+                .collect()
+                .await
+                .unwrap()
+                .to_bytes();
+
+            assert!(compressed.len() < data.len());
+
+            let decompressed = decompress(
+                IncomingBody::new(http_body_util::Full::new(compressed)),
+                encoding,
+            )
+            .collect()
+            .await
+            .unwrap()
+            .to_bytes();
+
+            assert_eq!(decompressed, data.as_bytes());
+        }
+    }
+
+    #[tokio::test]
+    async fn vary_without_compression() {
+        use crate::{
+            handler::{handler_fn, HandlerExt},
+            middleware::test_request,
+        };
+
+        let handler =
+            handler_fn(|_| async { Ok("predawn ".repeat(1024)) }).with(Compression::new());
+
+        let request = |accept_encoding: Option<&'static str>| {
+            let mut req = http::Request::builder().uri("/");
+
+            if let Some(accept_encoding) = accept_encoding {
+                req = req.header(ACCEPT_ENCODING, accept_encoding);
+            }
+
+            test_request(req.body("").unwrap(), "127.0.0.1:1234")
+        };
+
+        let response = handler.call(request(None)).await.unwrap();
+        assert!(!response.headers().contains_key(CONTENT_ENCODING));
+        assert_eq!(response.headers()[VARY], "accept-encoding");
+
+        let response = handler.call(request(Some("gzip"))).await.unwrap();
+        assert_eq!(response.headers()[CONTENT_ENCODING], "gzip");
+        assert_eq!(response.headers().get_all(VARY).iter().count(), 1);
+    }
+}
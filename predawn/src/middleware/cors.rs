@@ -0,0 +1,672 @@
+use std::{fmt, sync::Arc, time::Duration};
+
+use http::{
+    header::{
+        ACCESS_CONTROL_ALLOW_CREDENTIALS, ACCESS_CONTROL_ALLOW_HEADERS,
+        ACCESS_CONTROL_ALLOW_METHODS, ACCESS_CONTROL_ALLOW_ORIGIN, ACCESS_CONTROL_EXPOSE_HEADERS,
+        ACCESS_CONTROL_MAX_AGE, ACCESS_CONTROL_REQUEST_HEADERS, ACCESS_CONTROL_REQUEST_METHOD,
+        ORIGIN, VARY,
+    },
+    HeaderMap, HeaderName, HeaderValue, Method, StatusCode,
+};
+use predawn_core::{error::Error, request::Request, response::Response};
+use regex::Regex;
+
+use super::Middleware;
+use crate::{
+    config::cors::CorsConfig,
+    handler::{DynHandler, Handler},
+    response_error::CorsError,
+};
+
+/// A value of [`CorsConfig`] which cannot be parsed.
+#[derive(Debug, thiserror::Error)]
+pub enum CorsConfigError {
+    #[error("invalid origin regex: {0}")]
+    InvalidRegex(#[from] regex::Error),
+    #[error("invalid method `{0}`")]
+    InvalidMethod(String),
+    #[error("invalid header name `{0}`")]
+    InvalidHeader(String),
+    #[error("credentials can not be allowed for any origin `*`")]
+    CredentialsWithAnyOrigin,
+}
+
+#[derive(Debug, Clone)]
+enum AllowList<T> {
+    Any,
+    List(Vec<T>),
+}
+
+impl<T: PartialEq> AllowList<T> {
+    fn push(&mut self, value: T) {
+        match self {
+            AllowList::Any => *self = AllowList::List(vec![value]),
+            AllowList::List(values) => {
+                if !values.contains(&value) {
+                    values.push(value);
+                }
+            }
+        }
+    }
+
+    fn contains(&self, value: &T) -> bool {
+        match self {
+            AllowList::Any => true,
+            AllowList::List(values) => values.contains(value),
+        }
+    }
+}
+
+/// Answers [CORS](https://developer.mozilla.org/en-US/docs/Web/HTTP/CORS) preflight requests
+/// and adds the `Access-Control-*` headers to the responses of cross-origin requests.
+///
+/// Preflight requests are answered with `204 No Content`, even for paths which have no `OPTIONS`
+/// handler, as long as `Cors` is the outermost middleware of the requested method's handler
+/// (or of the whole app). Preflight requests with a disallowed origin, method or headers are
+/// rejected with `403 Forbidden`. Requests without an `Origin` header are passed through untouched.
+///
+/// ```rust
+/// use std::time::Duration;
+///
+/// use predawn::middleware::Cors;
+///
+/// let cors = Cors::new()
+///     .allow_origin("https://*.example.com")
+///     .allow_credentials(true)
+///     .max_age(Duration::from_secs(600));
+/// ```
+#[derive(Clone)]
+pub struct Cors {
+    inner: Arc<CorsInner>,
+}
+
+#[derive(Debug, Clone)]
+struct CorsInner {
+    any_origin: bool,
+    origins: Vec<String>,
+    origin_regex: Vec<Regex>,
+    methods: AllowList<Method>,
+    headers: AllowList<HeaderName>,
+    allow_credentials: bool,
+    max_age: Option<Duration>,
+    expose_headers: Vec<HeaderName>,
+}
+
+impl fmt::Debug for Cors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let inner = &*self.inner;
+
+        f.debug_struct("Cors")
+            .field("any_origin", &inner.any_origin)
+            .field("origins", &inner.origins)
+            .field("origin_regex", &inner.origin_regex)
+            .field("methods", &inner.methods)
+            .field("headers", &inner.headers)
+            .field("allow_credentials", &inner.allow_credentials)
+            .field("max_age", &inner.max_age)
+            .field("expose_headers", &inner.expose_headers)
+            .finish()
+    }
+}
+
+impl Default for Cors {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Cors {
+    /// Allows no origin, and any method and header of the allowed origins.
+    pub fn new() -> Self {
+        Self {
+            inner: Arc::new(CorsInner {
+                any_origin: false,
+                origins: Vec::new(),
+                origin_regex: Vec::new(),
+                methods: AllowList::Any,
+                headers: AllowList::Any,
+                allow_credentials: false,
+                max_age: None,
+                expose_headers: Vec::new(),
+            }),
+        }
+    }
+
+    pub fn from_config(config: &CorsConfig) -> Result<Self, CorsConfigError> {
+        if config.allow_credentials && config.allow_origins.iter().any(|origin| origin == "*") {
+            return Err(CorsConfigError::CredentialsWithAnyOrigin);
+        }
+
+        let mut cors = Self::new();
+
+        for origin in &config.allow_origins {
+            cors = cors.allow_origin(origin);
+        }
+
+        // the regexes must match whole origins, `https://example.com` must not match `https://example.com.evil.com`
+        for regex in &config.allow_origin_regex {
+            cors = cors.allow_origin_regex(Regex::new(&format!("^(?:{regex})$"))?);
+        }
+
+        if !config.allow_methods.iter().any(|method| method == "*") {
+            let methods = config
+                .allow_methods
+                .iter()
+                .map(|method| {
+                    Method::from_bytes(method.to_ascii_uppercase().as_bytes())
+                        .map_err(|_| CorsConfigError::InvalidMethod(method.clone()))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            cors = cors.allow_methods(methods);
+        }
+
+        if !config.allow_headers.iter().any(|header| header == "*") {
+            cors = cors.allow_headers(parse_header_names(&config.allow_headers)?);
+        }
+
+        cors = cors
+            .expose_headers(parse_header_names(&config.expose_headers)?)
+            .allow_credentials(config.allow_credentials);
+
+        if let Some(max_age) = config.max_age {
+            cors = cors.max_age(Duration::from_secs(max_age));
+        }
+
+        Ok(cors)
+    }
+
+    fn inner_mut(&mut self) -> &mut CorsInner {
+        Arc::make_mut(&mut self.inner)
+    }
+
+    /// Allows an origin, `*` allows any origin but not together with credentials,
+    /// `*` in an origin matches any subdomain, e.g. `https://*.example.com`.
+    #[track_caller]
+    pub fn allow_origin(mut self, origin: &str) -> Self {
+        let inner = self.inner_mut();
+
+        if origin == "*" {
+            if inner.allow_credentials {
+                panic!("credentials can not be allowed for any origin `*`");
+            }
+
+            inner.any_origin = true;
+        } else if origin.contains('*') {
+            let pattern = origin
+                .split('*')
+                .map(regex::escape)
+                .collect::<Vec<_>>()
+                .join("[A-Za-z0-9.-]*");
+
+            let regex = Regex::new(&format!("^{}$", pattern))
+                .expect("unreachable: an escaped wildcard origin must be a valid regex");
+
+            inner.origin_regex.push(regex);
+        } else {
+            inner.origins.push(origin.trim_end_matches('/').to_string());
+        }
+
+        self
+    }
+
+    /// Allows the origins which are matched by `regex`, it should be anchored with `^` and `$`.
+    pub fn allow_origin_regex(mut self, regex: Regex) -> Self {
+        self.inner_mut().origin_regex.push(regex);
+        self
+    }
+
+    pub fn allow_methods<I>(mut self, methods: I) -> Self
+    where
+        I: IntoIterator<Item = Method>,
+    {
+        let inner = self.inner_mut();
+        methods
+            .into_iter()
+            .for_each(|method| inner.methods.push(method));
+        self
+    }
+
+    /// Allows the method requested by a preflight request, whatever it is.
+    pub fn allow_any_method(mut self) -> Self {
+        self.inner_mut().methods = AllowList::Any;
+        self
+    }
+
+    pub fn allow_headers<I>(mut self, headers: I) -> Self
+    where
+        I: IntoIterator<Item = HeaderName>,
+    {
+        let inner = self.inner_mut();
+        headers
+            .into_iter()
+            .for_each(|header| inner.headers.push(header));
+        self
+    }
+
+    /// Allows the headers requested by a preflight request, whatever they are.
+    pub fn allow_any_header(mut self) -> Self {
+        self.inner_mut().headers = AllowList::Any;
+        self
+    }
+
+    pub fn expose_headers<I>(mut self, headers: I) -> Self
+    where
+        I: IntoIterator<Item = HeaderName>,
+    {
+        self.inner_mut().expose_headers.extend(headers);
+        self
+    }
+
+    /// Whether credentials are allowed, which can not be combined with any origin `*`.
+    #[track_caller]
+    pub fn allow_credentials(mut self, allow_credentials: bool) -> Self {
+        let inner = self.inner_mut();
+
+        if allow_credentials && inner.any_origin {
+            panic!("credentials can not be allowed for any origin `*`");
+        }
+
+        inner.allow_credentials = allow_credentials;
+        self
+    }
+
+    pub fn max_age(mut self, max_age: Duration) -> Self {
+        self.inner_mut().max_age = Some(max_age);
+        self
+    }
+
+    pub fn is_origin_allowed(&self, origin: &str) -> bool {
+        let inner = &*self.inner;
+
+        inner.any_origin
+            || inner.origins.iter().any(|o| o == origin)
+            || inner.origin_regex.iter().any(|r| r.is_match(origin))
+    }
+
+    fn allow_origin_header(&self, origin: &HeaderValue) -> HeaderValue {
+        if self.inner.any_origin {
+            HeaderValue::from_static("*")
+        } else {
+            origin.clone()
+        }
+    }
+
+    fn preflight(&self, origin: &HeaderValue, headers: &HeaderMap) -> Result<Response, CorsError> {
+        let inner = &*self.inner;
+
+        let requested_method = headers
+            .get(ACCESS_CONTROL_REQUEST_METHOD)
+            .map(|method| String::from_utf8_lossy(method.as_bytes()).into_owned())
+            .unwrap_or_default();
+
+        match Method::from_bytes(requested_method.as_bytes()) {
+            Ok(method) if inner.methods.contains(&method) => {}
+            _ => return Err(CorsError::MethodNotAllowed(requested_method)),
+        }
+
+        let requested_headers = headers
+            .get_all(ACCESS_CONTROL_REQUEST_HEADERS)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .map(str::trim)
+            .filter(|header| !header.is_empty())
+            .collect::<Vec<_>>();
+
+        for header in &requested_headers {
+            match HeaderName::from_bytes(header.as_bytes()) {
+                Ok(name) if inner.headers.contains(&name) => {}
+                _ => return Err(CorsError::HeaderNotAllowed(header.to_string())),
+            }
+        }
+
+        let mut response = Response::builder()
+            .status(StatusCode::NO_CONTENT)
+            .body(Default::default())
+            .unwrap();
+
+        let response_headers = response.headers_mut();
+
+        self.add_origin_headers(origin, response_headers);
+
+        let allow_methods = match &inner.methods {
+            AllowList::Any => requested_method,
+            AllowList::List(methods) => join(methods.iter().map(Method::as_str)),
+        };
+
+        if let Ok(value) = HeaderValue::try_from(allow_methods) {
+            response_headers.insert(ACCESS_CONTROL_ALLOW_METHODS, value);
+        }
+
+        let allow_headers = match &inner.headers {
+            AllowList::Any => requested_headers.join(", "),
+            AllowList::List(headers) => join(headers.iter().map(HeaderName::as_str)),
+        };
+
+        if !allow_headers.is_empty() {
+            if let Ok(value) = HeaderValue::try_from(allow_headers) {
+                response_headers.insert(ACCESS_CONTROL_ALLOW_HEADERS, value);
+            }
+        }
+
+        if let Some(max_age) = inner.max_age {
+            response_headers.insert(ACCESS_CONTROL_MAX_AGE, max_age.as_secs().into());
+        }
+
+        response_headers.append(
+            VARY,
+            HeaderValue::from_static(
+                "origin, access-control-request-method, access-control-request-headers",
+            ),
+        );
+
+        Ok(response)
+    }
+
+    fn add_origin_headers(&self, origin: &HeaderValue, headers: &mut HeaderMap) {
+        headers.insert(
+            ACCESS_CONTROL_ALLOW_ORIGIN,
+            self.allow_origin_header(origin),
+        );
+
+        if self.inner.allow_credentials {
+            headers.insert(
+                ACCESS_CONTROL_ALLOW_CREDENTIALS,
+                HeaderValue::from_static("true"),
+            );
+        }
+    }
+
+    fn add_actual_headers(&self, origin: &HeaderValue, headers: &mut HeaderMap) {
+        self.add_origin_headers(origin, headers);
+
+        if !self.inner.expose_headers.is_empty() {
+            let expose_headers = join(self.inner.expose_headers.iter().map(HeaderName::as_str));
+
+            headers.insert(
+                ACCESS_CONTROL_EXPOSE_HEADERS,
+                HeaderValue::try_from(expose_headers)
+                    .expect("unreachable: header names must be a valid header value"),
+            );
+        }
+    }
+
+    /// Whether the `Access-Control-Allow-Origin` header is not always `*`.
+    fn varies_by_origin(&self) -> bool {
+        !self.inner.any_origin
+    }
+}
+
+fn join<'a, I: Iterator<Item = &'a str>>(values: I) -> String {
+    values.collect::<Vec<_>>().join(", ")
+}
+
+fn parse_header_names(names: &[String]) -> Result<Vec<HeaderName>, CorsConfigError> {
+    names
+        .iter()
+        .map(|name| {
+            HeaderName::from_bytes(name.as_bytes())
+                .map_err(|_| CorsConfigError::InvalidHeader(name.clone()))
+        })
+        .collect()
+}
+
+impl<H: Handler> Middleware<H> for Cors {
+    type Output = CorsHandler;
+
+    fn transform(self, input: H) -> Self::Output {
+        CorsHandler {
+            cors: self,
+            inner: DynHandler::new(input),
+        }
+    }
+}
+
+/// The handler is erased, so that [`MethodRouter`](crate::route::MethodRouter) recognizes
+/// the routes whose handlers answer preflight requests by their type.
+pub struct CorsHandler {
+    cors: Cors,
+    inner: DynHandler,
+}
+
+impl CorsHandler {
+    async fn handle(&self, req: Request) -> Result<Response, Error> {
+        let head = &req.head;
+
+        let Some(origin) = head.headers.get(ORIGIN).cloned() else {
+            return self.inner.call(req).await;
+        };
+
+        if head.method == Method::OPTIONS
+            && head.headers.contains_key(ACCESS_CONTROL_REQUEST_METHOD)
+        {
+            let allowed = origin
+                .to_str()
+                .is_ok_and(|origin| self.cors.is_origin_allowed(origin));
+
+            if !allowed {
+                let origin = String::from_utf8_lossy(origin.as_bytes()).into_owned();
+                return Err(CorsError::OriginNotAllowed(origin).into());
+            }
+
+            return self
+                .cors
+                .preflight(&origin, &head.headers)
+                .map_err(Into::into);
+        }
+
+        let allowed = origin
+            .to_str()
+            .is_ok_and(|origin| self.cors.is_origin_allowed(origin));
+
+        let mut result = self.inner.call(req).await;
+
+        if allowed {
+            let headers = match &mut result {
+                Ok(response) => response.headers_mut(),
+                Err(error) => error.response_mut().headers_mut(),
+            };
+
+            self.cors.add_actual_headers(&origin, headers);
+        }
+
+        result
+    }
+}
+
+impl Handler for CorsHandler {
+    async fn call(&self, req: Request) -> Result<Response, Error> {
+        let mut result = self.handle(req).await;
+
+        // whether the origin is allowed changes the response, even without an `Origin` header
+        if self.cors.varies_by_origin() {
+            let headers = match &mut result {
+                Ok(response) => response.headers_mut(),
+                Err(error) => error.response_mut().headers_mut(),
+            };
+
+            let has_vary_origin = headers
+                .get_all(VARY)
+                .iter()
+                .filter_map(|value| value.to_str().ok())
+                .flat_map(|value| value.split(','))
+                .any(|value| value.trim().eq_ignore_ascii_case("origin"));
+
+            if !has_vary_origin {
+                headers.append(VARY, HeaderValue::from_static("origin"));
+            }
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wildcard_origin() {
+        let cors = Cors::new()
+            .allow_origin("https://*.example.com")
+            .allow_origin("http://localhost:3000/");
+
+        assert!(cors.is_origin_allowed("https://api.example.com"));
+        assert!(cors.is_origin_allowed("https://a.b.example.com"));
+        assert!(cors.is_origin_allowed("http://localhost:3000"));
+        assert!(!cors.is_origin_allowed("https://example.com.evil.com"));
+        assert!(!cors.is_origin_allowed("http://api.example.com"));
+        assert!(!cors.is_origin_allowed("https://evil.com/.example.com"));
+    }
+
+    #[test]
+    fn preflight_from_config() {
+        let config = CorsConfig {
+            allow_origins: vec!["https://example.com".into()],
+            allow_methods: vec!["get".into(), "POST".into()],
+            allow_headers: vec!["Content-Type".into()],
+            allow_credentials: true,
+            max_age: Some(600),
+            ..Default::default()
+        };
+
+        let cors = Cors::from_config(&config).unwrap();
+        let origin = HeaderValue::from_static("https://example.com");
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            ACCESS_CONTROL_REQUEST_METHOD,
+            HeaderValue::from_static("POST"),
+        );
+        headers.insert(
+            ACCESS_CONTROL_REQUEST_HEADERS,
+            HeaderValue::from_static("content-type"),
+        );
+
+        let response = cors.preflight(&origin, &headers).unwrap();
+        let headers = response.headers();
+
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert_eq!(headers[ACCESS_CONTROL_ALLOW_ORIGIN], "https://example.com");
+        assert_eq!(headers[ACCESS_CONTROL_ALLOW_CREDENTIALS], "true");
+        assert_eq!(headers[ACCESS_CONTROL_ALLOW_METHODS], "GET, POST");
+        assert_eq!(headers[ACCESS_CONTROL_ALLOW_HEADERS], "content-type");
+        assert_eq!(headers[ACCESS_CONTROL_MAX_AGE], "600");
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            ACCESS_CONTROL_REQUEST_METHOD,
+            HeaderValue::from_static("DELETE"),
+        );
+        assert!(matches!(
+            cors.preflight(&origin, &headers),
+            Err(CorsError::MethodNotAllowed(_))
+        ));
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            ACCESS_CONTROL_REQUEST_METHOD,
+            HeaderValue::from_static("GET"),
+        );
+        headers.insert(
+            ACCESS_CONTROL_REQUEST_HEADERS,
+            HeaderValue::from_static("x-secret"),
+        );
+        assert!(matches!(
+            cors.preflight(&origin, &headers),
+            Err(CorsError::HeaderNotAllowed(_))
+        ));
+    }
+
+    #[test]
+    fn credentials_with_any_origin() {
+        let config = CorsConfig {
+            allow_origins: vec!["*".into()],
+            allow_credentials: true,
+            ..Default::default()
+        };
+
+        assert!(matches!(
+            Cors::from_config(&config),
+            Err(CorsConfigError::CredentialsWithAnyOrigin)
+        ));
+    }
+
+    #[test]
+    #[should_panic = "credentials can not be allowed for any origin"]
+    fn builder_credentials_with_any_origin() {
+        let _ = Cors::new().allow_credentials(true).allow_origin("*");
+    }
+
+    #[test]
+    fn anchored_regex_from_config() {
+        let config = CorsConfig {
+            allow_origin_regex: vec![r"https://(api|www)\.example\.com".into()],
+            ..Default::default()
+        };
+
+        let cors = Cors::from_config(&config).unwrap();
+
+        assert!(cors.is_origin_allowed("https://api.example.com"));
+        assert!(!cors.is_origin_allowed("https://api.example.com.evil.com"));
+        assert!(!cors.is_origin_allowed("https://evil.com/https://www.example.com"));
+    }
+
+    #[tokio::test]
+    async fn route_preflight_and_vary() {
+        use crate::{
+            handler::{handler_fn, DynHandler, HandlerExt},
+            middleware::{test_request, Tracing},
+            route::MethodRouter,
+        };
+
+        let handler = handler_fn(|_| async { Ok("ok") })
+            .with(Tracing)
+            .with(Cors::new().allow_origin("https://example.com"));
+
+        let mut methods = indexmap::IndexMap::new();
+        methods.insert(Method::POST, DynHandler::new(handler));
+        let router = MethodRouter::from(methods);
+
+        let request = |method: Method, origin: Option<&'static str>| {
+            let mut req = http::Request::builder().method(method).uri("/");
+
+            if let Some(origin) = origin {
+                req = req
+                    .header(ORIGIN, origin)
+                    .header(ACCESS_CONTROL_REQUEST_METHOD, "POST");
+            }
+
+            test_request(req.body("").unwrap(), "127.0.0.1:1234")
+        };
+
+        // there is no `OPTIONS` handler, the preflight request is answered by `Cors`
+        let response = router
+            .call(request(Method::OPTIONS, Some("https://example.com")))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert_eq!(
+            response.headers()[ACCESS_CONTROL_ALLOW_ORIGIN],
+            "https://example.com"
+        );
+
+        let e = router
+            .call(request(Method::OPTIONS, Some("https://evil.com")))
+            .await
+            .unwrap_err();
+        assert_eq!(e.response().headers()[VARY], "origin");
+
+        let response = router
+            .call(request(Method::POST, Some("https://evil.com")))
+            .await
+            .unwrap();
+        assert!(!response.headers().contains_key(ACCESS_CONTROL_ALLOW_ORIGIN));
+        assert_eq!(response.headers()[VARY], "origin");
+
+        let response = router.call(request(Method::POST, None)).await.unwrap();
+        assert_eq!(response.headers()[VARY], "origin");
+    }
+}
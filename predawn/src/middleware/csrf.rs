@@ -0,0 +1,443 @@
+use std::{convert::Infallible, sync::Arc};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use bytes::Bytes;
+use http::{header::SET_COOKIE, HeaderName, Method};
+use http_body_util::Full;
+use predawn_core::{
+    body::IncomingBody, error::Error, from_request::FromRequest, request::Request,
+    response::Response,
+};
+use ring::rand::{SecureRandom, SystemRandom};
+use subtle::ConstantTimeEq;
+
+use super::Middleware;
+use crate::{
+    config::csrf::CsrfConfig,
+    cookie::{Key, SetCookie, SignedCookieJar},
+    extract::{authorization, CsrfToken},
+    handler::Handler,
+    response_error::CsrfError,
+};
+
+const TOKEN_LEN: usize = 32;
+
+/// Protects cookie-authenticated requests from cross-site request forgery with
+/// double-submit tokens.
+///
+/// A random token is kept in a cookie signed with the [`Key`], requests with unsafe methods
+/// must submit it in a header or in a field of an urlencoded or multipart form, otherwise
+/// they are rejected with `403 Forbidden`. The token is provided to handlers by the
+/// [`CsrfToken`] extractor, masked differently on every request.
+///
+/// Requests with safe methods (`GET`, `HEAD`, `OPTIONS`, `TRACE`) and requests with a
+/// bearer token, which browsers do not send by themselves, are not checked.
+/// The rejection is documented in OpenAPI by adding [`CsrfError`] to the `errors`
+/// of the handler or the controller, e.g. `errors = [CsrfError]`.
+#[derive(Debug, Clone)]
+pub struct Csrf {
+    inner: Arc<CsrfInner>,
+}
+
+#[derive(Debug, Clone)]
+struct CsrfInner {
+    key: Key,
+    cookie: SetCookie,
+    header_name: HeaderName,
+    form_field: Arc<str>,
+}
+
+impl Csrf {
+    pub fn new(key: Key) -> Self {
+        Self::from_config(key, &CsrfConfig::default())
+    }
+
+    #[track_caller]
+    pub fn from_config(key: Key, config: &CsrfConfig) -> Self {
+        let mut cookie = SetCookie::new(config.cookie_name.as_str(), "")
+            .path(config.cookie_path.as_str())
+            .secure(config.cookie_secure)
+            .same_site(config.cookie_same_site);
+
+        if let Some(domain) = &config.cookie_domain {
+            cookie = cookie.domain(domain.as_str());
+        }
+
+        Self {
+            inner: Arc::new(CsrfInner {
+                key,
+                cookie: SetCookie::new("", ""),
+                header_name: HeaderName::from_static("x-csrf-token"),
+                form_field: Arc::from(config.form_field.as_str()),
+            }),
+        }
+        .cookie(cookie)
+        .header_name(&config.header_name)
+    }
+
+    fn inner_mut(&mut self) -> &mut CsrfInner {
+        Arc::make_mut(&mut self.inner)
+    }
+
+    /// The name and the attributes of the cookie, its value is ignored,
+    /// it is always `HttpOnly`.
+    #[track_caller]
+    pub fn cookie(mut self, cookie: SetCookie) -> Self {
+        let cookie = cookie.with_value(String::new()).http_only(true);
+
+        if !cookie.is_valid() {
+            panic!("`{cookie}` is not a valid CSRF cookie");
+        }
+
+        self.inner_mut().cookie = cookie;
+        self
+    }
+
+    #[track_caller]
+    pub fn header_name(mut self, name: &str) -> Self {
+        match HeaderName::try_from(name) {
+            Ok(name) => self.inner_mut().header_name = name,
+            Err(_) => panic!("`{name}` is not a valid header name"),
+        }
+
+        self
+    }
+
+    pub fn form_field(mut self, field: &str) -> Self {
+        self.inner_mut().form_field = Arc::from(field);
+        self
+    }
+
+    /// Returns the token in the cookie, or a new one with the cookie to send.
+    fn cookie_token(&self, req: &Request) -> ([u8; TOKEN_LEN], Option<SetCookie>) {
+        let inner = &*self.inner;
+        let name = inner.cookie.name();
+
+        let token = SignedCookieJar::from_headers(&req.head.headers, inner.key.clone())
+            .get(name)
+            .and_then(|token| URL_SAFE_NO_PAD.decode(token).ok())
+            .and_then(|token| <[u8; TOKEN_LEN]>::try_from(token).ok());
+
+        if let Some(token) = token {
+            return (token, None);
+        }
+
+        let token = random::<TOKEN_LEN>();
+
+        let cookie = inner
+            .cookie
+            .clone()
+            .with_value(URL_SAFE_NO_PAD.encode(token));
+        let cookie = SignedCookieJar::new(inner.key.clone())
+            .insert(cookie)
+            .changes()[0]
+            .clone();
+
+        (token, Some(cookie))
+    }
+
+    /// Returns the submitted token, the body of a form is buffered and put back.
+    async fn submitted_token(&self, req: Request) -> Result<(Request, Option<String>), Error> {
+        let inner = &*self.inner;
+
+        if let Some(token) = req.head.headers.get(&inner.header_name) {
+            let token = token.to_str().ok().map(ToString::to_string);
+            return Ok((req, token));
+        }
+
+        let content_type = req.head.content_type().unwrap_or_default();
+
+        let is_form = content_type.starts_with("application/x-www-form-urlencoded");
+        let boundary = multer::parse_boundary(content_type).ok();
+
+        if !is_form && boundary.is_none() {
+            return Ok((req, None));
+        }
+
+        let (head, body) = req.split();
+        let bytes = Bytes::from_request(&head, body).await?;
+
+        let req = Request {
+            head,
+            body: IncomingBody::new(Full::new(bytes.clone())),
+        };
+
+        let field = &*inner.form_field;
+
+        if is_form {
+            let token = url::form_urlencoded::parse(&bytes)
+                .find(|(name, _)| name == field)
+                .map(|(_, token)| token.into_owned());
+
+            return Ok((req, token));
+        }
+
+        let stream = futures_util::stream::once(async move { Ok::<_, Infallible>(bytes) });
+        let mut multipart = multer::Multipart::new(stream, boundary.unwrap_or_default());
+
+        while let Ok(Some(part)) = multipart.next_field().await {
+            if part.name() == Some(field) {
+                return Ok((req, part.text().await.ok()));
+            }
+        }
+
+        Ok((req, None))
+    }
+}
+
+fn random<const N: usize>() -> [u8; N] {
+    let mut bytes = [0u8; N];
+
+    SystemRandom::new()
+        .fill(&mut bytes)
+        .expect("failed to generate a CSRF token");
+
+    bytes
+}
+
+/// XORs the token with a random pad, so the submitted token differs on every request.
+fn mask(token: &[u8; TOKEN_LEN]) -> String {
+    let pad = random::<TOKEN_LEN>();
+
+    let mut masked = pad.to_vec();
+    masked.extend(pad.iter().zip(token).map(|(p, t)| p ^ t));
+
+    URL_SAFE_NO_PAD.encode(masked)
+}
+
+fn unmask(masked: &str) -> Option<[u8; TOKEN_LEN]> {
+    let masked = URL_SAFE_NO_PAD.decode(masked).ok()?;
+
+    if masked.len() != TOKEN_LEN * 2 {
+        return None;
+    }
+
+    let (pad, token) = masked.split_at(TOKEN_LEN);
+
+    let mut unmasked = [0u8; TOKEN_LEN];
+
+    for (i, (p, t)) in pad.iter().zip(token).enumerate() {
+        unmasked[i] = p ^ t;
+    }
+
+    Some(unmasked)
+}
+
+fn is_safe(method: &Method) -> bool {
+    matches!(
+        *method,
+        Method::GET | Method::HEAD | Method::OPTIONS | Method::TRACE
+    )
+}
+
+impl<H: Handler> Middleware<H> for Csrf {
+    type Output = CsrfHandler<H>;
+
+    fn transform(self, input: H) -> Self::Output {
+        CsrfHandler {
+            csrf: self,
+            inner: input,
+        }
+    }
+}
+
+pub struct CsrfHandler<H> {
+    csrf: Csrf,
+    inner: H,
+}
+
+impl<H: Handler> Handler for CsrfHandler<H> {
+    async fn call(&self, mut req: Request) -> Result<Response, Error> {
+        let (token, cookie) = self.csrf.cookie_token(&req);
+
+        let exempt = is_safe(&req.head.method) || authorization(&req.head, "Bearer").is_some();
+
+        if !exempt {
+            let (checked_req, submitted) = self.csrf.submitted_token(req).await?;
+            req = checked_req;
+
+            let checked = match (&cookie, submitted) {
+                (Some(_), _) => Err(CsrfError::MissingCookie),
+                (None, None) => Err(CsrfError::MissingToken),
+                (None, Some(submitted)) => match unmask(&submitted) {
+                    Some(submitted) if bool::from(submitted.ct_eq(&token)) => Ok(()),
+                    _ => Err(CsrfError::Mismatch),
+                },
+            };
+
+            if let Err(e) = checked {
+                tracing::debug!(error = %e, "rejected a request by CSRF protection");
+
+                let mut error = Error::from(e);
+
+                if let Some(cookie) = &cookie {
+                    append_cookie(error.response_mut(), cookie);
+                }
+
+                return Err(error);
+            }
+        }
+
+        let inner = &*self.csrf.inner;
+
+        req.head.extensions.insert(CsrfToken {
+            token: mask(&token).into(),
+            form_field: inner.form_field.clone(),
+            header_name: inner.header_name.clone(),
+        });
+
+        let mut result = self.inner.call(req).await;
+
+        if let Some(cookie) = &cookie {
+            let response = match &mut result {
+                Ok(response) => response,
+                Err(error) => error.response_mut(),
+            };
+
+            append_cookie(response, cookie);
+        }
+
+        result
+    }
+}
+
+fn append_cookie(response: &mut Response, cookie: &SetCookie) {
+    let value = cookie
+        .to_header_value()
+        .expect("unreachable: the CSRF cookie is checked when it is set");
+
+    response.headers_mut().append(SET_COOKIE, value);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mask_unmask() {
+        let token = random::<TOKEN_LEN>();
+
+        let masked = mask(&token);
+        assert_ne!(masked, mask(&token));
+        assert_eq!(unmask(&masked), Some(token));
+
+        assert_eq!(unmask(&URL_SAFE_NO_PAD.encode(token)), None);
+        assert_eq!(unmask("not base64!"), None);
+    }
+
+    #[tokio::test]
+    async fn call() {
+        use std::slice;
+
+        use http::header::{AUTHORIZATION, CONTENT_TYPE, COOKIE};
+        use http_body_util::BodyExt;
+
+        use crate::{
+            handler::{handler_fn, HandlerExt},
+            middleware::test_request,
+        };
+
+        // responds with the masked token and the body it received
+        let handler = handler_fn(|req: Request| async move {
+            let token = req.head.extensions.get::<CsrfToken>().unwrap().to_string();
+
+            let (head, body) = req.split();
+            let body = Bytes::from_request(&head, body).await?;
+
+            Ok(format!("{token} {}", String::from_utf8_lossy(&body)))
+        })
+        .with(Csrf::new(Key::generate()));
+
+        let request = |method: Method, headers: &[(HeaderName, &str)], body: &str| {
+            let mut req = http::Request::builder().method(method).uri("/");
+
+            for (name, value) in headers {
+                req = req.header(name, *value);
+            }
+
+            test_request(req.body(body.to_string()).unwrap(), "127.0.0.1:1234")
+        };
+
+        let body = |response: Response| async move {
+            let body = response.into_body().collect().await.unwrap().to_bytes();
+            String::from_utf8(body.to_vec()).unwrap()
+        };
+
+        let csrf_error = |e: Error| e.downcast::<CsrfError>().unwrap().1;
+
+        // safe methods are not checked, the cookie is set on the first request
+        let response = handler.call(request(Method::GET, &[], "")).await.unwrap();
+        let set_cookie = response.headers()[SET_COOKIE].to_str().unwrap();
+        let cookie = set_cookie.split(';').next().unwrap().to_string();
+        let token = body(response).await.trim_end().to_string();
+
+        let cookie = (COOKIE, cookie.as_str());
+
+        let response = handler
+            .call(request(Method::GET, slice::from_ref(&cookie), ""))
+            .await
+            .unwrap();
+        assert!(!response.headers().contains_key(SET_COOKIE));
+
+        // requests with a bearer token are not checked
+        let bearer = (AUTHORIZATION, "Bearer token");
+        assert!(handler
+            .call(request(Method::POST, &[bearer], ""))
+            .await
+            .is_ok());
+
+        let header = (HeaderName::from_static("x-csrf-token"), token.as_str());
+
+        let (response, e, _) = handler
+            .call(request(Method::POST, slice::from_ref(&header), ""))
+            .await
+            .unwrap_err()
+            .downcast::<CsrfError>()
+            .unwrap();
+        assert_eq!(response.status(), http::StatusCode::FORBIDDEN);
+        assert!(response.headers().contains_key(SET_COOKIE));
+        assert!(matches!(e, CsrfError::MissingCookie));
+
+        let e = handler
+            .call(request(Method::POST, slice::from_ref(&cookie), ""))
+            .await
+            .unwrap_err();
+        assert!(matches!(csrf_error(e), CsrfError::MissingToken));
+
+        let forged = (header.0.clone(), &*mask(&random::<TOKEN_LEN>()));
+        let e = handler
+            .call(request(Method::POST, &[cookie.clone(), forged], ""))
+            .await
+            .unwrap_err();
+        assert!(matches!(csrf_error(e), CsrfError::Mismatch));
+
+        assert!(handler
+            .call(request(Method::POST, &[cookie.clone(), header], ""))
+            .await
+            .is_ok());
+
+        // the token is read from forms, whose bodies are put back
+        let form = format!("name=predawn&csrf_token={token}");
+        let urlencoded = (CONTENT_TYPE, "application/x-www-form-urlencoded");
+
+        let response = handler
+            .call(request(Method::POST, &[cookie.clone(), urlencoded], &form))
+            .await
+            .unwrap();
+        assert!(body(response).await.ends_with(&form));
+
+        let multipart = format!(
+            "--boundary\r\n\
+            Content-Disposition: form-data; name=\"csrf_token\"\r\n\r\n\
+            {token}\r\n\
+            --boundary--\r\n"
+        );
+        let content_type = (CONTENT_TYPE, "multipart/form-data; boundary=boundary");
+
+        let response = handler
+            .call(request(Method::POST, &[cookie, content_type], &multipart))
+            .await
+            .unwrap();
+        assert!(body(response).await.ends_with(&multipart));
+    }
+}
@@ -0,0 +1,329 @@
+use std::{
+    net::{IpAddr, Ipv6Addr, SocketAddr},
+    sync::{Arc, RwLock},
+};
+
+use http::HeaderName;
+use ipnet::IpNet;
+use predawn_core::{
+    error::Error,
+    request::{Head, Request},
+    response::Response,
+};
+
+use super::Middleware;
+use crate::{config::ip_filter::IpFilterConfig, handler::Handler, response_error::IpFilterError};
+
+const X_FORWARDED_FOR: HeaderName = HeaderName::from_static("x-forwarded-for");
+
+/// An entry of [`IpFilterConfig`] which is neither an IP range nor an IP address.
+#[derive(Debug, thiserror::Error)]
+#[error("invalid IP range `{0}`")]
+pub struct IpFilterConfigError(String);
+
+/// The allow, deny and trusted proxy lists of [`IpFilter`].
+#[derive(Debug, Clone, Default)]
+pub struct IpLists {
+    allow: Vec<IpNet>,
+    deny: Vec<IpNet>,
+    trusted_proxies: Vec<IpNet>,
+}
+
+impl IpLists {
+    /// Allows any client.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_config(config: &IpFilterConfig) -> Result<Self, IpFilterConfigError> {
+        Ok(Self {
+            allow: parse_ranges(&config.allow)?,
+            deny: parse_ranges(&config.deny)?,
+            trusted_proxies: parse_ranges(&config.trusted_proxies)?,
+        })
+    }
+
+    /// Once a range is allowed, only the clients in the allowed ranges are allowed.
+    pub fn allow<R: Into<IpNet>>(mut self, range: R) -> Self {
+        self.allow.push(range.into());
+        self
+    }
+
+    /// Denies the clients in the range, even if they are allowed.
+    pub fn deny<R: Into<IpNet>>(mut self, range: R) -> Self {
+        self.deny.push(range.into());
+        self
+    }
+
+    /// Trusts the `X-Forwarded-For` header of the proxies in the range.
+    pub fn trust_proxy<R: Into<IpNet>>(mut self, range: R) -> Self {
+        self.trusted_proxies.push(range.into());
+        self
+    }
+
+    /// The client is the last address which is not a trusted proxy, looking from
+    /// the remote address back through `X-Forwarded-For`. An entry which is not an
+    /// address stops the search at the trusted proxy which forwarded it.
+    fn client_ip(&self, head: &Head) -> IpAddr {
+        let mut client = head.remote_addr().ip().to_canonical();
+
+        if !contains(&self.trusted_proxies, client) {
+            return client;
+        }
+
+        let forwarded = head
+            .headers
+            .get_all(X_FORWARDED_FOR)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .collect::<Vec<_>>();
+
+        for addr in forwarded.into_iter().rev() {
+            let Some(addr) = parse_forwarded(addr) else {
+                return client;
+            };
+
+            client = addr.to_canonical();
+
+            if !contains(&self.trusted_proxies, client) {
+                break;
+            }
+        }
+
+        client
+    }
+
+    fn check(&self, ip: IpAddr) -> Result<(), IpFilterError> {
+        if let Some(rule) = self.deny.iter().find(|range| range.contains(&ip)) {
+            return Err(IpFilterError {
+                ip,
+                rule: Some(*rule),
+            });
+        }
+
+        if !self.allow.is_empty() && !contains(&self.allow, ip) {
+            return Err(IpFilterError { ip, rule: None });
+        }
+
+        Ok(())
+    }
+}
+
+fn parse_ranges(ranges: &[String]) -> Result<Vec<IpNet>, IpFilterConfigError> {
+    ranges
+        .iter()
+        .map(|range| {
+            range
+                .parse::<IpNet>()
+                .or_else(|_| range.parse::<IpAddr>().map(IpNet::from))
+                .map_err(|_| IpFilterConfigError(range.clone()))
+        })
+        .collect()
+}
+
+/// Parses an entry of `X-Forwarded-For`, which some proxies send with a port,
+/// e.g. `1.2.3.4:5678`, `[::1]` or `[::1]:80`.
+fn parse_forwarded(addr: &str) -> Option<IpAddr> {
+    let addr = addr.trim();
+
+    addr.parse::<IpAddr>()
+        .or_else(|_| addr.parse::<SocketAddr>().map(|addr| addr.ip()))
+        .ok()
+        .or_else(|| {
+            addr.strip_prefix('[')?
+                .strip_suffix(']')?
+                .parse::<Ipv6Addr>()
+                .ok()
+                .map(IpAddr::V6)
+        })
+}
+
+fn contains(ranges: &[IpNet], ip: IpAddr) -> bool {
+    ranges.iter().any(|range| range.contains(&ip))
+}
+
+/// Rejects requests with `403 Forbidden` unless their client IP is allowed by the [`IpLists`],
+/// the client IP is the remote address, or the one forwarded by trusted proxies. The rejection
+/// is documented in OpenAPI by adding [`IpFilterError`] to the `errors` of the controller.
+///
+/// Clones share the lists, which are replaced at runtime by [`IpFilter::set_lists`].
+/// A filter shared by controllers is registered in the [`Context`](rudi::Context):
+///
+/// ```rust
+/// use predawn::{
+///     config::ip_filter::IpFilterConfig,
+///     handler::{Handler, HandlerExt},
+///     middleware::IpFilter,
+/// };
+/// use rudi::{Context, Singleton};
+///
+/// #[Singleton]
+/// fn CreateIpFilter(#[di(ref)] config: &IpFilterConfig) -> IpFilter {
+///     IpFilter::from_config(config).expect("invalid IP filter config")
+/// }
+///
+/// // used by `#[controller(middleware = internal_only, errors = [IpFilterError])]`
+/// fn internal_only<H: Handler>(cx: &mut Context, handler: H) -> impl Handler {
+///     handler.with(cx.resolve::<IpFilter>())
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct IpFilter {
+    lists: Arc<RwLock<Arc<IpLists>>>,
+}
+
+impl IpFilter {
+    pub fn new(lists: IpLists) -> Self {
+        Self {
+            lists: Arc::new(RwLock::new(Arc::new(lists))),
+        }
+    }
+
+    pub fn from_config(config: &IpFilterConfig) -> Result<Self, IpFilterConfigError> {
+        IpLists::from_config(config).map(Self::new)
+    }
+
+    pub fn lists(&self) -> Arc<IpLists> {
+        self.lists.read().unwrap().clone()
+    }
+
+    /// Replaces the lists of this filter and its clones, including the ones
+    /// which are already wrapping handlers.
+    pub fn set_lists(&self, lists: IpLists) {
+        *self.lists.write().unwrap() = Arc::new(lists);
+    }
+}
+
+impl<H: Handler> Middleware<H> for IpFilter {
+    type Output = IpFilterHandler<H>;
+
+    fn transform(self, input: H) -> Self::Output {
+        IpFilterHandler {
+            filter: self,
+            inner: input,
+        }
+    }
+}
+
+pub struct IpFilterHandler<H> {
+    filter: IpFilter,
+    inner: H,
+}
+
+impl<H: Handler> Handler for IpFilterHandler<H> {
+    async fn call(&self, req: Request) -> Result<Response, Error> {
+        let lists = self.filter.lists();
+        let ip = lists.client_ip(&req.head);
+
+        if let Err(e) = lists.check(ip) {
+            match &e.rule {
+                Some(rule) => tracing::warn!(%ip, %rule, "rejected a request by a deny rule"),
+                None => tracing::warn!(%ip, "rejected a request matching no allow rule"),
+            }
+
+            return Err(e.into());
+        }
+
+        self.inner.call(req).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(ip: &str) -> IpAddr {
+        ip.parse().unwrap()
+    }
+
+    #[test]
+    fn check() {
+        let config = IpFilterConfig {
+            allow: vec!["10.0.0.0/8".into(), "::1".into()],
+            deny: vec!["10.1.0.0/16".into()],
+            trusted_proxies: Vec::new(),
+        };
+
+        let lists = IpLists::from_config(&config).unwrap();
+
+        assert!(lists.check(ip("10.2.3.4")).is_ok());
+        assert!(lists.check(ip("::1")).is_ok());
+
+        let e = lists.check(ip("10.1.2.3")).unwrap_err();
+        assert_eq!(e.rule, Some("10.1.0.0/16".parse().unwrap()));
+
+        let e = lists.check(ip("192.168.1.1")).unwrap_err();
+        assert_eq!(e.rule, None);
+
+        assert!(IpLists::new().check(ip("192.168.1.1")).is_ok());
+
+        let config = IpFilterConfig {
+            allow: vec!["10.0.0.0/33".into()],
+            ..Default::default()
+        };
+
+        assert!(IpLists::from_config(&config).is_err());
+    }
+
+    #[test]
+    fn client_ip() {
+        use crate::middleware::test_request;
+
+        let lists = IpLists::new()
+            .trust_proxy("10.0.0.0/8".parse::<IpNet>().unwrap())
+            .trust_proxy(ip("::1"));
+
+        let client_ip = |remote_addr: &str, forwarded: &[&str]| {
+            let mut req = http::Request::builder().uri("/");
+
+            for value in forwarded {
+                req = req.header(X_FORWARDED_FOR, *value);
+            }
+
+            let req = test_request(req.body("").unwrap(), remote_addr);
+            lists.client_ip(&req.head)
+        };
+
+        // the header of untrusted clients is ignored
+        assert_eq!(client_ip("1.2.3.4:80", &["5.6.7.8"]), ip("1.2.3.4"));
+
+        // the chain is followed through trusted proxies only
+        assert_eq!(
+            client_ip("10.0.0.1:80", &["6.6.6.6, 1.2.3.4, 10.0.0.2"]),
+            ip("1.2.3.4")
+        );
+        assert_eq!(
+            client_ip("10.0.0.1:80", &["6.6.6.6", "1.2.3.4, 10.0.0.2"]),
+            ip("1.2.3.4")
+        );
+
+        // a spoofed trusted address in front of the client does not hide it
+        assert_eq!(
+            client_ip("10.0.0.1:80", &["10.0.0.3, 1.2.3.4"]),
+            ip("1.2.3.4")
+        );
+
+        // ports and brackets are stripped
+        assert_eq!(client_ip("10.0.0.1:80", &["1.2.3.4:5678"]), ip("1.2.3.4"));
+        assert_eq!(client_ip("10.0.0.1:80", &["[::1], 1.2.3.4"]), ip("1.2.3.4"));
+        assert_eq!(
+            client_ip("10.0.0.1:80", &["1.2.3.4, [::1]:80"]),
+            ip("1.2.3.4")
+        );
+        assert_eq!(client_ip("10.0.0.1:80", &["::ffff:1.2.3.4"]), ip("1.2.3.4"));
+
+        // an unparseable entry stops at the trusted proxy which forwarded it
+        assert_eq!(
+            client_ip("10.0.0.1:80", &["1.2.3.4, unknown"]),
+            ip("10.0.0.1")
+        );
+        assert_eq!(
+            client_ip("10.0.0.1:80", &["1.2.3.4, unknown, 10.0.0.2"]),
+            ip("10.0.0.2")
+        );
+
+        // only trusted proxies, the first one is the client
+        assert_eq!(client_ip("10.0.0.1:80", &["10.0.0.2"]), ip("10.0.0.2"));
+    }
+}
@@ -0,0 +1,830 @@
+use std::{
+    any::type_name,
+    fmt, io,
+    marker::PhantomData,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use http::HeaderValue;
+use predawn_core::{error::Error, request::Request, response::Response};
+use ring::{
+    hmac,
+    signature::{self, RsaPublicKeyComponents, UnparsedPublicKey},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::Middleware;
+use crate::{
+    config::jwt::JwtConfig,
+    extract::{authorization, is_bearer_scheme},
+    handler::Handler,
+    response_error::{JwtAuthError, JwtError},
+    SecurityScheme,
+};
+
+/// The algorithms which are supported by [`JwtAuth`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Algorithm {
+    HS256,
+    RS256,
+    ES256,
+}
+
+impl Algorithm {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Algorithm::HS256 => "HS256",
+            Algorithm::RS256 => "RS256",
+            Algorithm::ES256 => "ES256",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "HS256" => Some(Algorithm::HS256),
+            "RS256" => Some(Algorithm::RS256),
+            "ES256" => Some(Algorithm::ES256),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A key in the JSON Web Key format (RFC 7517),
+/// `oct` keys are used for `HS256`, `RSA` keys for `RS256` and `EC` `P-256` keys for `ES256`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Jwk {
+    pub kty: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kid: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alg: Option<String>,
+    #[serde(default, rename = "use", skip_serializing_if = "Option::is_none")]
+    pub use_: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub k: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub n: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub e: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub crv: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub x: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub y: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct JwkSet {
+    keys: Vec<Jwk>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum JwkError {
+    #[error("failed to read `{path}`: {error}")]
+    Io { path: PathBuf, error: io::Error },
+    #[error("invalid JSON Web Key Set: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("unsupported key type `{kty}`")]
+    UnsupportedKeyType { kty: String },
+    #[error("unsupported algorithm `{alg}` for key type `{kty}`")]
+    UnsupportedAlgorithm { kty: String, alg: String },
+    #[error("missing or invalid parameter `{0}`")]
+    InvalidParameter(&'static str),
+}
+
+enum KeyMaterial {
+    Hmac(hmac::Key),
+    Rsa(RsaPublicKeyComponents<Vec<u8>>),
+    /// An uncompressed point of the `P-256` curve.
+    Ec(Vec<u8>),
+}
+
+struct DecodingKey {
+    kid: Option<String>,
+    algorithm: Algorithm,
+    material: KeyMaterial,
+}
+
+impl DecodingKey {
+    fn verify(&self, message: &[u8], signature: &[u8]) -> bool {
+        match &self.material {
+            KeyMaterial::Hmac(key) => hmac::verify(key, message, signature).is_ok(),
+            KeyMaterial::Rsa(key) => key
+                .verify(&signature::RSA_PKCS1_2048_8192_SHA256, message, signature)
+                .is_ok(),
+            KeyMaterial::Ec(point) => {
+                UnparsedPublicKey::new(&signature::ECDSA_P256_SHA256_FIXED, point)
+                    .verify(message, signature)
+                    .is_ok()
+            }
+        }
+    }
+}
+
+/// The keys used to verify the signatures of tokens.
+#[derive(Default)]
+pub struct JwtKeys {
+    keys: Vec<DecodingKey>,
+}
+
+impl JwtKeys {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads the keys of a [`JwtConfig`], including the ones of its JWKS file.
+    pub fn from_config(config: &JwtConfig) -> Result<Self, JwkError> {
+        let mut keys = Self::new();
+
+        if let Some(secret) = &config.secret {
+            keys.add_secret(None, secret.as_bytes());
+        }
+
+        for jwk in &config.keys {
+            keys.add_jwk(jwk)?;
+        }
+
+        if let Some(path) = &config.jwks {
+            keys.load_jwks(path)?;
+        }
+
+        Ok(keys)
+    }
+
+    /// Adds a shared secret of `HS256` tokens.
+    pub fn add_secret(&mut self, kid: Option<String>, secret: &[u8]) {
+        self.keys.push(DecodingKey {
+            kid,
+            algorithm: Algorithm::HS256,
+            material: KeyMaterial::Hmac(hmac::Key::new(hmac::HMAC_SHA256, secret)),
+        });
+    }
+
+    pub fn add_jwk(&mut self, jwk: &Jwk) -> Result<(), JwkError> {
+        fn decode(value: &Option<String>, name: &'static str) -> Result<Vec<u8>, JwkError> {
+            value
+                .as_deref()
+                .and_then(|value| URL_SAFE_NO_PAD.decode(value.trim_end_matches('=')).ok())
+                .ok_or(JwkError::InvalidParameter(name))
+        }
+
+        let (algorithm, material) = match jwk.kty.as_str() {
+            "oct" => (Algorithm::HS256, {
+                let k = decode(&jwk.k, "k")?;
+                KeyMaterial::Hmac(hmac::Key::new(hmac::HMAC_SHA256, &k))
+            }),
+            "RSA" => (
+                Algorithm::RS256,
+                KeyMaterial::Rsa(RsaPublicKeyComponents {
+                    n: decode(&jwk.n, "n")?,
+                    e: decode(&jwk.e, "e")?,
+                }),
+            ),
+            "EC" => {
+                if jwk.crv.as_deref() != Some("P-256") {
+                    return Err(JwkError::InvalidParameter("crv"));
+                }
+
+                let x = decode(&jwk.x, "x")?;
+                let y = decode(&jwk.y, "y")?;
+
+                if x.len() != 32 {
+                    return Err(JwkError::InvalidParameter("x"));
+                }
+
+                if y.len() != 32 {
+                    return Err(JwkError::InvalidParameter("y"));
+                }
+
+                let mut point = Vec::with_capacity(65);
+                point.push(0x04);
+                point.extend(x);
+                point.extend(y);
+
+                (Algorithm::ES256, KeyMaterial::Ec(point))
+            }
+            kty => {
+                return Err(JwkError::UnsupportedKeyType {
+                    kty: kty.to_string(),
+                })
+            }
+        };
+
+        if let Some(alg) = &jwk.alg {
+            if alg != algorithm.as_str() {
+                return Err(JwkError::UnsupportedAlgorithm {
+                    kty: jwk.kty.clone(),
+                    alg: alg.clone(),
+                });
+            }
+        }
+
+        self.keys.push(DecodingKey {
+            kid: jwk.kid.clone(),
+            algorithm,
+            material,
+        });
+
+        Ok(())
+    }
+
+    /// Adds the signing keys of a JSON Web Key Set, encryption keys are skipped.
+    pub fn add_jwks(&mut self, json: &[u8]) -> Result<(), JwkError> {
+        let JwkSet { keys } = serde_json::from_slice(json)?;
+
+        keys.iter()
+            .filter(|jwk| jwk.use_.as_deref().unwrap_or("sig") == "sig")
+            .try_for_each(|jwk| self.add_jwk(jwk))
+    }
+
+    pub fn load_jwks<P: AsRef<Path>>(&mut self, path: P) -> Result<(), JwkError> {
+        let path = path.as_ref();
+
+        let json = std::fs::read(path).map_err(|error| JwkError::Io {
+            path: path.to_path_buf(),
+            error,
+        })?;
+
+        self.add_jwks(&json)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+}
+
+impl fmt::Debug for JwtKeys {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.keys.iter().map(|key| (&key.kid, key.algorithm)))
+            .finish()
+    }
+}
+
+#[derive(Deserialize)]
+struct JoseHeader {
+    alg: String,
+    kid: Option<String>,
+}
+
+/// The claims of a token verified by [`JwtAuth<S>`], which are extracted by
+/// [`Claims<T, S>`](crate::extract::Claims).
+pub(crate) struct VerifiedClaims<S> {
+    pub(crate) claims: Arc<Value>,
+    _marker: PhantomData<fn() -> S>,
+}
+
+impl<S> VerifiedClaims<S> {
+    pub(crate) fn scopes(&self) -> Vec<&str> {
+        scopes(&self.claims)
+    }
+}
+
+impl<S> Clone for VerifiedClaims<S> {
+    fn clone(&self) -> Self {
+        Self {
+            claims: self.claims.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// Verifies the `Authorization: Bearer <token>` header of requests as a JSON Web Token,
+/// the claims of valid tokens are available to handlers through
+/// [`Claims<T, S>`](crate::extract::Claims).
+///
+/// `S` must be an HTTP `bearer`, an OAuth2 or an OpenID Connect security scheme,
+/// its name is used as the realm of the `WWW-Authenticate` challenge.
+///
+/// `exp` is required, `nbf` is checked if it is present, `iss` and `aud` are checked if they
+/// are configured. Missing and invalid tokens are rejected with `401 Unauthorized`,
+/// tokens without the [required scopes](JwtAuth::required_scopes) with `403 Forbidden`.
+pub struct JwtAuth<S> {
+    keys: Arc<JwtKeys>,
+    issuer: Option<String>,
+    audience: Vec<String>,
+    leeway: Duration,
+    required_scopes: Vec<String>,
+    allow_anonymous: bool,
+    _marker: PhantomData<fn() -> S>,
+}
+
+impl<S> Clone for JwtAuth<S> {
+    fn clone(&self) -> Self {
+        Self {
+            keys: self.keys.clone(),
+            issuer: self.issuer.clone(),
+            audience: self.audience.clone(),
+            leeway: self.leeway,
+            required_scopes: self.required_scopes.clone(),
+            allow_anonymous: self.allow_anonymous,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<S> fmt::Debug for JwtAuth<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JwtAuth")
+            .field("scheme", &type_name::<S>())
+            .field("keys", &self.keys)
+            .field("issuer", &self.issuer)
+            .field("audience", &self.audience)
+            .field("leeway", &self.leeway)
+            .field("required_scopes", &self.required_scopes)
+            .field("allow_anonymous", &self.allow_anonymous)
+            .finish()
+    }
+}
+
+impl<S> JwtAuth<S> {
+    pub fn new(keys: JwtKeys) -> Self {
+        Self {
+            keys: Arc::new(keys),
+            issuer: None,
+            audience: Vec::new(),
+            leeway: Duration::from_secs(60),
+            required_scopes: Vec::new(),
+            allow_anonymous: false,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn from_config(config: &JwtConfig) -> Result<Self, JwkError> {
+        let mut auth = Self::new(JwtKeys::from_config(config)?);
+
+        auth.issuer.clone_from(&config.issuer);
+        auth.audience.clone_from(&config.audience);
+        auth.leeway = Duration::from_secs(config.leeway);
+
+        Ok(auth)
+    }
+
+    pub fn issuer<T: Into<String>>(mut self, issuer: T) -> Self {
+        self.issuer = Some(issuer.into());
+        self
+    }
+
+    /// Adds an accepted audience, a token must have one of them.
+    pub fn audience<T: Into<String>>(mut self, audience: T) -> Self {
+        self.audience.push(audience.into());
+        self
+    }
+
+    pub fn leeway(mut self, leeway: Duration) -> Self {
+        self.leeway = leeway;
+        self
+    }
+
+    /// The scopes which a token must have all of, in its `scope` or `scp` claim.
+    pub fn required_scopes<I, T>(mut self, scopes: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        self.required_scopes = scopes.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Passes requests without a bearer token to the inner handler, default is `false`.
+    pub fn allow_anonymous(mut self, allow_anonymous: bool) -> Self {
+        self.allow_anonymous = allow_anonymous;
+        self
+    }
+
+    /// Verifies a token and returns its claims.
+    pub fn verify(&self, token: &str) -> Result<Value, JwtError> {
+        let mut parts = token.split('.');
+
+        let (Some(header), Some(payload), Some(signature), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(JwtError::Malformed);
+        };
+
+        let header: JoseHeader = decode_json(header)?;
+
+        let Some(algorithm) = Algorithm::from_name(&header.alg) else {
+            return Err(JwtError::UnsupportedAlgorithm(header.alg));
+        };
+
+        let mut keys = self
+            .keys
+            .keys
+            .iter()
+            .filter(|key| key.algorithm == algorithm)
+            .filter(|key| match (&header.kid, &key.kid) {
+                (Some(kid), Some(key_id)) => kid == key_id,
+                _ => true,
+            })
+            .peekable();
+
+        if keys.peek().is_none() {
+            return Err(JwtError::UnknownKey);
+        }
+
+        let signature = URL_SAFE_NO_PAD
+            .decode(signature)
+            .map_err(|_| JwtError::Malformed)?;
+
+        let message = &token.as_bytes()[..header_and_payload_len(token)];
+
+        if !keys.any(|key| key.verify(message, &signature)) {
+            return Err(JwtError::InvalidSignature);
+        }
+
+        let claims: Value = decode_json(payload)?;
+
+        if !claims.is_object() {
+            return Err(JwtError::Malformed);
+        }
+
+        self.validate(&claims)?;
+
+        Ok(claims)
+    }
+
+    fn validate(&self, claims: &Value) -> Result<(), JwtError> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs_f64();
+
+        let leeway = self.leeway.as_secs_f64();
+
+        // a NumericDate may have a fraction of a second, e.g. `1700000000.5`
+        match claims.get("exp") {
+            Some(exp) => {
+                let exp = exp.as_f64().ok_or(JwtError::InvalidClaim("exp"))?;
+
+                if now > exp + leeway {
+                    return Err(JwtError::Expired);
+                }
+            }
+            None => return Err(JwtError::MissingClaim("exp")),
+        }
+
+        if let Some(nbf) = claims.get("nbf") {
+            let nbf = nbf.as_f64().ok_or(JwtError::InvalidClaim("nbf"))?;
+
+            if now + leeway < nbf {
+                return Err(JwtError::NotYetValid);
+            }
+        }
+
+        if let Some(issuer) = &self.issuer {
+            match claims.get("iss") {
+                Some(Value::String(iss)) if iss == issuer => {}
+                Some(_) => return Err(JwtError::InvalidIssuer),
+                None => return Err(JwtError::MissingClaim("iss")),
+            }
+        }
+
+        if !self.audience.is_empty() {
+            let matched = match claims.get("aud") {
+                Some(Value::String(aud)) => self.audience.contains(aud),
+                Some(Value::Array(auds)) => auds
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .any(|aud| self.audience.iter().any(|a| a == aud)),
+                Some(_) => false,
+                None => return Err(JwtError::MissingClaim("aud")),
+            };
+
+            if !matched {
+                return Err(JwtError::InvalidAudience);
+            }
+        }
+
+        if !self.required_scopes.is_empty() {
+            let scopes = scopes(claims);
+
+            if !self
+                .required_scopes
+                .iter()
+                .all(|required| scopes.contains(&required.as_str()))
+            {
+                return Err(JwtError::InsufficientScope);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The scopes of the `scope` claim (space separated), or of the `scp` claim (string or array).
+fn scopes(claims: &Value) -> Vec<&str> {
+    match claims.get("scope").or_else(|| claims.get("scp")) {
+        Some(Value::String(scope)) => scope.split_ascii_whitespace().collect(),
+        Some(Value::Array(scopes)) => scopes.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn header_and_payload_len(token: &str) -> usize {
+    token.rfind('.').unwrap_or(token.len())
+}
+
+fn decode_json<T: for<'de> Deserialize<'de>>(part: &str) -> Result<T, JwtError> {
+    let bytes = URL_SAFE_NO_PAD
+        .decode(part)
+        .map_err(|_| JwtError::Malformed)?;
+
+    serde_json::from_slice(&bytes).map_err(|_| JwtError::Malformed)
+}
+
+impl<S: SecurityScheme + 'static, H: Handler> Middleware<H> for JwtAuth<S> {
+    type Output = JwtAuthHandler<H, S>;
+
+    #[track_caller]
+    fn transform(self, input: H) -> Self::Output {
+        if !is_bearer_scheme::<S>() {
+            panic!(
+                "`JwtAuth<{}>` requires an HTTP `bearer`, an OAuth2 or an OpenID Connect security scheme",
+                type_name::<S>()
+            );
+        }
+
+        JwtAuthHandler {
+            auth: self,
+            inner: input,
+        }
+    }
+}
+
+pub struct JwtAuthHandler<H, S> {
+    auth: JwtAuth<S>,
+    inner: H,
+}
+
+impl<H, S> JwtAuthHandler<H, S>
+where
+    S: SecurityScheme,
+{
+    fn error(&self, error: JwtError) -> JwtAuthError {
+        let challenge = match &error {
+            JwtError::Missing => format!("Bearer realm=\"{}\"", S::NAME),
+            JwtError::InsufficientScope => format!(
+                "Bearer realm=\"{}\", error=\"insufficient_scope\", scope=\"{}\"",
+                S::NAME,
+                self.auth.required_scopes.join(" ")
+            ),
+            error => {
+                // the algorithm is taken from the token, so it's never echoed
+                let description = match error {
+                    JwtError::UnsupportedAlgorithm(_) => "unsupported algorithm".to_string(),
+                    error => error.to_string(),
+                };
+
+                format!(
+                    "Bearer realm=\"{}\", error=\"invalid_token\", error_description=\"{}\"",
+                    S::NAME,
+                    description
+                )
+            }
+        };
+
+        // the scheme name and the scopes are not checked up front, fall back to a bare challenge
+        let challenge =
+            HeaderValue::try_from(challenge).unwrap_or_else(|_| HeaderValue::from_static("Bearer"));
+
+        JwtAuthError { challenge, error }
+    }
+}
+
+impl<H, S> Handler for JwtAuthHandler<H, S>
+where
+    H: Handler,
+    S: SecurityScheme + 'static,
+{
+    async fn call(&self, mut req: Request) -> Result<Response, Error> {
+        let Some(authorization) = authorization(&req.head, "Bearer") else {
+            if self.auth.allow_anonymous {
+                return self.inner.call(req).await;
+            }
+
+            return Err(self.error(JwtError::Missing).into());
+        };
+
+        let claims = match self.auth.verify(authorization.credentials()) {
+            Ok(claims) => claims,
+            Err(e) => {
+                ::tracing::debug!(error = %e, scheme = S::NAME, "rejected bearer token");
+                return Err(self.error(e).into());
+            }
+        };
+
+        req.head.extensions.insert(VerifiedClaims::<S> {
+            claims: Arc::new(claims),
+            _marker: PhantomData,
+        });
+
+        self.inner.call(req).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ring::{
+        rand::SystemRandom,
+        signature::{EcdsaKeyPair, KeyPair, ECDSA_P256_SHA256_FIXED_SIGNING},
+    };
+    use serde_json::json;
+
+    use super::*;
+
+    #[derive(crate::SecurityScheme)]
+    #[http(scheme = bearer)]
+    struct Bearer;
+
+    fn now() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+    }
+
+    fn encode<F>(alg: &str, claims: &Value, sign: F) -> String
+    where
+        F: FnOnce(&[u8]) -> Vec<u8>,
+    {
+        let header = URL_SAFE_NO_PAD.encode(json!({ "alg": alg, "typ": "JWT" }).to_string());
+        let payload = URL_SAFE_NO_PAD.encode(claims.to_string());
+        let message = format!("{}.{}", header, payload);
+        let signature = URL_SAFE_NO_PAD.encode(sign(message.as_bytes()));
+
+        format!("{}.{}", message, signature)
+    }
+
+    fn hs256(claims: &Value, secret: &[u8]) -> String {
+        encode("HS256", claims, |message| {
+            let key = hmac::Key::new(hmac::HMAC_SHA256, secret);
+            hmac::sign(&key, message).as_ref().to_vec()
+        })
+    }
+
+    fn auth() -> JwtAuth<()> {
+        let mut keys = JwtKeys::new();
+        keys.add_secret(None, b"secret");
+
+        JwtAuth::new(keys)
+            .issuer("predawn")
+            .audience("api")
+            .leeway(Duration::from_secs(5))
+    }
+
+    #[test]
+    fn verify_hs256() {
+        let auth = auth();
+
+        let claims = json!({ "sub": "alice", "iss": "predawn", "aud": ["api"], "exp": now() + 60 });
+        assert_eq!(auth.verify(&hs256(&claims, b"secret")).unwrap(), claims);
+
+        assert!(matches!(
+            auth.verify(&hs256(&claims, b"other")),
+            Err(JwtError::InvalidSignature)
+        ));
+
+        let expired = json!({ "iss": "predawn", "aud": "api", "exp": now() - 10 });
+        assert!(matches!(
+            auth.verify(&hs256(&expired, b"secret")),
+            Err(JwtError::Expired)
+        ));
+
+        let within_leeway = json!({ "iss": "predawn", "aud": "api", "exp": now() - 2 });
+        assert!(auth.verify(&hs256(&within_leeway, b"secret")).is_ok());
+
+        let fractional = json!({ "iss": "predawn", "aud": "api", "exp": now() as f64 + 60.5, "nbf": now() as f64 - 0.5 });
+        assert!(auth.verify(&hs256(&fractional, b"secret")).is_ok());
+
+        let expired_fractional =
+            json!({ "iss": "predawn", "aud": "api", "exp": now() as f64 - 10.5 });
+        assert!(matches!(
+            auth.verify(&hs256(&expired_fractional, b"secret")),
+            Err(JwtError::Expired)
+        ));
+
+        let not_a_date = json!({ "iss": "predawn", "aud": "api", "exp": "tomorrow" });
+        assert!(matches!(
+            auth.verify(&hs256(&not_a_date, b"secret")),
+            Err(JwtError::InvalidClaim("exp"))
+        ));
+
+        let not_yet_valid =
+            json!({ "iss": "predawn", "aud": "api", "exp": now() + 60, "nbf": now() + 30 });
+        assert!(matches!(
+            auth.verify(&hs256(&not_yet_valid, b"secret")),
+            Err(JwtError::NotYetValid)
+        ));
+
+        let other_audience = json!({ "iss": "predawn", "aud": "web", "exp": now() + 60 });
+        assert!(matches!(
+            auth.verify(&hs256(&other_audience, b"secret")),
+            Err(JwtError::InvalidAudience)
+        ));
+
+        let other_issuer = json!({ "iss": "other", "aud": "api", "exp": now() + 60 });
+        assert!(matches!(
+            auth.verify(&hs256(&other_issuer, b"secret")),
+            Err(JwtError::InvalidIssuer)
+        ));
+
+        let no_exp = json!({ "iss": "predawn", "aud": "api" });
+        assert!(matches!(
+            auth.verify(&hs256(&no_exp, b"secret")),
+            Err(JwtError::MissingClaim("exp"))
+        ));
+
+        let none = format!(
+            "{}.{}.",
+            URL_SAFE_NO_PAD.encode(r#"{"alg":"none"}"#),
+            URL_SAFE_NO_PAD.encode(claims.to_string())
+        );
+        assert!(matches!(
+            auth.verify(&none),
+            Err(JwtError::UnsupportedAlgorithm(_))
+        ));
+
+        let control = format!(
+            "{}.{}.",
+            URL_SAFE_NO_PAD.encode(r#"{"alg":"\r\nX-Injected: 1"}"#),
+            URL_SAFE_NO_PAD.encode(claims.to_string())
+        );
+        let e = auth.verify(&control).unwrap_err();
+        assert!(matches!(e, JwtError::UnsupportedAlgorithm(_)));
+
+        let handler = JwtAuthHandler {
+            auth: JwtAuth::<Bearer>::new(JwtKeys::new()),
+            inner: (),
+        };
+        assert_eq!(
+            handler.error(e).challenge,
+            r#"Bearer realm="Bearer", error="invalid_token", error_description="unsupported algorithm""#
+        );
+
+        let auth = auth.required_scopes(["read", "write"]);
+
+        let read = json!({ "iss": "predawn", "aud": "api", "exp": now() + 60, "scope": "read" });
+        assert!(matches!(
+            auth.verify(&hs256(&read, b"secret")),
+            Err(JwtError::InsufficientScope)
+        ));
+
+        let read_write =
+            json!({ "iss": "predawn", "aud": "api", "exp": now() + 60, "scp": ["write", "read"] });
+        assert!(auth.verify(&hs256(&read_write, b"secret")).is_ok());
+    }
+
+    #[test]
+    fn verify_es256_from_jwks() {
+        let rng = SystemRandom::new();
+        let pkcs8 = EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, &rng).unwrap();
+        let key_pair =
+            EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, pkcs8.as_ref(), &rng)
+                .unwrap();
+
+        let point = key_pair.public_key().as_ref();
+
+        let jwks = json!({
+            "keys": [
+                { "kty": "RSA", "use": "enc", "n": "AQAB", "e": "AQAB" },
+                {
+                    "kty": "EC",
+                    "crv": "P-256",
+                    "alg": "ES256",
+                    "kid": "1",
+                    "x": URL_SAFE_NO_PAD.encode(&point[1..33]),
+                    "y": URL_SAFE_NO_PAD.encode(&point[33..]),
+                }
+            ]
+        });
+
+        let mut keys = JwtKeys::new();
+        keys.add_jwks(jwks.to_string().as_bytes()).unwrap();
+
+        let auth = JwtAuth::<()>::new(keys);
+
+        let claims = json!({ "sub": "bob", "exp": now() + 60 });
+        let token = encode("ES256", &claims, |message| {
+            key_pair.sign(&rng, message).unwrap().as_ref().to_vec()
+        });
+
+        assert_eq!(auth.verify(&token).unwrap(), claims);
+
+        assert!(matches!(
+            auth.verify(&hs256(&claims, b"secret")),
+            Err(JwtError::UnknownKey)
+        ));
+    }
+}
@@ -36,24 +36,19 @@ pub struct RequestBodyLimitHandler<H> {
 
 impl<H: Handler> Handler for RequestBodyLimitHandler<H> {
     async fn call(&self, mut req: Request) -> Result<Response, Error> {
-        let content_length = req.head.content_length();
-
-        let limit = match content_length {
-            Some(len) => {
-                if len > self.limit {
-                    return Err(RequestBodyLimitError {
-                        actual: Some(len),
-                        expected: self.limit,
-                    }
-                    .into());
-                } else {
-                    len
+        if let Some(len) = req.head.content_length() {
+            if len > self.limit {
+                return Err(RequestBodyLimitError {
+                    actual: Some(len),
+                    expected: self.limit,
                 }
+                .into());
             }
-            None => self.limit,
-        };
+        }
 
-        req.head.body_limit = BodyLimit(limit);
+        // not narrowed to `Content-Length`, which is the compressed size of a request
+        // which is decompressed by an inner middleware
+        req.head.body_limit = BodyLimit(self.limit);
 
         self.inner.call(req).await
     }
@@ -1,16 +1,40 @@
+#[cfg_attr(docsrs, doc(cfg(feature = "compression")))]
+#[cfg(feature = "compression")]
+mod compression;
+mod cors;
+mod csrf;
+mod ip_filter;
+mod jwt;
 mod limit;
+mod rate_limit;
+mod security_headers;
+mod session;
 #[cfg_attr(docsrs, doc(cfg(feature = "tower-compat")))]
 #[cfg(feature = "tower-compat")]
 mod tower_compat;
 mod tracing;
 
+#[cfg_attr(docsrs, doc(cfg(feature = "compression")))]
+#[cfg(feature = "compression")]
+pub use self::compression::{Compression, CompressionHandler, Decompression, DecompressionHandler};
 #[cfg_attr(docsrs, doc(cfg(feature = "tower-compat")))]
 #[cfg(feature = "tower-compat")]
 pub use self::tower_compat::TowerLayerCompatExt;
 pub use self::{
+    cors::{Cors, CorsConfigError, CorsHandler},
+    csrf::{Csrf, CsrfHandler},
+    ip_filter::{IpFilter, IpFilterConfigError, IpFilterHandler, IpLists},
+    jwt::{Algorithm, Jwk, JwkError, JwtAuth, JwtAuthHandler, JwtKeys},
     limit::{RequestBodyLimit, RequestBodyLimitHandler},
+    rate_limit::{RateLimit, RateLimitHandler},
+    security_headers::{CspOptOut, SecurityHeaders, SecurityHeadersHandler},
+    session::{SessionHandler, SessionMiddleware},
     tracing::{Tracing, TracingHandler},
 };
+pub(crate) use self::{
+    jwt::VerifiedClaims,
+    rate_limit::{insert_rate_limit_headers, whole_seconds, RATE_LIMIT_HEADERS},
+};
 use crate::handler::Handler;
 
 pub trait Middleware<H: Handler> {
@@ -18,3 +42,34 @@ pub trait Middleware<H: Handler> {
 
     fn transform(self, input: H) -> Self::Output;
 }
+
+/// Builds a request sent from `remote_addr`, for testing middlewares.
+#[cfg(test)]
+pub(crate) fn test_request<B>(
+    req: http::Request<B>,
+    remote_addr: &str,
+) -> predawn_core::request::Request
+where
+    B: Into<bytes::Bytes>,
+{
+    use http_body_util::Full;
+    use predawn_core::{
+        body::IncomingBody,
+        request::{BodyLimit, LocalAddr, OriginalUri, RemoteAddr, DEFAULT_BODY_LIMIT},
+    };
+
+    let (mut parts, body) = req.into_parts();
+
+    parts.extensions.insert(BodyLimit(DEFAULT_BODY_LIMIT));
+    parts
+        .extensions
+        .insert(LocalAddr("127.0.0.1:8080".parse().unwrap()));
+    parts
+        .extensions
+        .insert(RemoteAddr(remote_addr.parse().unwrap()));
+    parts.extensions.insert(OriginalUri(parts.uri.clone()));
+
+    let body = IncomingBody::new(Full::new(body.into()));
+
+    http::Request::from_parts(parts, body).try_into().unwrap()
+}
@@ -0,0 +1,369 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use http::{HeaderMap, HeaderName, HeaderValue};
+use predawn_core::{
+    error::Error,
+    request::{Head, Request},
+    response::Response,
+};
+
+use super::Middleware;
+use crate::{
+    config::rate_limit::{RateLimitConfig, RateLimitKey},
+    handler::Handler,
+    response_error::RateLimitError,
+    route::MatchedRoute,
+};
+
+/// The `RateLimit-*` headers of responses and their descriptions in OpenAPI.
+pub(crate) const RATE_LIMIT_HEADERS: [(&str, &str); 3] = [
+    ("ratelimit-limit", "The number of requests allowed at once."),
+    (
+        "ratelimit-remaining",
+        "The number of requests still allowed.",
+    ),
+    (
+        "ratelimit-reset",
+        "The seconds until the limit is fully restored.",
+    ),
+];
+
+pub(crate) fn insert_rate_limit_headers(
+    headers: &mut HeaderMap,
+    limit: u32,
+    remaining: u32,
+    reset: Duration,
+) {
+    let [limit_name, remaining_name, reset_name] =
+        RATE_LIMIT_HEADERS.map(|(name, _)| HeaderName::from_static(name));
+
+    headers.insert(limit_name, HeaderValue::from(limit));
+    headers.insert(remaining_name, HeaderValue::from(remaining));
+    headers.insert(reset_name, HeaderValue::from(whole_seconds(reset)));
+}
+
+/// Rounds up, as the headers have no fractions of seconds.
+pub(crate) fn whole_seconds(duration: Duration) -> u64 {
+    duration.as_secs() + u64::from(duration.subsec_nanos() > 0)
+}
+
+/// Limits the rate of requests with the generic cell rate algorithm, which behaves like
+/// a bucket of `burst` tokens refilled at `requests` per `period`.
+///
+/// Every key, the IP address of the client by default, has its own limit. Requests over
+/// the limit are rejected with `429 Too Many Requests` and a `Retry-After` header, all
+/// responses have the `RateLimit-Limit`, `RateLimit-Remaining` and `RateLimit-Reset`
+/// headers. The rejection is documented in OpenAPI by adding
+/// [`RateLimitError`] to the `errors` of the handler or the controller.
+///
+/// Clones share the limits, a new one is created by [`RateLimit::new`] or by any setter.
+#[derive(Debug, Clone)]
+pub struct RateLimit {
+    inner: Arc<RateLimitInner>,
+    buckets: Arc<Mutex<Buckets>>,
+}
+
+#[derive(Debug, Clone)]
+struct RateLimitInner {
+    /// The interval between two requests at the sustained rate.
+    interval: Duration,
+    burst: u32,
+    /// `interval * burst`, how far ahead of now the arrival of a key may be.
+    tolerance: Duration,
+    key: Key,
+}
+
+#[derive(Debug, Clone)]
+enum Key {
+    RemoteAddr,
+    Header(HeaderName),
+    Route,
+}
+
+#[derive(Debug, Default)]
+struct Buckets {
+    /// The theoretical arrival time of the next request of every key,
+    /// the limit of a key is fully restored once it has passed.
+    arrivals: HashMap<Box<[u8]>, Instant>,
+    next_purge: Option<Instant>,
+}
+
+impl RateLimit {
+    /// Allows `requests` per `period`, which are all allowed at once by default.
+    #[track_caller]
+    pub fn new(requests: u32, period: Duration) -> Self {
+        if requests == 0 || period.is_zero() {
+            panic!("a rate limit must allow at least one request in a non-empty period");
+        }
+
+        let interval = period / requests;
+
+        Self {
+            inner: Arc::new(RateLimitInner {
+                interval,
+                burst: requests,
+                tolerance: tolerance(interval, requests),
+                key: Key::RemoteAddr,
+            }),
+            buckets: Default::default(),
+        }
+    }
+
+    #[track_caller]
+    pub fn from_config(config: &RateLimitConfig) -> Self {
+        let mut rate_limit = Self::new(config.requests, Duration::from_secs(config.period));
+
+        if let Some(burst) = config.burst {
+            rate_limit = rate_limit.burst(burst);
+        }
+
+        match (config.key, &config.header_name) {
+            (RateLimitKey::RemoteAddr, _) => rate_limit,
+            (RateLimitKey::Header, Some(name)) => rate_limit.key_by_header(name),
+            (RateLimitKey::Header, None) => {
+                panic!("`header_name` is required to limit requests by a header")
+            }
+            (RateLimitKey::Route, _) => rate_limit.key_by_route(),
+        }
+    }
+
+    fn inner_mut(&mut self) -> &mut RateLimitInner {
+        self.buckets = Default::default();
+        Arc::make_mut(&mut self.inner)
+    }
+
+    /// The number of requests allowed at once.
+    #[track_caller]
+    pub fn burst(mut self, burst: u32) -> Self {
+        if burst == 0 {
+            panic!("a rate limit must allow at least one request at once");
+        }
+
+        let inner = self.inner_mut();
+        inner.tolerance = tolerance(inner.interval, burst);
+        inner.burst = burst;
+        self
+    }
+
+    /// Limits requests by the IP address of the client, which is the default.
+    pub fn key_by_remote_addr(mut self) -> Self {
+        self.inner_mut().key = Key::RemoteAddr;
+        self
+    }
+
+    /// Limits requests by the value of a header, e.g. an API key,
+    /// the requests without it share a limit.
+    #[track_caller]
+    pub fn key_by_header(mut self, name: &str) -> Self {
+        match HeaderName::try_from(name) {
+            Ok(name) => self.inner_mut().key = Key::Header(name),
+            Err(_) => panic!("`{name}` is not a valid header name"),
+        }
+
+        self
+    }
+
+    /// Limits requests by the route matched by the router, e.g. `/users/{id}`.
+    ///
+    /// The route is only known by handlers behind the router, such as the ones wrapped by
+    /// the `middleware` of a handler or a controller, others limit requests by their path.
+    pub fn key_by_route(mut self) -> Self {
+        self.inner_mut().key = Key::Route;
+        self
+    }
+
+    fn key(&self, head: &Head) -> Box<[u8]> {
+        match &self.inner.key {
+            Key::RemoteAddr => head.remote_addr().ip().to_string().into_bytes().into(),
+            Key::Header(name) => head
+                .headers
+                .get(name)
+                .map(HeaderValue::as_bytes)
+                .unwrap_or_default()
+                .into(),
+            Key::Route => head
+                .extensions
+                .get::<MatchedRoute>()
+                .map_or(head.uri.path(), MatchedRoute::as_str)
+                .as_bytes()
+                .into(),
+        }
+    }
+
+    /// Returns the number of the remaining requests and how long until the limit of the key
+    /// is fully restored.
+    fn acquire(&self, key: Box<[u8]>, now: Instant) -> Result<(u32, Duration), RateLimitError> {
+        let RateLimitInner {
+            interval,
+            burst,
+            tolerance,
+            ..
+        } = *self.inner;
+
+        let mut buckets = self.buckets.lock().unwrap();
+        buckets.purge(now, tolerance);
+
+        let arrival = buckets
+            .arrivals
+            .get(&key)
+            .map_or(now, |arrival| now.max(*arrival));
+
+        let next = arrival + interval;
+        let reset = next - now;
+
+        if reset > tolerance {
+            return Err(RateLimitError {
+                limit: burst,
+                retry_after: reset - tolerance,
+                reset: arrival - now,
+            });
+        }
+
+        buckets.arrivals.insert(key, next);
+
+        let remaining = (tolerance - reset).as_nanos() / interval.as_nanos();
+
+        Ok((remaining as u32, reset))
+    }
+}
+
+/// Returns `interval * burst`, panics if it overflows or if the arrivals, which are at most
+/// `interval * (burst + 1)` ahead of now, may overflow while the limit is used.
+#[track_caller]
+fn tolerance(interval: Duration, burst: u32) -> Duration {
+    let tolerance = interval.checked_mul(burst);
+
+    // leaves as much room again for the time the limit is used
+    let representable = tolerance
+        .and_then(|tolerance| tolerance.checked_add(interval))
+        .and_then(|ahead| ahead.checked_mul(2))
+        .and_then(|ahead| Instant::now().checked_add(ahead));
+
+    match (tolerance, representable) {
+        (Some(tolerance), Some(_)) => tolerance,
+        _ => panic!("a rate limit of {burst} requests at once every {interval:?} is too long"),
+    }
+}
+
+impl Buckets {
+    /// Removes the keys whose limits are fully restored, at most once in `tolerance`.
+    fn purge(&mut self, now: Instant, tolerance: Duration) {
+        if self.next_purge.is_some_and(|next_purge| now < next_purge) {
+            return;
+        }
+
+        self.arrivals.retain(|_, arrival| *arrival > now);
+        self.next_purge = Some(now + tolerance);
+    }
+}
+
+impl<H: Handler> Middleware<H> for RateLimit {
+    type Output = RateLimitHandler<H>;
+
+    fn transform(self, input: H) -> Self::Output {
+        RateLimitHandler {
+            rate_limit: self,
+            inner: input,
+        }
+    }
+}
+
+pub struct RateLimitHandler<H> {
+    rate_limit: RateLimit,
+    inner: H,
+}
+
+impl<H: Handler> Handler for RateLimitHandler<H> {
+    async fn call(&self, req: Request) -> Result<Response, Error> {
+        let key = self.rate_limit.key(&req.head);
+
+        let (remaining, reset) = match self.rate_limit.acquire(key, Instant::now()) {
+            Ok(acquired) => acquired,
+            Err(e) => {
+                tracing::debug!(
+                    retry_after = ?e.retry_after,
+                    "rejected a request by rate limiting"
+                );
+
+                return Err(e.into());
+            }
+        };
+
+        let mut result = self.inner.call(req).await;
+
+        let response = match &mut result {
+            Ok(response) => response,
+            Err(error) => error.response_mut(),
+        };
+
+        insert_rate_limit_headers(
+            response.headers_mut(),
+            self.rate_limit.inner.burst,
+            remaining,
+            reset,
+        );
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn acquire() {
+        let rate_limit = RateLimit::new(2, Duration::from_secs(2)).burst(3);
+        let key = || Box::<[u8]>::from(&b"key"[..]);
+
+        let now = Instant::now();
+
+        assert_eq!(
+            rate_limit.acquire(key(), now).unwrap(),
+            (2, Duration::from_secs(1))
+        );
+        assert_eq!(
+            rate_limit.acquire(key(), now).unwrap(),
+            (1, Duration::from_secs(2))
+        );
+        assert_eq!(
+            rate_limit.acquire(key(), now).unwrap(),
+            (0, Duration::from_secs(3))
+        );
+
+        let e = rate_limit.acquire(key(), now).unwrap_err();
+        assert_eq!(e.limit, 3);
+        assert_eq!(e.retry_after, Duration::from_secs(1));
+        assert_eq!(e.reset, Duration::from_secs(3));
+
+        assert!(rate_limit.acquire(Box::from(&b"other"[..]), now).is_ok());
+
+        let later = now + Duration::from_secs(1);
+        assert_eq!(
+            rate_limit.acquire(key(), later).unwrap(),
+            (0, Duration::from_secs(3))
+        );
+
+        let restored = now + Duration::from_secs(10);
+        assert_eq!(
+            rate_limit.acquire(key(), restored).unwrap(),
+            (2, Duration::from_secs(1))
+        );
+    }
+
+    #[test]
+    #[should_panic = "too long"]
+    fn overflowing_burst() {
+        let _ = RateLimit::new(1, Duration::from_secs(u64::MAX / 2)).burst(3);
+    }
+
+    #[test]
+    #[should_panic = "too long"]
+    fn overflowing_period() {
+        let _ = RateLimit::new(1, Duration::MAX);
+    }
+}
@@ -0,0 +1,296 @@
+use std::sync::Arc;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use http::{
+    header::{
+        InvalidHeaderValue, CONTENT_SECURITY_POLICY, CONTENT_SECURITY_POLICY_REPORT_ONLY,
+        REFERRER_POLICY, STRICT_TRANSPORT_SECURITY, X_CONTENT_TYPE_OPTIONS, X_FRAME_OPTIONS,
+    },
+    HeaderName, HeaderValue,
+};
+use predawn_core::{error::Error, request::Request, response::Response};
+use ring::rand::{SecureRandom, SystemRandom};
+
+use super::Middleware;
+use crate::{config::security_headers::SecurityHeadersConfig, extract::CspNonce, handler::Handler};
+
+const PERMISSIONS_POLICY: HeaderName = HeaderName::from_static("permissions-policy");
+
+const NONCE_PLACEHOLDER: &str = "{nonce}";
+
+/// Inserted into the extensions of a response to keep [`SecurityHeaders`] from setting
+/// `Content-Security-Policy` on it, e.g. by the OpenAPI UI plugins, which load scripts and
+/// styles from CDNs and inline them.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CspOptOut;
+
+/// Sets `Strict-Transport-Security`, `Content-Security-Policy`, `X-Content-Type-Options`,
+/// `X-Frame-Options`, `Referrer-Policy` and `Permissions-Policy` on responses,
+/// unless they already have them.
+///
+/// `{nonce}` in the policy is replaced by a nonce which is generated for every request,
+/// handlers get it with the [`CspNonce`] extractor. A response opts out of the policy
+/// with [`CspOptOut`].
+///
+/// The defaults are the ones of [`SecurityHeadersConfig`], an empty value disables a header.
+#[derive(Debug, Clone)]
+pub struct SecurityHeaders {
+    inner: Arc<SecurityHeadersInner>,
+}
+
+#[derive(Debug, Clone)]
+struct SecurityHeadersInner {
+    strict_transport_security: Option<HeaderValue>,
+    content_security_policy: Option<String>,
+    content_security_policy_report_only: bool,
+    x_content_type_options: Option<HeaderValue>,
+    x_frame_options: Option<HeaderValue>,
+    referrer_policy: Option<HeaderValue>,
+    permissions_policy: Option<HeaderValue>,
+}
+
+impl Default for SecurityHeaders {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SecurityHeaders {
+    pub fn new() -> Self {
+        Self::from_config(&SecurityHeadersConfig::default())
+            .expect("unreachable: the default security headers must be valid")
+    }
+
+    pub fn from_config(config: &SecurityHeadersConfig) -> Result<Self, InvalidHeaderValue> {
+        let content_security_policy = non_empty(&config.content_security_policy);
+
+        if let Some(policy) = &content_security_policy {
+            HeaderValue::try_from(policy.replace(NONCE_PLACEHOLDER, "nonce"))?;
+        }
+
+        Ok(Self {
+            inner: Arc::new(SecurityHeadersInner {
+                strict_transport_security: header_value(&config.strict_transport_security)?,
+                content_security_policy,
+                content_security_policy_report_only: config.content_security_policy_report_only,
+                x_content_type_options: header_value(&config.x_content_type_options)?,
+                x_frame_options: header_value(&config.x_frame_options)?,
+                referrer_policy: header_value(&config.referrer_policy)?,
+                permissions_policy: header_value(&config.permissions_policy)?,
+            }),
+        })
+    }
+
+    fn inner_mut(&mut self) -> &mut SecurityHeadersInner {
+        Arc::make_mut(&mut self.inner)
+    }
+
+    #[track_caller]
+    pub fn strict_transport_security(mut self, value: &str) -> Self {
+        self.inner_mut().strict_transport_security = expect_header_value(value);
+        self
+    }
+
+    /// `{nonce}` is replaced by the nonce of the request.
+    #[track_caller]
+    pub fn content_security_policy(mut self, policy: &str) -> Self {
+        let policy = non_empty(policy);
+
+        if let Some(policy) = &policy {
+            expect_header_value(&policy.replace(NONCE_PLACEHOLDER, "nonce"));
+        }
+
+        self.inner_mut().content_security_policy = policy;
+        self
+    }
+
+    /// Sends the policy as `Content-Security-Policy-Report-Only`.
+    pub fn content_security_policy_report_only(mut self, report_only: bool) -> Self {
+        self.inner_mut().content_security_policy_report_only = report_only;
+        self
+    }
+
+    #[track_caller]
+    pub fn x_content_type_options(mut self, value: &str) -> Self {
+        self.inner_mut().x_content_type_options = expect_header_value(value);
+        self
+    }
+
+    #[track_caller]
+    pub fn x_frame_options(mut self, value: &str) -> Self {
+        self.inner_mut().x_frame_options = expect_header_value(value);
+        self
+    }
+
+    #[track_caller]
+    pub fn referrer_policy(mut self, value: &str) -> Self {
+        self.inner_mut().referrer_policy = expect_header_value(value);
+        self
+    }
+
+    #[track_caller]
+    pub fn permissions_policy(mut self, value: &str) -> Self {
+        self.inner_mut().permissions_policy = expect_header_value(value);
+        self
+    }
+
+    fn nonce(&self) -> Option<String> {
+        let policy = self.inner.content_security_policy.as_ref()?;
+
+        if !policy.contains(NONCE_PLACEHOLDER) {
+            return None;
+        }
+
+        let mut bytes = [0u8; 16];
+
+        SystemRandom::new()
+            .fill(&mut bytes)
+            .expect("failed to generate a CSP nonce");
+
+        Some(STANDARD.encode(bytes))
+    }
+
+    fn apply(&self, response: &mut Response, nonce: Option<&str>) {
+        let inner = &*self.inner;
+
+        let csp_opt_out = response.extensions().get::<CspOptOut>().is_some();
+
+        let content_security_policy = inner
+            .content_security_policy
+            .as_ref()
+            .filter(|_| !csp_opt_out)
+            .map(|policy| match nonce {
+                Some(nonce) => policy.replace(NONCE_PLACEHOLDER, nonce),
+                None => policy.clone(),
+            })
+            .map(|policy| {
+                HeaderValue::try_from(policy).expect("unreachable: the nonce is base64 encoded")
+            });
+
+        let content_security_policy_name = if inner.content_security_policy_report_only {
+            CONTENT_SECURITY_POLICY_REPORT_ONLY
+        } else {
+            CONTENT_SECURITY_POLICY
+        };
+
+        let headers = response.headers_mut();
+
+        let values = [
+            (
+                STRICT_TRANSPORT_SECURITY,
+                inner.strict_transport_security.clone(),
+            ),
+            (content_security_policy_name, content_security_policy),
+            (X_CONTENT_TYPE_OPTIONS, inner.x_content_type_options.clone()),
+            (X_FRAME_OPTIONS, inner.x_frame_options.clone()),
+            (REFERRER_POLICY, inner.referrer_policy.clone()),
+            (PERMISSIONS_POLICY, inner.permissions_policy.clone()),
+        ];
+
+        for (name, value) in values {
+            if let Some(value) = value {
+                headers.entry(name).or_insert(value);
+            }
+        }
+    }
+}
+
+fn non_empty(value: &str) -> Option<String> {
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+fn header_value(value: &str) -> Result<Option<HeaderValue>, InvalidHeaderValue> {
+    non_empty(value).map(HeaderValue::try_from).transpose()
+}
+
+#[track_caller]
+fn expect_header_value(value: &str) -> Option<HeaderValue> {
+    match header_value(value) {
+        Ok(value) => value,
+        Err(_) => panic!("`{value}` is not a valid header value"),
+    }
+}
+
+impl<H: Handler> Middleware<H> for SecurityHeaders {
+    type Output = SecurityHeadersHandler<H>;
+
+    fn transform(self, input: H) -> Self::Output {
+        SecurityHeadersHandler {
+            headers: self,
+            inner: input,
+        }
+    }
+}
+
+pub struct SecurityHeadersHandler<H> {
+    headers: SecurityHeaders,
+    inner: H,
+}
+
+impl<H: Handler> Handler for SecurityHeadersHandler<H> {
+    async fn call(&self, mut req: Request) -> Result<Response, Error> {
+        let nonce = self.headers.nonce();
+
+        if let Some(nonce) = &nonce {
+            req.head.extensions.insert(CspNonce(nonce.as_str().into()));
+        }
+
+        let mut result = self.inner.call(req).await;
+
+        let response = match &mut result {
+            Ok(response) => response,
+            Err(error) => error.response_mut(),
+        };
+
+        self.headers.apply(response, nonce.as_deref());
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::header::CONTENT_SECURITY_POLICY;
+
+    use super::*;
+
+    #[test]
+    fn apply() {
+        let headers = SecurityHeaders::new()
+            .content_security_policy("script-src 'nonce-{nonce}'")
+            .x_frame_options("");
+
+        let nonce = headers.nonce().unwrap();
+        assert_eq!(STANDARD.decode(&nonce).unwrap().len(), 16);
+        assert_ne!(headers.nonce().unwrap(), nonce);
+
+        let mut response = Response::default();
+        response
+            .headers_mut()
+            .insert(REFERRER_POLICY, HeaderValue::from_static("no-referrer"));
+
+        headers.apply(&mut response, Some(&nonce));
+
+        let h = response.headers();
+        assert_eq!(
+            h[CONTENT_SECURITY_POLICY],
+            format!("script-src 'nonce-{nonce}'").as_str()
+        );
+        assert_eq!(
+            h[STRICT_TRANSPORT_SECURITY],
+            "max-age=31536000; includeSubDomains"
+        );
+        assert_eq!(h[X_CONTENT_TYPE_OPTIONS], "nosniff");
+        assert_eq!(h[REFERRER_POLICY], "no-referrer");
+        assert!(!h.contains_key(X_FRAME_OPTIONS));
+
+        let mut response = Response::default();
+        response.extensions_mut().insert(CspOptOut);
+
+        headers.apply(&mut response, Some(&nonce));
+
+        assert!(!response.headers().contains_key(CONTENT_SECURITY_POLICY));
+        assert!(response.headers().contains_key(X_CONTENT_TYPE_OPTIONS));
+    }
+}
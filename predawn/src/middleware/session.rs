@@ -0,0 +1,131 @@
+use std::{sync::Arc, time::Duration};
+
+use http::header::SET_COOKIE;
+use predawn_core::{error::Error, request::Request, response::Response};
+
+use super::Middleware;
+use crate::{
+    config::session::SessionConfig,
+    cookie::{Key, SetCookie},
+    handler::Handler,
+    session::{Session, SessionSettings, SessionStore},
+};
+
+/// Puts a [`Session`] into the request and saves it after the handler if it was accessed.
+///
+/// The session id travels in a cookie signed with the [`Key`], which is `HttpOnly`
+/// and has no `Max-Age`, the expiry is enforced on the server.
+#[derive(Debug, Clone)]
+pub struct SessionMiddleware {
+    settings: Arc<SessionSettings>,
+}
+
+impl SessionMiddleware {
+    pub fn new<S: SessionStore>(store: S, key: Key) -> Self {
+        Self::from_config(store, key, &SessionConfig::default())
+    }
+
+    #[track_caller]
+    pub fn from_config<S: SessionStore>(store: S, key: Key, config: &SessionConfig) -> Self {
+        let mut cookie = SetCookie::new(config.cookie_name.as_str(), "")
+            .path(config.cookie_path.as_str())
+            .secure(config.cookie_secure)
+            .same_site(config.cookie_same_site);
+
+        if let Some(domain) = &config.cookie_domain {
+            cookie = cookie.domain(domain.as_str());
+        }
+
+        let this = Self {
+            settings: Arc::new(SessionSettings {
+                store: Arc::new(store),
+                key,
+                cookie: SetCookie::new("", ""),
+                idle_timeout: Duration::from_secs(config.idle_timeout),
+                absolute_timeout: Duration::from_secs(config.absolute_timeout),
+            }),
+        };
+
+        this.cookie(cookie)
+    }
+
+    fn settings_mut(&mut self) -> &mut SessionSettings {
+        Arc::make_mut(&mut self.settings)
+    }
+
+    /// The name and the attributes of the cookie, its value is ignored,
+    /// it is always `HttpOnly`.
+    #[track_caller]
+    pub fn cookie(mut self, cookie: SetCookie) -> Self {
+        let cookie = cookie.with_value(String::new()).http_only(true);
+
+        if !cookie.is_valid() {
+            panic!("`{cookie}` is not a valid session cookie");
+        }
+
+        self.settings_mut().cookie = cookie;
+        self
+    }
+
+    pub fn idle_timeout(mut self, idle_timeout: Duration) -> Self {
+        self.settings_mut().idle_timeout = idle_timeout;
+        self
+    }
+
+    pub fn absolute_timeout(mut self, absolute_timeout: Duration) -> Self {
+        self.settings_mut().absolute_timeout = absolute_timeout;
+        self
+    }
+}
+
+impl<H: Handler> Middleware<H> for SessionMiddleware {
+    type Output = SessionHandler<H>;
+
+    fn transform(self, input: H) -> Self::Output {
+        SessionHandler {
+            settings: self.settings,
+            inner: input,
+        }
+    }
+}
+
+pub struct SessionHandler<H> {
+    settings: Arc<SessionSettings>,
+    inner: H,
+}
+
+impl<H: Handler> Handler for SessionHandler<H> {
+    async fn call(&self, mut req: Request) -> Result<Response, Error> {
+        let session = Session::new(self.settings.clone(), &req.head.headers);
+
+        req.head.extensions.insert(session.clone());
+
+        let mut result = self.inner.call(req).await;
+
+        let cookie = match session.save().await {
+            Ok(cookie) => cookie,
+            Err(e) => match result {
+                Ok(_) => return Err(e.into()),
+                Err(_) => {
+                    tracing::error!(error = %e, "failed to save the session");
+                    None
+                }
+            },
+        };
+
+        if let Some(cookie) = cookie {
+            let response = match &mut result {
+                Ok(response) => response,
+                Err(error) => error.response_mut(),
+            };
+
+            let value = cookie
+                .to_header_value()
+                .expect("unreachable: the session cookie is checked when it is set");
+
+            response.headers_mut().append(SET_COOKIE, value);
+        }
+
+        result
+    }
+}
@@ -5,9 +5,9 @@ use std::{
 };
 
 use futures_util::{future::BoxFuture, FutureExt};
-use hyper::body::Incoming;
 use predawn_core::{
-    error::Error, into_response::IntoResponse, request::Request, response::Response,
+    body::IncomingBody, error::Error, into_response::IntoResponse, request::Request,
+    response::Response,
 };
 use tower::{Layer, Service};
 
@@ -28,10 +28,10 @@ impl<H, L> Middleware<H> for TowerLayerCompatMiddleware<L>
 where
     H: Handler,
     L: Layer<HandlerToService<H>>,
-    L::Service: Service<http::Request<Incoming>> + Send + Sync + 'static,
-    <L::Service as Service<http::Request<Incoming>>>::Future: Send,
-    <L::Service as Service<http::Request<Incoming>>>::Response: IntoResponse,
-    <L::Service as Service<http::Request<Incoming>>>::Error: Into<Error>,
+    L::Service: Service<http::Request<IncomingBody>> + Send + Sync + 'static,
+    <L::Service as Service<http::Request<IncomingBody>>>::Future: Send,
+    <L::Service as Service<http::Request<IncomingBody>>>::Response: IntoResponse,
+    <L::Service as Service<http::Request<IncomingBody>>>::Error: Into<Error>,
 {
     type Output = ServiceToHandler<L::Service>;
 
@@ -43,7 +43,7 @@ where
 
 pub struct HandlerToService<H>(Arc<H>);
 
-impl<H> Service<http::Request<Incoming>> for HandlerToService<H>
+impl<H> Service<http::Request<IncomingBody>> for HandlerToService<H>
 where
     H: Handler,
 {
@@ -55,7 +55,7 @@ where
         Poll::Ready(Ok(()))
     }
 
-    fn call(&mut self, req: http::Request<Incoming>) -> Self::Future {
+    fn call(&mut self, req: http::Request<IncomingBody>) -> Self::Future {
         let handler = self.0.clone();
 
         let req = Request::try_from(req).expect("not found some element in request extensions");
@@ -68,7 +68,7 @@ pub struct ServiceToHandler<S>(Mutex<S>);
 
 impl<S> Handler for ServiceToHandler<S>
 where
-    S: Service<http::Request<Incoming>> + Send + Sync + 'static,
+    S: Service<http::Request<IncomingBody>> + Send + Sync + 'static,
     S::Response: IntoResponse,
     S::Error: Into<Error>,
     S::Future: Send,
@@ -83,7 +83,7 @@ where
         let fut = svc
             .lock()
             .unwrap()
-            .call(http::Request::<Incoming>::from(req));
+            .call(http::Request::<IncomingBody>::from(req));
 
         Ok(fut.await.map_err(Into::into)?.into_response()?)
     }
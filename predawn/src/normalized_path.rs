@@ -53,6 +53,17 @@ impl NormalizedPath {
     pub fn into_inner(self) -> String {
         self.0
     }
+
+    /// Returns the parent path if the last segment is a catch-all parameter, e.g. `/{*path}`.
+    pub fn strip_catch_all(&self) -> Option<Self> {
+        let (parent, last) = self.0.rsplit_once('/')?;
+
+        if last.starts_with("{*") && last.ends_with('}') {
+            Some(Self::new(parent))
+        } else {
+            None
+        }
+    }
 }
 
 impl fmt::Display for NormalizedPath {
@@ -177,4 +188,17 @@ mod tests {
         assert_eq!(join("/a", "/b/"), "/a/b");
         assert_eq!(join("/a/", "/b/"), "/a/b");
     }
+
+    #[test]
+    fn test_strip_catch_all() {
+        fn strip(path: &str) -> Option<NormalizedPath> {
+            NormalizedPath::new(path).strip_catch_all()
+        }
+
+        assert_eq!(strip("/{*path}"), Some("/".into()));
+        assert_eq!(strip("/a/{*path}"), Some("/a".into()));
+        assert_eq!(strip("/a/{path}"), None);
+        assert_eq!(strip("/a"), None);
+        assert_eq!(strip("/"), None);
+    }
 }
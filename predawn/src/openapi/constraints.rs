@@ -0,0 +1,107 @@
+use super::{Schema, SchemaKind, Type};
+
+/// The validation keywords of `#[schema(...)]`, written into the schema of a field or a type.
+#[doc(hidden)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Constraints {
+    pub format: Option<&'static str>,
+    pub pattern: Option<&'static str>,
+    pub min_length: Option<usize>,
+    pub max_length: Option<usize>,
+    pub minimum: Option<f64>,
+    pub maximum: Option<f64>,
+}
+
+impl Constraints {
+    /// Writes the keywords which apply to the type of `schema`,
+    /// `min_length` and `max_length` become `minItems` and `maxItems` of arrays.
+    pub fn apply(&self, schema: &mut Schema) {
+        let format = self.format.map(ToString::to_string);
+        let pattern = self.pattern.map(ToString::to_string);
+
+        match &mut schema.schema_kind {
+            SchemaKind::Type(Type::String(ty)) => {
+                if format.is_some() {
+                    ty.format = format.into();
+                }
+
+                if pattern.is_some() {
+                    ty.pattern = pattern;
+                }
+
+                if self.min_length.is_some() {
+                    ty.min_length = self.min_length;
+                }
+
+                if self.max_length.is_some() {
+                    ty.max_length = self.max_length;
+                }
+            }
+            SchemaKind::Type(Type::Integer(ty)) => {
+                if format.is_some() {
+                    ty.format = format.into();
+                }
+
+                if let Some(minimum) = self.minimum {
+                    ty.minimum = Some(minimum.ceil() as i64);
+                }
+
+                if let Some(maximum) = self.maximum {
+                    ty.maximum = Some(maximum.floor() as i64);
+                }
+            }
+            SchemaKind::Type(Type::Number(ty)) => {
+                if format.is_some() {
+                    ty.format = format.into();
+                }
+
+                if self.minimum.is_some() {
+                    ty.minimum = self.minimum;
+                }
+
+                if self.maximum.is_some() {
+                    ty.maximum = self.maximum;
+                }
+            }
+            SchemaKind::Type(Type::Array(ty)) => {
+                if self.min_length.is_some() {
+                    ty.min_items = self.min_length;
+                }
+
+                if self.max_length.is_some() {
+                    ty.max_items = self.max_length;
+                }
+            }
+            SchemaKind::Any(ty) => {
+                if format.is_some() {
+                    ty.format = format;
+                }
+
+                if pattern.is_some() {
+                    ty.pattern = pattern;
+                }
+
+                if self.min_length.is_some() {
+                    ty.min_length = self.min_length;
+                }
+
+                if self.max_length.is_some() {
+                    ty.max_length = self.max_length;
+                }
+
+                if self.minimum.is_some() {
+                    ty.minimum = self.minimum;
+                }
+
+                if self.maximum.is_some() {
+                    ty.maximum = self.maximum;
+                }
+            }
+            SchemaKind::Type(Type::Object(_) | Type::Boolean(_))
+            | SchemaKind::OneOf { .. }
+            | SchemaKind::AllOf { .. }
+            | SchemaKind::AnyOf { .. }
+            | SchemaKind::Not { .. } => {}
+        }
+    }
+}
@@ -0,0 +1,293 @@
+mod constraints;
+mod v3_1;
+
+use std::collections::BTreeMap;
+
+use indexmap::IndexMap;
+pub use predawn_core::openapi::*;
+
+#[doc(hidden)]
+pub use self::constraints::Constraints;
+pub use self::v3_1::to_v3_1;
+
+#[doc(hidden)]
+pub fn transform_parameters(parameters: Vec<Parameter>) -> Vec<ReferenceOr<Parameter>> {
+    parameters.into_iter().map(ReferenceOr::Item).collect()
+}
+
+#[doc(hidden)]
+pub fn transform_request_body(
+    request_body: Option<RequestBody>,
+) -> Option<ReferenceOr<RequestBody>> {
+    request_body.map(ReferenceOr::Item)
+}
+
+#[doc(hidden)]
+pub fn transform_responses(
+    responses: BTreeMap<http::StatusCode, Response>,
+) -> IndexMap<StatusCode, ReferenceOr<Response>> {
+    responses
+        .into_iter()
+        .map(|(status, response)| {
+            (
+                StatusCode::Code(status.as_u16()),
+                ReferenceOr::Item(response),
+            )
+        })
+        .collect()
+}
+
+/// Combines the security requirements of two extractors, both of which must be satisfied,
+/// so every requirement of `old` is combined with every requirement of `new`.
+#[doc(hidden)]
+pub fn merge_security_requirements(
+    old: Option<Vec<SecurityRequirement>>,
+    new: Vec<SecurityRequirement>,
+) -> Vec<SecurityRequirement> {
+    let Some(old) = old else {
+        return new;
+    };
+
+    let mut merged = Vec::new();
+
+    for old in &old {
+        for new in &new {
+            let mut requirement = old.clone();
+
+            new.iter().for_each(|(name, scopes)| {
+                let merged_scopes = requirement.entry(name.clone()).or_default();
+
+                scopes.iter().for_each(|scope| {
+                    if !merged_scopes.contains(scope) {
+                        merged_scopes.push(scope.clone());
+                    }
+                });
+            });
+
+            if !merged.contains(&requirement) {
+                merged.push(requirement);
+            }
+        }
+    }
+
+    merged
+}
+
+/// Adds the permissions required by a handler to the scopes of the OAuth2 and OpenID Connect
+/// security schemes of the operation, the scopes of other schemes must be empty.
+///
+/// Like the scopes of `security`, which are checked at compile time, the permissions must be
+/// declared by the flows of the OAuth2 schemes, which are only known here for the schemes of
+/// the extractors. OpenID Connect schemes declare no scopes.
+#[doc(hidden)]
+#[track_caller]
+pub fn add_required_scopes(
+    handler: &str,
+    security: &mut Option<Vec<SecurityRequirement>>,
+    security_schemes: &BTreeMap<&'static str, (&'static str, SecurityScheme)>,
+    permissions: &[&str],
+) {
+    let scheme = |name: &str| {
+        security_schemes
+            .values()
+            .find(|(scheme_name, _)| *scheme_name == name)
+            .map(|(_, scheme)| scheme)
+    };
+
+    for (name, scopes) in security
+        .iter_mut()
+        .flatten()
+        .flat_map(|requirement| requirement.iter_mut())
+    {
+        match scheme(name) {
+            Some(SecurityScheme::OAuth2 { flows, .. }) => {
+                if let Some(permission) = permissions
+                    .iter()
+                    .find(|permission| !is_oauth2_scope_declared(flows, permission))
+                {
+                    panic!(
+                        "`{handler}` requires the permission `{permission}`, \
+                        which is not a scope declared by the security scheme `{name}`"
+                    );
+                }
+            }
+            Some(SecurityScheme::OpenIDConnect { .. }) => {}
+            _ => continue,
+        }
+
+        for permission in permissions {
+            if !scopes.iter().any(|scope| scope == permission) {
+                scopes.push(permission.to_string());
+            }
+        }
+    }
+}
+
+fn is_oauth2_scope_declared(flows: &OAuth2Flows, scope: &str) -> bool {
+    let OAuth2Flows {
+        implicit,
+        password,
+        client_credentials,
+        authorization_code,
+        ..
+    } = flows;
+
+    implicit.iter().any(|flow| flow.scopes.contains_key(scope))
+        || password.iter().any(|flow| flow.scopes.contains_key(scope))
+        || client_credentials
+            .iter()
+            .any(|flow| flow.scopes.contains_key(scope))
+        || authorization_code
+            .iter()
+            .any(|flow| flow.scopes.contains_key(scope))
+}
+
+/// Whether `scope` is one of the `declared` scopes, always `true` if they are unknown.
+#[doc(hidden)]
+pub const fn is_scope_declared(declared: Option<&[&str]>, scope: &str) -> bool {
+    let Some(declared) = declared else {
+        return true;
+    };
+
+    let mut i = 0;
+
+    while i < declared.len() {
+        let name = declared[i].as_bytes();
+        let scope = scope.as_bytes();
+
+        if name.len() == scope.len() {
+            let mut j = 0;
+
+            while j < name.len() && name[j] == scope[j] {
+                j += 1;
+            }
+
+            if j == name.len() {
+                return true;
+            }
+        }
+
+        i += 1;
+    }
+
+    false
+}
+
+/// Merges the schema of a `#[serde(flatten)]` field into `ty`,
+/// schemas which are not objects are collected into `flattened`.
+#[doc(hidden)]
+pub fn flatten_schema(
+    ty: &mut ObjectType,
+    flattened: &mut Vec<ReferenceOr<Schema>>,
+    schema: Schema,
+    required: bool,
+) {
+    let SchemaKind::Type(Type::Object(object)) = schema.schema_kind else {
+        flattened.push(ReferenceOr::Item(schema));
+        return;
+    };
+
+    ty.properties.extend(object.properties);
+
+    if required {
+        ty.required.extend(object.required);
+    }
+
+    if ty.additional_properties.is_none() {
+        ty.additional_properties = object.additional_properties;
+    }
+}
+
+/// Creates the schema of an object, which is combined with the schemas of flattened fields
+/// that can not be merged into it.
+#[doc(hidden)]
+pub fn object_schema(ty: ObjectType, mut flattened: Vec<ReferenceOr<Schema>>) -> Schema {
+    let object = Schema {
+        schema_data: Default::default(),
+        schema_kind: SchemaKind::Type(Type::Object(ty)),
+    };
+
+    if flattened.is_empty() {
+        return object;
+    }
+
+    flattened.insert(0, ReferenceOr::Item(object));
+
+    Schema {
+        schema_data: Default::default(),
+        schema_kind: SchemaKind::AllOf { all_of: flattened },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn security_schemes() -> BTreeMap<&'static str, (&'static str, SecurityScheme)> {
+        let mut security_schemes = BTreeMap::new();
+        security_schemes.insert(
+            "Bearer",
+            (
+                "Bearer",
+                SecurityScheme::HTTP {
+                    scheme: "bearer".into(),
+                    bearer_format: None,
+                    description: None,
+                    extensions: Default::default(),
+                },
+            ),
+        );
+        security_schemes.insert(
+            "OAuth2",
+            (
+                "OAuth2",
+                SecurityScheme::OAuth2 {
+                    flows: OAuth2Flows {
+                        client_credentials: Some(ClientCredentialsOAuth2Flow {
+                            token_url: "https://example.com/token".into(),
+                            refresh_url: None,
+                            scopes: [("read", ""), ("write", ""), ("admin", "")]
+                                .map(|(scope, description)| (scope.into(), description.into()))
+                                .into(),
+                            extensions: Default::default(),
+                        }),
+                        ..Default::default()
+                    },
+                    description: None,
+                    extensions: Default::default(),
+                },
+            ),
+        );
+
+        security_schemes
+    }
+
+    #[test]
+    fn required_scopes_of_oauth2_only() {
+        let mut requirement = SecurityRequirement::default();
+        requirement.insert("Bearer".into(), Vec::new());
+        requirement.insert("OAuth2".into(), vec!["read".into()]);
+
+        let mut security = Some(vec![requirement]);
+        add_required_scopes(
+            "handler",
+            &mut security,
+            &security_schemes(),
+            &["read", "write"],
+        );
+
+        let requirement = &security.unwrap()[0];
+        assert!(requirement["Bearer"].is_empty());
+        assert_eq!(requirement["OAuth2"], ["read", "write"]);
+    }
+
+    #[test]
+    #[should_panic = "not a scope declared by the security scheme `OAuth2`"]
+    fn undeclared_required_scope() {
+        let mut requirement = SecurityRequirement::default();
+        requirement.insert("OAuth2".into(), Vec::new());
+
+        let mut security = Some(vec![requirement]);
+        add_required_scopes("handler", &mut security, &security_schemes(), &["delete"]);
+    }
+}
@@ -0,0 +1,227 @@
+use serde_json::{json, Map, Value};
+
+use super::OpenAPI;
+
+const WEBHOOKS_EXTENSION: &str = "x-webhooks";
+const PREFIX_ITEMS_EXTENSION: &str = "x-prefixItems";
+
+/// Converts an OpenAPI 3.0 document into an OpenAPI 3.1 document.
+///
+/// - `nullable` becomes a `"null"` type (JSON Schema 2020-12)
+/// - `example` of schemas becomes `examples`
+/// - single-value `enum` becomes `const`
+/// - boolean `exclusiveMinimum` / `exclusiveMaximum` become numbers
+/// - the `x-prefixItems` extension of tuples becomes `prefixItems`
+/// - the `x-webhooks` extension becomes `webhooks`
+pub fn to_v3_1(api: &OpenAPI) -> Value {
+    let mut value = serde_json::to_value(api).expect("failed to serialize `OpenAPI` as JSON");
+
+    let Value::Object(root) = &mut value else {
+        unreachable!("`OpenAPI` must be serialized as an object")
+    };
+
+    root.insert("openapi".to_string(), Value::from("3.1.0"));
+
+    if let Some(webhooks) = root.remove(WEBHOOKS_EXTENSION) {
+        root.insert("webhooks".to_string(), webhooks);
+    }
+
+    transform_document(&mut value);
+
+    value
+}
+
+/// Walks the non-schema part of the document, looking for schemas.
+fn transform_document(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                match key.as_str() {
+                    "schema" => transform_schema(value),
+                    "schemas" => transform_schema_map(value),
+                    // arbitrary values, not part of the document structure
+                    "example" | "examples" => {}
+                    key if key.starts_with("x-") => {}
+                    _ => transform_document(value),
+                }
+            }
+        }
+        Value::Array(array) => array.iter_mut().for_each(transform_document),
+        _ => {}
+    }
+}
+
+fn transform_schema_map(value: &mut Value) {
+    if let Value::Object(map) = value {
+        map.values_mut().for_each(transform_schema);
+    }
+}
+
+fn transform_schema(value: &mut Value) {
+    let Value::Object(schema) = value else {
+        return;
+    };
+
+    if schema.contains_key("$ref") {
+        return;
+    }
+
+    if let Some(prefix_items) = schema.remove(PREFIX_ITEMS_EXTENSION) {
+        // every item is described by `prefixItems`, and `maxItems` forbids additional items
+        schema.remove("items");
+        schema.insert("prefixItems".to_string(), prefix_items);
+    }
+
+    for (key, value) in schema.iter_mut() {
+        match key.as_str() {
+            "properties" | "patternProperties" => transform_schema_map(value),
+            "items" | "additionalProperties" | "not" => transform_schema(value),
+            "allOf" | "anyOf" | "oneOf" | "prefixItems" => {
+                if let Value::Array(array) = value {
+                    array.iter_mut().for_each(transform_schema);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(example) = schema.remove("example") {
+        schema.insert("examples".to_string(), Value::Array(vec![example]));
+    }
+
+    transform_exclusive(schema, "exclusiveMinimum", "minimum");
+    transform_exclusive(schema, "exclusiveMaximum", "maximum");
+
+    let nullable = matches!(schema.remove("nullable"), Some(Value::Bool(true)));
+
+    if nullable {
+        if let Some(Value::Array(values)) = schema.get_mut("enum") {
+            if !values.contains(&Value::Null) {
+                values.push(Value::Null);
+            }
+        }
+    }
+
+    if let Some(Value::Array(values)) = schema.get("enum") {
+        if values.len() == 1 {
+            let value = values[0].clone();
+            schema.remove("enum");
+            schema.insert("const".to_string(), value);
+        }
+    }
+
+    if !nullable {
+        return;
+    }
+
+    match schema.get_mut("type") {
+        Some(Value::String(ty)) => {
+            let ty = Value::String(std::mem::take(ty));
+            schema.insert("type".to_string(), json!([ty, "null"]));
+        }
+        Some(Value::Array(types)) => {
+            if !types.contains(&Value::from("null")) {
+                types.push(Value::from("null"));
+            }
+        }
+        _ => {
+            let schema = std::mem::take(schema);
+            *value = json!({ "anyOf": [Value::Object(schema), { "type": "null" }] });
+        }
+    }
+}
+
+fn transform_exclusive(schema: &mut Map<String, Value>, exclusive: &str, bound: &str) {
+    match schema.get(exclusive) {
+        Some(Value::Bool(true)) => match schema.remove(bound) {
+            Some(bound) => {
+                schema.insert(exclusive.to_string(), bound);
+            }
+            None => {
+                schema.remove(exclusive);
+            }
+        },
+        Some(Value::Bool(false)) => {
+            schema.remove(exclusive);
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transformed(mut schema: Value) -> Value {
+        transform_schema(&mut schema);
+        schema
+    }
+
+    #[test]
+    fn test_transform_schema() {
+        assert_eq!(
+            transformed(json!({ "type": "string", "nullable": true, "example": "a" })),
+            json!({ "type": ["string", "null"], "examples": ["a"] })
+        );
+
+        assert_eq!(
+            transformed(json!({ "type": "string", "enum": ["a"] })),
+            json!({ "type": "string", "const": "a" })
+        );
+
+        assert_eq!(
+            transformed(json!({ "type": "string", "enum": ["a"], "nullable": true })),
+            json!({ "type": ["string", "null"], "enum": ["a", null] })
+        );
+
+        assert_eq!(
+            transformed(
+                json!({ "allOf": [{ "$ref": "#/components/schemas/A" }], "nullable": true })
+            ),
+            json!({
+                "anyOf": [
+                    { "allOf": [{ "$ref": "#/components/schemas/A" }] },
+                    { "type": "null" }
+                ]
+            })
+        );
+
+        assert_eq!(
+            transformed(
+                json!({ "type": "integer", "minimum": 0, "exclusiveMinimum": true, "exclusiveMaximum": false })
+            ),
+            json!({ "type": "integer", "exclusiveMinimum": 0 })
+        );
+
+        assert_eq!(
+            transformed(json!({
+                "type": "object",
+                "properties": {
+                    "a": { "type": "array", "items": { "type": "integer", "nullable": true } }
+                }
+            })),
+            json!({
+                "type": "object",
+                "properties": {
+                    "a": { "type": "array", "items": { "type": ["integer", "null"] } }
+                }
+            })
+        );
+
+        assert_eq!(
+            transformed(json!({
+                "type": "array",
+                "items": { "anyOf": [{ "type": "integer" }, { "type": "string" }] },
+                "minItems": 2,
+                "maxItems": 2,
+                "x-prefixItems": [{ "type": "integer" }, { "type": "string", "nullable": true }]
+            })),
+            json!({
+                "type": "array",
+                "minItems": 2,
+                "maxItems": 2,
+                "prefixItems": [{ "type": "integer" }, { "type": ["string", "null"] }]
+            })
+        );
+    }
+}
@@ -1,17 +1,162 @@
+mod openapi_document;
 mod openapi_json;
+mod openapi_yaml;
+mod static_files;
 pub mod ui;
 
 use std::sync::Arc;
 
-use http::Method;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use http::{header::AUTHORIZATION, HeaderValue, Method};
 use indexmap::IndexMap;
+use predawn_core::{error::Error, request::Request, response::Response};
 use rudi::Context;
+use subtle::ConstantTimeEq;
 
-use crate::{handler::DynHandler, normalized_path::NormalizedPath};
+pub use self::static_files::StaticFiles;
+use crate::{
+    config::{
+        openapi::{BasicAuthConfig, OpenAPIConfig},
+        Config,
+    },
+    environment::Environment,
+    handler::{DynHandler, Handler},
+    normalized_path::NormalizedPath,
+    response_error::UnauthorizedError,
+};
 
 pub trait Plugin {
     fn create_route(
         self: Arc<Self>,
         cx: &mut Context,
     ) -> (NormalizedPath, IndexMap<Method, DynHandler>);
+
+    /// Whether the route is mounted under the application root path
+    /// instead of the non-application root path.
+    fn is_application(&self) -> bool {
+        false
+    }
+}
+
+/// Whether an OpenAPI route is enabled by `openapi.enabled`, `openapi.environments`
+/// and the route's own flag.
+pub(crate) fn openapi_enabled<F>(cx: &Context, route_enabled: F) -> bool
+where
+    F: Fn(&OpenAPIConfig) -> bool,
+{
+    let Some(config) = cx.get_single_option::<Config>() else {
+        return false;
+    };
+
+    let cfg = OpenAPIConfig::from(config);
+
+    let env_matched = match cx.get_single_option::<Environment>() {
+        Some(env) => cfg.is_enabled(env),
+        None => cfg.enabled,
+    };
+
+    env_matched && route_enabled(&cfg)
+}
+
+/// Wraps an OpenAPI route with HTTP basic authentication if `openapi.basic_auth` is set.
+pub(crate) fn protect_openapi_route(cx: &mut Context, handler: DynHandler) -> DynHandler {
+    match cx.resolve::<OpenAPIConfig>().basic_auth {
+        Some(cfg) => DynHandler::new(BasicAuthHandler::new(cfg, handler)),
+        None => handler,
+    }
+}
+
+struct BasicAuthHandler<H> {
+    inner: H,
+    credentials: Box<[u8]>,
+    challenge: HeaderValue,
+}
+
+impl<H> BasicAuthHandler<H> {
+    #[track_caller]
+    fn new(cfg: BasicAuthConfig, inner: H) -> Self {
+        let BasicAuthConfig {
+            username,
+            password,
+            realm,
+        } = cfg;
+
+        let challenge =
+            HeaderValue::try_from(format!("Basic realm=\"{realm}\", charset=\"UTF-8\""))
+                .expect("`openapi.basic_auth.realm` must be a valid header value");
+
+        Self {
+            inner,
+            credentials: format!("{username}:{password}").into_bytes().into(),
+            challenge,
+        }
+    }
+
+    /// The scheme is case-insensitive, only the credentials are compared in constant time.
+    fn is_authorized(&self, value: &HeaderValue) -> bool {
+        let Some((scheme, credentials)) = value
+            .to_str()
+            .ok()
+            .and_then(|value| value.trim().split_once(' '))
+        else {
+            return false;
+        };
+
+        if !scheme.eq_ignore_ascii_case("basic") {
+            return false;
+        }
+
+        let Ok(credentials) = STANDARD.decode(credentials.trim_start()) else {
+            return false;
+        };
+
+        credentials.ct_eq(&self.credentials).into()
+    }
+}
+
+impl<H: Handler> Handler for BasicAuthHandler<H> {
+    async fn call(&self, req: Request) -> Result<Response, Error> {
+        let authorized = req
+            .head
+            .headers
+            .get(AUTHORIZATION)
+            .is_some_and(|value| self.is_authorized(value));
+
+        if !authorized {
+            return Err(UnauthorizedError {
+                challenge: self.challenge.clone(),
+            }
+            .into());
+        }
+
+        self.inner.call(req).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn basic_auth() {
+        let cfg = BasicAuthConfig {
+            username: "admin".into(),
+            password: "secret".into(),
+            realm: "OpenAPI".into(),
+        };
+
+        let handler = BasicAuthHandler::new(cfg, ());
+        let authorized = |value| handler.is_authorized(&HeaderValue::from_static(value));
+
+        // `admin:secret`
+        assert!(authorized("Basic YWRtaW46c2VjcmV0"));
+        assert!(authorized("basic YWRtaW46c2VjcmV0"));
+        assert!(authorized("BASIC   YWRtaW46c2VjcmV0"));
+
+        // `admin:secre`
+        assert!(!authorized("Basic YWRtaW46c2VjcmU="));
+        assert!(!authorized("Bearer YWRtaW46c2VjcmV0"));
+        assert!(!authorized("Basic"));
+        assert!(!authorized("Basic !!!"));
+    }
 }
@@ -0,0 +1,73 @@
+use bytes::Bytes;
+use http::{header::CONTENT_TYPE, HeaderValue};
+use predawn_core::{openapi::OpenAPI, response::Response};
+use rudi::Context;
+
+use crate::{
+    config::openapi::{OpenAPIConfig, OpenAPIVersion},
+    openapi,
+};
+
+pub(crate) const APPLICATION_YAML: &str = "application/yaml";
+
+/// The OpenAPI document serialized once at startup and shared by the JSON and YAML routes.
+#[derive(Clone)]
+pub(crate) struct SerializedOpenAPI {
+    json: Bytes,
+    yaml: Bytes,
+}
+
+impl SerializedOpenAPI {
+    #[track_caller]
+    pub(crate) fn resolve(cx: &mut Context) -> Self {
+        if let Some(serialized) = cx.get_single_option::<Self>() {
+            return serialized.clone();
+        }
+
+        let cfg = cx.resolve::<OpenAPIConfig>();
+        let api = cx.resolve::<OpenAPI>();
+
+        let api = match cfg.version {
+            OpenAPIVersion::V3_0 => {
+                serde_json::to_value(&api).expect("failed to serialize `OpenAPI` as JSON")
+            }
+            OpenAPIVersion::V3_1 => openapi::to_v3_1(&api),
+        };
+
+        let json = if cfg.pretty {
+            serde_json::to_vec_pretty(&api)
+        } else {
+            serde_json::to_vec(&api)
+        }
+        .expect("failed to serialize `OpenAPI` as JSON");
+
+        let yaml = serde_yaml::to_string(&api).expect("failed to serialize `OpenAPI` as YAML");
+
+        let serialized = Self {
+            json: json.into(),
+            yaml: yaml.into(),
+        };
+
+        cx.insert_singleton(serialized.clone());
+
+        serialized
+    }
+
+    pub(crate) fn json(&self) -> Response {
+        create_response(self.json.clone(), mime::APPLICATION_JSON.as_ref())
+    }
+
+    pub(crate) fn yaml(&self) -> Response {
+        create_response(self.yaml.clone(), APPLICATION_YAML)
+    }
+}
+
+fn create_response(bytes: Bytes, content_type: &'static str) -> Response {
+    let mut response: Response = Response::new(bytes.into());
+
+    response
+        .headers_mut()
+        .insert(CONTENT_TYPE, HeaderValue::from_static(content_type));
+
+    response
+}
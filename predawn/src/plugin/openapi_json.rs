@@ -1,16 +1,18 @@
 use std::sync::Arc;
 
-use http::Method;
+use http::{
+    header::{ACCEPT, VARY},
+    HeaderMap, HeaderValue, Method,
+};
 use indexmap::IndexMap;
-use predawn_core::openapi::OpenAPI;
 use rudi::{Context, Singleton};
 
-use super::Plugin;
+use super::{openapi_document::SerializedOpenAPI, Plugin};
 use crate::{
     config::openapi::OpenAPIConfig,
     handler::{handler_fn, DynHandler},
     normalized_path::NormalizedPath,
-    payload::Json,
+    util,
 };
 
 #[derive(Clone, Copy)]
@@ -24,13 +26,22 @@ impl Plugin for OpenAPIJson {
     ) -> (NormalizedPath, IndexMap<Method, DynHandler>) {
         let json_path = cx.resolve::<OpenAPIConfig>().json_path;
 
-        let api = cx.resolve::<OpenAPI>();
+        let serialized = SerializedOpenAPI::resolve(cx);
+
+        let handler = handler_fn(move |req| {
+            let mut response = if prefers_yaml(&req.head.headers) {
+                serialized.yaml()
+            } else {
+                serialized.json()
+            };
+
+            response
+                .headers_mut()
+                .insert(VARY, HeaderValue::from_static("accept"));
 
-        let handler = handler_fn(move |_| {
-            let api = api.clone();
-            async move { Ok(Json(api)) }
+            async move { Ok(response) }
         });
-        let handler = DynHandler::new(handler);
+        let handler = super::protect_openapi_route(cx, DynHandler::new(handler));
 
         let mut map = IndexMap::with_capacity(1);
         map.insert(Method::GET, handler);
@@ -39,12 +50,63 @@ impl Plugin for OpenAPIJson {
     }
 }
 
-#[Singleton]
+fn condition(cx: &Context) -> bool {
+    super::openapi_enabled(cx, |cfg| cfg.json_enabled)
+}
+
+#[Singleton(condition = condition)]
 fn OpenAPIJsonRegister() -> OpenAPIJson {
     OpenAPIJson
 }
 
-#[Singleton(name = std::any::type_name::<OpenAPIJson>())]
+#[Singleton(name = std::any::type_name::<OpenAPIJson>(), condition = condition)]
 fn OpenAPIJsonToPlugin(json: OpenAPIJson) -> Arc<dyn Plugin> {
     Arc::new(json)
 }
+
+/// Whether the `Accept` header prefers YAML over JSON, JSON wins ties.
+fn prefers_yaml(headers: &HeaderMap) -> bool {
+    let json = util::header_quality(headers, ACCEPT, |media_type| match media_type {
+        "application/json" => Some(2),
+        "application/*" => Some(1),
+        "*/*" => Some(0),
+        _ => None,
+    });
+
+    let yaml = util::header_quality(headers, ACCEPT, |media_type| match media_type {
+        "application/yaml" | "application/x-yaml" | "text/yaml" | "text/x-yaml" => Some(2),
+        "application/*" | "text/*" => Some(1),
+        "*/*" => Some(0),
+        _ => None,
+    });
+
+    yaml.unwrap_or_default() > json.unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use http::HeaderValue;
+
+    use super::*;
+
+    fn accept(value: &'static str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(ACCEPT, HeaderValue::from_static(value));
+        headers
+    }
+
+    #[test]
+    fn test_prefers_yaml() {
+        assert!(!prefers_yaml(&HeaderMap::new()));
+        assert!(!prefers_yaml(&accept("*/*")));
+        assert!(!prefers_yaml(&accept("application/json")));
+        assert!(!prefers_yaml(&accept("application/json, application/yaml")));
+        assert!(!prefers_yaml(&accept("application/yaml;q=0.5, */*")));
+
+        assert!(prefers_yaml(&accept("application/yaml")));
+        assert!(prefers_yaml(&accept("text/yaml, */*;q=0.1")));
+        assert!(prefers_yaml(&accept(
+            "application/json;q=0.5, application/yaml"
+        )));
+    }
+}
@@ -0,0 +1,52 @@
+use std::sync::Arc;
+
+use http::Method;
+use indexmap::IndexMap;
+use rudi::{Context, Singleton};
+
+use super::{openapi_document::SerializedOpenAPI, Plugin};
+use crate::{
+    config::openapi::OpenAPIConfig,
+    handler::{handler_fn, DynHandler},
+    normalized_path::NormalizedPath,
+};
+
+#[derive(Clone, Copy)]
+pub struct OpenAPIYaml;
+
+impl Plugin for OpenAPIYaml {
+    #[track_caller]
+    fn create_route(
+        self: Arc<Self>,
+        cx: &mut Context,
+    ) -> (NormalizedPath, IndexMap<Method, DynHandler>) {
+        let yaml_path = cx.resolve::<OpenAPIConfig>().yaml_path;
+
+        let serialized = SerializedOpenAPI::resolve(cx);
+
+        let handler = handler_fn(move |_| {
+            let response = serialized.yaml();
+            async move { Ok(response) }
+        });
+        let handler = super::protect_openapi_route(cx, DynHandler::new(handler));
+
+        let mut map = IndexMap::with_capacity(1);
+        map.insert(Method::GET, handler);
+
+        (yaml_path, map)
+    }
+}
+
+fn condition(cx: &Context) -> bool {
+    super::openapi_enabled(cx, |cfg| cfg.yaml_enabled)
+}
+
+#[Singleton(condition = condition)]
+fn OpenAPIYamlRegister() -> OpenAPIYaml {
+    OpenAPIYaml
+}
+
+#[Singleton(name = std::any::type_name::<OpenAPIYaml>(), condition = condition)]
+fn OpenAPIYamlToPlugin(yaml: OpenAPIYaml) -> Arc<dyn Plugin> {
+    Arc::new(yaml)
+}
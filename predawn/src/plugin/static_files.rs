@@ -0,0 +1,314 @@
+use std::{
+    io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use http::{
+    header::{ACCEPT_ENCODING, CACHE_CONTROL, CONTENT_ENCODING, VARY},
+    HeaderMap, HeaderValue, Method,
+};
+use indexmap::IndexMap;
+use mime::APPLICATION_OCTET_STREAM;
+use predawn_core::{error::Error, into_response::IntoResponse, request::Head, response::Response};
+use rudi::{Context, Singleton};
+
+use super::Plugin;
+use crate::{
+    config::{server::ServerConfig, static_files::StaticFilesConfig, Config},
+    handler::{handler_fn, DynHandler},
+    normalized_path::NormalizedPath,
+    response::File,
+    response_error::FileError,
+    util,
+};
+
+const INDEX_FILE: &str = "index.html";
+
+/// Serves the directory configured under the `static` prefix.
+#[derive(Debug, Clone)]
+pub struct StaticFiles {
+    cfg: StaticFilesConfig,
+}
+
+impl StaticFiles {
+    pub fn new(cfg: StaticFilesConfig) -> Self {
+        Self { cfg }
+    }
+}
+
+impl Plugin for StaticFiles {
+    #[track_caller]
+    fn create_route(
+        self: Arc<Self>,
+        cx: &mut Context,
+    ) -> (NormalizedPath, IndexMap<Method, DynHandler>) {
+        let dir = self
+            .cfg
+            .dir
+            .clone()
+            .expect("`static.dir` must be set to serve static files");
+
+        // symlinks are resolved, so the resolved paths of requests are compared with it
+        let dir = std::fs::canonicalize(&dir)
+            .unwrap_or_else(|e| panic!("failed to resolve `static.dir` `{}`: {e}", dir.display()));
+
+        let prefix = cx
+            .resolve::<ServerConfig>()
+            .root_path
+            .join(self.cfg.mount.clone());
+
+        let service = Arc::new(Service {
+            dir,
+            prefix,
+            cfg: self.cfg.clone(),
+        });
+
+        let handler = handler_fn(move |req| {
+            let service = service.clone();
+            async move { service.serve(req.head).await }
+        });
+        let handler = DynHandler::new(handler);
+
+        let mut map = IndexMap::with_capacity(1);
+        map.insert(Method::GET, handler);
+
+        (self.cfg.mount.clone().join("/{*path}".into()), map)
+    }
+
+    fn is_application(&self) -> bool {
+        true
+    }
+}
+
+fn dir_configured(cx: &Context) -> bool {
+    cx.get_single_option::<Config>()
+        .is_some_and(|cfg| StaticFilesConfig::from(cfg).dir.is_some())
+}
+
+fn register_condition(cx: &Context) -> bool {
+    !cx.contains_provider::<StaticFiles>() && dir_configured(cx)
+}
+
+fn to_plugin_condition(cx: &Context) -> bool {
+    cx.contains_provider::<StaticFiles>() || dir_configured(cx)
+}
+
+#[Singleton(condition = register_condition)]
+fn StaticFilesRegister(#[di(ref)] cfg: &Config) -> StaticFiles {
+    StaticFiles::new(StaticFilesConfig::from(cfg))
+}
+
+#[Singleton(name = std::any::type_name::<StaticFiles>(), condition = to_plugin_condition)]
+fn StaticFilesToPlugin(files: StaticFiles) -> Arc<dyn Plugin> {
+    Arc::new(files)
+}
+
+struct Service {
+    dir: PathBuf,
+    prefix: NormalizedPath,
+    cfg: StaticFilesConfig,
+}
+
+impl Service {
+    async fn serve(&self, head: Head) -> Result<Response, Error> {
+        let path = head
+            .uri
+            .path()
+            .strip_prefix(self.prefix.as_str())
+            .unwrap_or_default();
+
+        let resolved = resolve_path(&self.dir, path);
+
+        let result = match resolved {
+            Some(path) => self.open(&head, path).await,
+            None => Err(FileError::Io(io::ErrorKind::NotFound.into())),
+        };
+
+        match result {
+            Ok(response) => Ok(response),
+            Err(FileError::Io(e)) if self.cfg.fallback && e.kind() == io::ErrorKind::NotFound => {
+                Ok(self.open(&head, self.dir.join(INDEX_FILE)).await?)
+            }
+            Err(FileError::NotAFile) if self.cfg.fallback => {
+                Ok(self.open(&head, self.dir.join(INDEX_FILE)).await?)
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Resolves the symlinks of `path`, a path outside of `dir` is not found.
+    async fn confine(&self, path: &Path) -> Result<PathBuf, FileError> {
+        let path = tokio::fs::canonicalize(path).await?;
+
+        if path.starts_with(&self.dir) {
+            Ok(path)
+        } else {
+            Err(FileError::Io(io::ErrorKind::NotFound.into()))
+        }
+    }
+
+    async fn open(&self, head: &Head, mut path: PathBuf) -> Result<Response, FileError> {
+        if tokio::fs::metadata(&path)
+            .await
+            .is_ok_and(|metadata| metadata.is_dir())
+        {
+            path.push(INDEX_FILE);
+        }
+
+        let content_type = mime_guess::from_path(&path).first_or(APPLICATION_OCTET_STREAM);
+
+        let is_html = content_type.subtype() == mime::HTML;
+
+        let cache_control = if is_html {
+            &self.cfg.html_cache_control
+        } else {
+            &self.cfg.cache_control
+        };
+
+        let mut precompressed = None;
+
+        if self.cfg.precompressed {
+            for (encoding, extension) in [("br", "br"), ("gzip", "gz")] {
+                if !accepts_encoding(&head.headers, encoding) {
+                    continue;
+                }
+
+                let mut compressed = path.clone().into_os_string();
+                compressed.push(".");
+                compressed.push(extension);
+
+                let compressed = match self.confine(Path::new(&compressed)).await {
+                    Ok(compressed) => compressed,
+                    Err(_) => continue,
+                };
+
+                match File::open(head, &compressed).await {
+                    Ok(file) => {
+                        precompressed = Some((file, encoding));
+                        break;
+                    }
+                    Err(FileError::Io(_) | FileError::NotAFile) => continue,
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+
+        let (file, encoding) = match precompressed {
+            Some((file, encoding)) => (file, Some(encoding)),
+            None => (File::open(head, self.confine(&path).await?).await?, None),
+        };
+
+        let mut response = file.content_type(content_type).into_response()?;
+
+        let headers = response.headers_mut();
+
+        if self.cfg.precompressed {
+            headers.insert(VARY, HeaderValue::from_static("accept-encoding"));
+        }
+
+        if let Some(encoding) = encoding {
+            headers.insert(CONTENT_ENCODING, HeaderValue::from_static(encoding));
+        }
+
+        if let Some(cache_control) = cache_control
+            .as_deref()
+            .and_then(|value| HeaderValue::from_str(value).ok())
+        {
+            headers.insert(CACHE_CONTROL, cache_control);
+        }
+
+        Ok(response)
+    }
+}
+
+/// Resolves a request path to a file path inside `dir`,
+/// returns `None` if the path tries to escape from `dir`.
+fn resolve_path(dir: &Path, path: &str) -> Option<PathBuf> {
+    let path = percent_encoding::percent_decode_str(path)
+        .decode_utf8()
+        .ok()?;
+
+    let mut resolved = dir.to_path_buf();
+
+    for segment in path.split('/') {
+        match segment {
+            "" | "." => continue,
+            ".." => return None,
+            _ => {}
+        }
+
+        if segment.contains(['\\', ':', '\0']) {
+            return None;
+        }
+
+        resolved.push(segment);
+    }
+
+    Some(resolved)
+}
+
+fn accepts_encoding(headers: &HeaderMap, encoding: &str) -> bool {
+    util::header_quality(headers, ACCEPT_ENCODING, |name| {
+        if name.eq_ignore_ascii_case(encoding) {
+            Some(1)
+        } else if name == "*" {
+            Some(0)
+        } else {
+            None
+        }
+    })
+    .is_some_and(|quality| quality > 0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_path() {
+        let dir = Path::new("/srv/www");
+
+        assert_eq!(resolve_path(dir, ""), Some(PathBuf::from("/srv/www")));
+        assert_eq!(
+            resolve_path(dir, "/a/b.js"),
+            Some(PathBuf::from("/srv/www/a/b.js"))
+        );
+        assert_eq!(
+            resolve_path(dir, "/a/./b%20c.js"),
+            Some(PathBuf::from("/srv/www/a/b c.js"))
+        );
+
+        assert_eq!(resolve_path(dir, "/../etc/passwd"), None);
+        assert_eq!(resolve_path(dir, "/a/%2e%2e/%2e%2e/etc/passwd"), None);
+        assert_eq!(resolve_path(dir, "/a/..%5c..%5cetc"), None);
+        assert_eq!(resolve_path(dir, "/C:/windows"), None);
+        assert_eq!(resolve_path(dir, "/%ff"), None);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_confine() {
+        let root = std::env::temp_dir().join(format!("predawn-static-{}", std::process::id()));
+        let dir = root.join("www");
+        std::fs::create_dir_all(&dir).unwrap();
+
+        std::fs::write(root.join("secret.txt"), "secret").unwrap();
+        std::fs::write(dir.join("a.txt"), "a").unwrap();
+        std::os::unix::fs::symlink(root.join("secret.txt"), dir.join("link.txt")).unwrap();
+
+        let service = Service {
+            dir: std::fs::canonicalize(&dir).unwrap(),
+            prefix: "/".into(),
+            cfg: StaticFilesConfig::default(),
+        };
+
+        let confined = service.confine(&dir.join("a.txt")).await;
+        let escaped = service.confine(&dir.join("link.txt")).await;
+
+        std::fs::remove_dir_all(&root).unwrap();
+
+        assert!(confined.is_ok());
+        assert!(matches!(escaped, Err(FileError::Io(e)) if e.kind() == io::ErrorKind::NotFound));
+    }
+}
@@ -0,0 +1,228 @@
+use std::{collections::HashMap, io, io::Read, sync::Arc};
+
+use bytes::Bytes;
+use http::{
+    header::{CACHE_CONTROL, CONTENT_TYPE},
+    HeaderValue, Method,
+};
+use indexmap::IndexMap;
+use mime::APPLICATION_OCTET_STREAM;
+use predawn_core::response::Response;
+use rudi::{Context, Singleton};
+
+use crate::{
+    config::{
+        openapi::{OpenAPIConfig, UiAssets},
+        server::ServerConfig,
+    },
+    handler::{handler_fn, DynHandler},
+    normalized_path::NormalizedPath,
+    plugin::Plugin,
+    response_error::MatchError,
+};
+
+/// The files of the `dist` folder of Swagger UI, keyed by their URL relative to the asset base.
+const SWAGGER_UI_FILES: [(&str, &str); 3] = [
+    ("swagger-ui/swagger-ui.css", "swagger-ui.css"),
+    ("swagger-ui/swagger-ui-bundle.js", "swagger-ui-bundle.js"),
+    (
+        "swagger-ui/swagger-ui-standalone-preset.js",
+        "swagger-ui-standalone-preset.js",
+    ),
+];
+
+fn extract_swagger_ui() -> zip::result::ZipResult<HashMap<&'static str, Bytes>> {
+    let reader = io::Cursor::new(utoipa_swagger_ui_vendored::SWAGGER_UI_VENDORED);
+    let mut archive = zip::ZipArchive::new(reader)?;
+
+    let mut assets = HashMap::with_capacity(SWAGGER_UI_FILES.len());
+
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i)?;
+
+        let Some((_, dist_name)) = file.name().split_once("/dist/") else {
+            continue;
+        };
+
+        let Some((url, _)) = SWAGGER_UI_FILES
+            .iter()
+            .find(|(_, file_name)| *file_name == dist_name)
+        else {
+            continue;
+        };
+
+        let mut buf = Vec::with_capacity(file.size() as usize);
+        file.read_to_end(&mut buf)?;
+
+        assets.insert(*url, Bytes::from(buf));
+    }
+
+    Ok(assets)
+}
+
+pub(crate) fn contains(file: &str) -> bool {
+    SWAGGER_UI_FILES.iter().any(|(url, _)| *url == file)
+}
+
+/// Serves the OpenAPI UI assets bundled into the binary,
+/// registered when `openapi.ui_assets` is `"embedded"`.
+#[derive(Debug, Clone, Copy)]
+pub struct EmbeddedUiAssets;
+
+impl Plugin for EmbeddedUiAssets {
+    fn create_route(
+        self: Arc<Self>,
+        cx: &mut Context,
+    ) -> (NormalizedPath, IndexMap<Method, DynHandler>) {
+        let openapi_cfg = cx.resolve::<OpenAPIConfig>();
+
+        let unbundled = [
+            ("RapiDoc", openapi_cfg.rapidoc_enabled),
+            ("Scalar", openapi_cfg.scalar_enabled),
+            ("Redoc", openapi_cfg.redoc_enabled),
+            ("OpenAPI Explorer", openapi_cfg.openapi_explorer_enabled),
+        ];
+
+        for (ui, _) in unbundled.iter().filter(|(_, enabled)| *enabled) {
+            tracing::warn!(
+                "{ui} is not registered, only the assets of Swagger UI are embedded, \
+                 disable it or load its assets from a custom base URL"
+            );
+        }
+
+        let assets_path = openapi_cfg.ui_assets_path;
+
+        let prefix = cx
+            .resolve::<ServerConfig>()
+            .full_non_application_root_path()
+            .join(assets_path.clone());
+
+        let prefix: Arc<str> = format!("{}/", prefix.trim_end_matches('/')).into();
+
+        let assets = match extract_swagger_ui() {
+            Ok(assets) => Arc::new(assets),
+            Err(e) => panic!("failed to extract the embedded Swagger UI assets: {e}"),
+        };
+
+        if let Some((url, _)) = SWAGGER_UI_FILES
+            .iter()
+            .find(|(url, _)| !assets.contains_key(url))
+        {
+            panic!("`{url}` is missing from the embedded Swagger UI assets");
+        }
+
+        let handler = handler_fn(move |req| {
+            let prefix = prefix.clone();
+            let assets = assets.clone();
+
+            async move {
+                let file = req
+                    .head
+                    .uri
+                    .path()
+                    .strip_prefix(&*prefix)
+                    .unwrap_or_default();
+
+                let Some(bytes) = assets.get(file) else {
+                    return Err(MatchError(matchit::MatchError::NotFound).into());
+                };
+
+                let content_type = mime_guess::from_path(file).first_or(APPLICATION_OCTET_STREAM);
+
+                let mut response: Response = Response::new(bytes.clone().into());
+
+                let headers = response.headers_mut();
+
+                if let Ok(content_type) = HeaderValue::from_str(content_type.as_ref()) {
+                    headers.insert(CONTENT_TYPE, content_type);
+                }
+
+                headers.insert(
+                    CACHE_CONTROL,
+                    HeaderValue::from_static("public, max-age=604800"),
+                );
+
+                Ok(response)
+            }
+        });
+        let handler = crate::plugin::protect_openapi_route(cx, DynHandler::new(handler));
+
+        let mut map = IndexMap::with_capacity(1);
+        map.insert(Method::GET, handler);
+
+        (assets_path.join("/{*path}".into()), map)
+    }
+}
+
+fn is_embedded(cx: &Context) -> bool {
+    crate::plugin::openapi_enabled(cx, |cfg| cfg.ui_assets == UiAssets::Embedded)
+}
+
+#[Singleton(condition = is_embedded)]
+fn EmbeddedUiAssetsRegister() -> EmbeddedUiAssets {
+    EmbeddedUiAssets
+}
+
+#[Singleton(name = std::any::type_name::<EmbeddedUiAssets>(), condition = is_embedded)]
+fn EmbeddedUiAssetsToPlugin(assets: EmbeddedUiAssets) -> Arc<dyn Plugin> {
+    Arc::new(assets)
+}
+
+#[cfg(test)]
+mod tests {
+    use config::ConfigError;
+    use http::StatusCode;
+
+    use crate::{
+        app::{create_app, Hooks},
+        config::Config,
+        environment::Environment,
+        handler::Handler,
+        middleware::test_request,
+    };
+
+    struct App;
+
+    impl Hooks for App {
+        fn load_config(_: &Environment) -> Result<Config, ConfigError> {
+            let config = config::Config::builder()
+                .set_override("openapi.ui_assets", "embedded")?
+                .build()?;
+
+            Ok(Config::new(config))
+        }
+
+        fn init_logger(_: &Config) {}
+    }
+
+    #[tokio::test]
+    async fn default_uis() {
+        let (_, router) = create_app::<App>(Environment::Dev).await;
+
+        let status = |path: &str| {
+            let req = http::Request::builder().uri(path).body("").unwrap();
+            let req = test_request(req, "127.0.0.1:1234");
+
+            let router = &router;
+
+            async move {
+                match router.call(req).await {
+                    Ok(response) => response.status(),
+                    Err(e) => e.response().status(),
+                }
+            }
+        };
+
+        // the UIs are mounted under the non-application root path
+        assert_eq!(status("/p/swagger-ui").await, StatusCode::OK);
+        assert_eq!(
+            status("/p/ui-assets/swagger-ui/swagger-ui-bundle.js").await,
+            StatusCode::OK
+        );
+
+        // the UIs whose assets are not embedded are skipped
+        for path in ["/p/rapidoc", "/p/scalar", "/p/redoc", "/p/openapi-explorer"] {
+            assert_eq!(status(path).await, StatusCode::NOT_FOUND, "{path}");
+        }
+    }
+}
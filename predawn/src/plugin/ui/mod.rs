@@ -1,3 +1,5 @@
+#[cfg(feature = "embedded-ui")]
+mod embedded;
 mod openapi_explorer;
 mod rapidoc;
 mod redoc;
@@ -10,10 +12,18 @@ use mime::TEXT_HTML_UTF_8;
 use predawn_core::response::Response;
 use rudi::Context;
 
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-ui")))]
+#[cfg(feature = "embedded-ui")]
+pub use self::embedded::EmbeddedUiAssets;
 pub use self::{rapidoc::RapiDoc, swagger_ui::SwaggerUI};
 use crate::{
-    config::{openapi::OpenAPIConfig, server::ServerConfig, Config},
+    config::{
+        openapi::{OpenAPIConfig, UiAssets},
+        server::ServerConfig,
+        Config,
+    },
     handler::{handler_fn, DynHandler},
+    middleware::CspOptOut,
     normalized_path::NormalizedPath,
 };
 
@@ -25,20 +35,86 @@ pub(crate) fn create_route<F>(
 where
     F: Fn(OpenAPIConfig) -> NormalizedPath,
 {
-    (get_path(cx.resolve::<OpenAPIConfig>()), create_map(html))
+    let path = get_path(cx.resolve::<OpenAPIConfig>());
+    (path, create_map(cx, html))
 }
 
+/// Whether a UI is enabled, a UI whose `asset` is not bundled is skipped when the assets are
+/// embedded, which [`EmbeddedUiAssets`] warns about.
+pub(crate) fn ui_enabled<F>(cx: &Context, asset: &str, route_enabled: F) -> bool
+where
+    F: Fn(&OpenAPIConfig) -> bool,
+{
+    crate::plugin::openapi_enabled(cx, |cfg| route_enabled(cfg) && is_available(cfg, asset))
+}
+
+/// Without the `embedded-ui` feature, the UIs are not skipped so that `asset_url` reports
+/// the missing feature.
+#[cfg_attr(not(feature = "embedded-ui"), allow(unused_variables))]
+fn is_available(cfg: &OpenAPIConfig, asset: &str) -> bool {
+    match cfg.ui_assets {
+        #[cfg(feature = "embedded-ui")]
+        UiAssets::Embedded => embedded::contains(asset),
+        _ => true,
+    }
+}
+
+/// Returns the URL of the OpenAPI JSON route which the UIs load,
+/// panics if the route is disabled.
+#[track_caller]
 pub(crate) fn json_path(cfg: &Config) -> NormalizedPath {
     let server_cfg = ServerConfig::from(cfg);
     let openapi_cfg = OpenAPIConfig::from(cfg);
 
+    if !openapi_cfg.json_enabled {
+        panic!(
+            "the OpenAPI UIs load `openapi.json_path`, disable them as well or enable \
+             `openapi.json_enabled`"
+        );
+    }
+
     let full_non_application_root_path = server_cfg.full_non_application_root_path();
     let normalized_json_path = openapi_cfg.json_path;
 
     full_non_application_root_path.join(normalized_json_path)
 }
 
-fn create_map(html: String) -> IndexMap<Method, DynHandler> {
+/// Returns the URL of an asset, e.g. `rapidoc/rapidoc-min.js`,
+/// or `None` if the asset should be loaded from the default CDN.
+///
+/// Panics if the assets are embedded but the asset is not, so that an enabled UI
+/// never silently falls back to the CDN, such UIs are skipped by [`ui_enabled`].
+#[track_caller]
+pub(crate) fn asset_url(cfg: &Config, file: &str) -> Option<String> {
+    let openapi_cfg = OpenAPIConfig::from(cfg);
+
+    let base = match openapi_cfg.ui_assets {
+        UiAssets::Cdn => return None,
+        UiAssets::Custom(base) => base,
+        UiAssets::Embedded => {
+            #[cfg(not(feature = "embedded-ui"))]
+            panic!("`openapi.ui_assets = \"embedded\"` requires the `embedded-ui` feature");
+
+            #[cfg(feature = "embedded-ui")]
+            {
+                if !embedded::contains(file) {
+                    panic!("`{file}` is not embedded");
+                }
+
+                let server_cfg = ServerConfig::from(cfg);
+
+                server_cfg
+                    .full_non_application_root_path()
+                    .join(openapi_cfg.ui_assets_path)
+                    .into_inner()
+            }
+        }
+    };
+
+    Some(format!("{}/{}", base.trim_end_matches('/'), file))
+}
+
+fn create_map(cx: &mut Context, html: String) -> IndexMap<Method, DynHandler> {
     let handler = handler_fn(move |_| {
         let html = html.clone();
 
@@ -48,11 +124,13 @@ fn create_map(html: String) -> IndexMap<Method, DynHandler> {
                 CONTENT_TYPE,
                 HeaderValue::from_static(TEXT_HTML_UTF_8.as_ref()),
             );
+            // the UI pages load scripts and styles from CDNs and inline them
+            response.extensions_mut().insert(CspOptOut);
             Ok(response)
         }
     });
 
-    let handler = DynHandler::new(handler);
+    let handler = super::protect_openapi_route(cx, DynHandler::new(handler));
 
     let mut map = IndexMap::with_capacity(1);
     map.insert(Method::GET, handler);
@@ -6,6 +6,9 @@ use rudi::{Context, Singleton};
 
 use crate::{config::Config, handler::DynHandler, normalized_path::NormalizedPath, plugin::Plugin};
 
+/// The script of the UI, relative to the asset base.
+const ASSET: &str = "openapi-explorer/openapi-explorer.min.js";
+
 const TEMPLATE: &str = r###"
 <!DOCTYPE html>
 <html>
@@ -40,17 +43,27 @@ impl Plugin for OpenapiExplorer {
     }
 }
 
+fn enabled(cx: &Context) -> bool {
+    super::ui_enabled(cx, ASSET, |cfg| cfg.openapi_explorer_enabled)
+}
+
 fn condition(cx: &Context) -> bool {
-    !cx.contains_provider::<OpenapiExplorer>()
+    !cx.contains_provider::<OpenapiExplorer>() && enabled(cx)
 }
 
 #[Singleton(condition = condition)]
 fn OpenapiExplorerRegister(#[di(ref)] cfg: &Config) -> OpenapiExplorer {
     let json_path = super::json_path(cfg).into_inner();
-    OpenapiExplorer::new(json_path)
+    let mut openapi_explorer = OpenapiExplorer::new(json_path);
+
+    if let Some(url) = super::asset_url(cfg, ASSET) {
+        openapi_explorer.js_url = url.into();
+    }
+
+    openapi_explorer
 }
 
-#[Singleton(name = std::any::type_name::<OpenapiExplorer>())]
+#[Singleton(name = std::any::type_name::<OpenapiExplorer>(), condition = enabled)]
 fn OpenapiExplorerToPlugin(openapi_explorer: OpenapiExplorer) -> Arc<dyn Plugin> {
     Arc::new(openapi_explorer)
 }
@@ -6,6 +6,9 @@ use rudi::{Context, Singleton};
 
 use crate::{config::Config, handler::DynHandler, normalized_path::NormalizedPath, plugin::Plugin};
 
+/// The script of the UI, relative to the asset base.
+const ASSET: &str = "rapidoc/rapidoc-min.js";
+
 const TEMPLATE: &str = r###"
 <!DOCTYPE html>
 <html>
@@ -39,17 +42,27 @@ impl Plugin for RapiDoc {
     }
 }
 
+fn enabled(cx: &Context) -> bool {
+    super::ui_enabled(cx, ASSET, |cfg| cfg.rapidoc_enabled)
+}
+
 fn condition(cx: &Context) -> bool {
-    !cx.contains_provider::<RapiDoc>()
+    !cx.contains_provider::<RapiDoc>() && enabled(cx)
 }
 
 #[Singleton(condition = condition)]
 fn RapiDocRegister(#[di(ref)] cfg: &Config) -> RapiDoc {
     let json_path = super::json_path(cfg).into_inner();
-    RapiDoc::new(json_path)
+    let mut rapidoc = RapiDoc::new(json_path);
+
+    if let Some(url) = super::asset_url(cfg, ASSET) {
+        rapidoc.js_url = url.into();
+    }
+
+    rapidoc
 }
 
-#[Singleton(name = std::any::type_name::<RapiDoc>())]
+#[Singleton(name = std::any::type_name::<RapiDoc>(), condition = enabled)]
 fn RapiDocToPlugin(rapidoc: RapiDoc) -> Arc<dyn Plugin> {
     Arc::new(rapidoc)
 }
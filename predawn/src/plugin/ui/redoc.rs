@@ -6,6 +6,9 @@ use rudi::{Context, Singleton};
 
 use crate::{config::Config, handler::DynHandler, normalized_path::NormalizedPath, plugin::Plugin};
 
+/// The script of the UI, relative to the asset base.
+const ASSET: &str = "redoc/redoc.standalone.js";
+
 const TEMPLATE: &str = r###"
 <!DOCTYPE html>
 <html>
@@ -43,17 +46,27 @@ impl Plugin for Redoc {
     }
 }
 
+fn enabled(cx: &Context) -> bool {
+    super::ui_enabled(cx, ASSET, |cfg| cfg.redoc_enabled)
+}
+
 fn condition(cx: &Context) -> bool {
-    !cx.contains_provider::<Redoc>()
+    !cx.contains_provider::<Redoc>() && enabled(cx)
 }
 
 #[Singleton(condition = condition)]
 fn RedocRegister(#[di(ref)] cfg: &Config) -> Redoc {
     let json_path = super::json_path(cfg).into_inner();
-    Redoc::new(json_path)
+    let mut redoc = Redoc::new(json_path);
+
+    if let Some(url) = super::asset_url(cfg, ASSET) {
+        redoc.js_url = url.into();
+    }
+
+    redoc
 }
 
-#[Singleton(name = std::any::type_name::<Redoc>())]
+#[Singleton(name = std::any::type_name::<Redoc>(), condition = enabled)]
 fn RedocToPlugin(redoc: Redoc) -> Arc<dyn Plugin> {
     Arc::new(redoc)
 }
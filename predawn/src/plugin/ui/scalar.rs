@@ -6,6 +6,9 @@ use rudi::{Context, Singleton};
 
 use crate::{config::Config, handler::DynHandler, normalized_path::NormalizedPath, plugin::Plugin};
 
+/// The script of the UI, relative to the asset base.
+const ASSET: &str = "scalar/standalone.js";
+
 const TEMPLATE: &str = r###"
 <!DOCTYPE html>
 <html>
@@ -48,17 +51,27 @@ impl Plugin for Scalar {
     }
 }
 
+fn enabled(cx: &Context) -> bool {
+    super::ui_enabled(cx, ASSET, |cfg| cfg.scalar_enabled)
+}
+
 fn condition(cx: &Context) -> bool {
-    !cx.contains_provider::<Scalar>()
+    !cx.contains_provider::<Scalar>() && enabled(cx)
 }
 
 #[Singleton(condition = condition)]
 fn ScalarRegister(#[di(ref)] cfg: &Config) -> Scalar {
     let json_path = super::json_path(cfg).into_inner();
-    Scalar::new(json_path)
+    let mut scalar = Scalar::new(json_path);
+
+    if let Some(url) = super::asset_url(cfg, ASSET) {
+        scalar.js_url = url.into();
+    }
+
+    scalar
 }
 
-#[Singleton(name = std::any::type_name::<Scalar>())]
+#[Singleton(name = std::any::type_name::<Scalar>(), condition = enabled)]
 fn ScalarToPlugin(scalar: Scalar) -> Arc<dyn Plugin> {
     Arc::new(scalar)
 }
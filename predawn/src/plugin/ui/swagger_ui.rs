@@ -53,17 +53,35 @@ impl Plugin for SwaggerUI {
     }
 }
 
+fn enabled(cx: &Context) -> bool {
+    crate::plugin::openapi_enabled(cx, |cfg| cfg.swagger_ui_enabled)
+}
+
 fn condition(cx: &Context) -> bool {
-    !cx.contains_provider::<SwaggerUI>()
+    !cx.contains_provider::<SwaggerUI>() && enabled(cx)
 }
 
 #[Singleton(condition = condition)]
 fn SwaggerUIRegister(#[di(ref)] cfg: &Config) -> SwaggerUI {
     let json_path = super::json_path(cfg).into_inner();
-    SwaggerUI::new(json_path)
+    let mut ui = SwaggerUI::new(json_path);
+
+    if let Some(url) = super::asset_url(cfg, "swagger-ui/swagger-ui.css") {
+        ui = ui.css_url(url);
+    }
+
+    if let Some(url) = super::asset_url(cfg, "swagger-ui/swagger-ui-bundle.js") {
+        ui = ui.bundle_js_url(url);
+    }
+
+    if let Some(url) = super::asset_url(cfg, "swagger-ui/swagger-ui-standalone-preset.js") {
+        ui = ui.standalone_preset_url(url);
+    }
+
+    ui
 }
 
-#[Singleton(name = std::any::type_name::<SwaggerUI>())]
+#[Singleton(name = std::any::type_name::<SwaggerUI>(), condition = enabled)]
 fn SwaggerUIToPlugin(ui: SwaggerUI) -> Arc<dyn Plugin> {
     Arc::new(ui)
 }
@@ -4,6 +4,7 @@ use http::{
     header::{CONTENT_DISPOSITION, CONTENT_TYPE},
     HeaderValue, StatusCode,
 };
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
 use predawn_core::{
     api_response::ApiResponse,
     into_response::IntoResponse,
@@ -15,19 +16,60 @@ use predawn_schema::ToSchema;
 
 use crate::response_error::DownloadError;
 
+/// The characters which are percent-encoded in `filename*`, all but `attr-char` of RFC 5987.
+const FILENAME_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'!')
+    .remove(b'#')
+    .remove(b'$')
+    .remove(b'&')
+    .remove(b'+')
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'^')
+    .remove(b'_')
+    .remove(b'`')
+    .remove(b'|')
+    .remove(b'~');
+
 #[derive(Debug)]
-enum DownloadType {
+pub(crate) enum DownloadType {
     Inline,
     Attachment,
 }
 
 impl DownloadType {
-    fn as_str(&self) -> &'static str {
+    pub(crate) fn as_str(&self) -> &'static str {
         match self {
             DownloadType::Inline => "inline",
             DownloadType::Attachment => "attachment",
         }
     }
+
+    /// The value of `Content-Disposition`, the file name is quoted and escaped, and a name
+    /// which is not ASCII is also sent as `filename*`, with an ASCII fallback in `filename`.
+    pub(crate) fn content_disposition(&self, file_name: &str) -> String {
+        let mut fallback = String::with_capacity(file_name.len());
+
+        for c in file_name.chars() {
+            match c {
+                '"' | '\\' => {
+                    fallback.push('\\');
+                    fallback.push(c);
+                }
+                c if c.is_ascii() => fallback.push(c),
+                _ => fallback.push('_'),
+            }
+        }
+
+        let mut value = format!("{}; filename=\"{}\"", self.as_str(), fallback);
+
+        if !file_name.is_ascii() {
+            value.push_str("; filename*=UTF-8''");
+            value.extend(utf8_percent_encode(file_name, FILENAME_ENCODE_SET));
+        }
+
+        value
+    }
 }
 
 #[derive(Debug)]
@@ -72,7 +114,7 @@ impl<T> Download<T> {
         ty: DownloadType,
         file_name: Box<str>,
     ) -> Result<HeaderValue, DownloadError<E>> {
-        let content_disposition = format!("{}; filename=\"{}\"", ty.as_str(), file_name);
+        let content_disposition = ty.content_disposition(&file_name);
 
         HeaderValue::from_str(&content_disposition).map_err(|_| {
             DownloadError::InvalidContentDisposition {
@@ -0,0 +1,594 @@
+use std::{
+    collections::{BTreeMap, VecDeque},
+    io::{self, SeekFrom},
+    ops::Range,
+    path::Path,
+    sync::atomic::{AtomicU64, Ordering},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use bytes::{Bytes, BytesMut};
+use futures_util::stream;
+use http::{
+    header::{
+        ACCEPT_RANGES, CONTENT_DISPOSITION, CONTENT_LENGTH, CONTENT_RANGE, CONTENT_TYPE, ETAG,
+        IF_MODIFIED_SINCE, IF_NONE_MATCH, IF_RANGE, LAST_MODIFIED, RANGE,
+    },
+    HeaderMap, HeaderValue, Method, StatusCode,
+};
+use mime::{Mime, APPLICATION_OCTET_STREAM};
+use predawn_core::{
+    api_response::ApiResponse,
+    body::ResponseBody,
+    into_response::IntoResponse,
+    openapi::{self, Schema},
+    request::Head,
+    response::Response,
+};
+use predawn_schema::ToSchema;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+use super::download::DownloadType;
+use crate::response_error::FileError;
+
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// More ranges in a `Range` header are ignored, and the whole file is sent.
+const MAX_RANGES: usize = 16;
+
+/// A response that streams a file from disk.
+///
+/// It honours the `Range`, `If-Range`, `If-None-Match` and `If-Modified-Since`
+/// request headers, answering with `206 Partial Content` (single or `multipart/byteranges`)
+/// or `304 Not Modified` when appropriate.
+#[derive(Debug)]
+pub struct File {
+    file: tokio::fs::File,
+    size: u64,
+    etag: HeaderValue,
+    last_modified: Option<HeaderValue>,
+    content_type: Mime,
+    disposition: Option<(DownloadType, Box<str>)>,
+    plan: Plan,
+}
+
+#[derive(Debug)]
+enum Plan {
+    Full,
+    NotModified,
+    Single(Range<u64>),
+    Multi(Vec<Range<u64>>),
+}
+
+impl File {
+    pub async fn open<P>(head: &Head, path: P) -> Result<Self, FileError>
+    where
+        P: AsRef<Path>,
+    {
+        Self::inner_open(head, path.as_ref()).await
+    }
+
+    async fn inner_open(head: &Head, path: &Path) -> Result<Self, FileError> {
+        let file = tokio::fs::File::open(path).await?;
+        let metadata = file.metadata().await?;
+
+        if !metadata.is_file() {
+            return Err(FileError::NotAFile);
+        }
+
+        let size = metadata.len();
+        let modified = metadata.modified().ok();
+
+        let etag = create_etag(size, modified);
+        let last_modified = modified
+            .map(|modified| HeaderValue::try_from(httpdate::fmt_http_date(modified)).unwrap());
+
+        let content_type = mime_guess::from_path(path).first_or(APPLICATION_OCTET_STREAM);
+
+        let plan = evaluate(head, size, &etag, modified)?;
+
+        Ok(Self {
+            file,
+            size,
+            etag,
+            last_modified,
+            content_type,
+            disposition: None,
+            plan,
+        })
+    }
+
+    pub fn content_type(mut self, content_type: Mime) -> Self {
+        self.content_type = content_type;
+        self
+    }
+
+    pub fn inline<N>(mut self, file_name: N) -> Self
+    where
+        N: Into<Box<str>>,
+    {
+        self.disposition = Some((DownloadType::Inline, file_name.into()));
+        self
+    }
+
+    pub fn attachment<N>(mut self, file_name: N) -> Self
+    where
+        N: Into<Box<str>>,
+    {
+        self.disposition = Some((DownloadType::Attachment, file_name.into()));
+        self
+    }
+}
+
+fn create_etag(size: u64, modified: Option<SystemTime>) -> HeaderValue {
+    let modified = modified
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_nanos())
+        .unwrap_or_default();
+
+    HeaderValue::try_from(format!("\"{:x}-{:x}\"", size, modified)).unwrap()
+}
+
+fn evaluate(
+    head: &Head,
+    size: u64,
+    etag: &HeaderValue,
+    modified: Option<SystemTime>,
+) -> Result<Plan, FileError> {
+    if head.method != Method::GET && head.method != Method::HEAD {
+        return Ok(Plan::Full);
+    }
+
+    let headers = &head.headers;
+
+    if is_not_modified(headers, etag, modified) {
+        return Ok(Plan::NotModified);
+    }
+
+    let Some(range) = header_str(headers, RANGE) else {
+        return Ok(Plan::Full);
+    };
+
+    if !if_range_matches(headers, etag, modified) {
+        return Ok(Plan::Full);
+    }
+
+    match parse_range(range, size) {
+        None => Ok(Plan::Full),
+        Some(None) => Err(FileError::RangeNotSatisfiable { size }),
+        Some(Some(mut ranges)) => {
+            if ranges.len() == 1 {
+                Ok(Plan::Single(ranges.remove(0)))
+            } else {
+                Ok(Plan::Multi(ranges))
+            }
+        }
+    }
+}
+
+fn header_str(headers: &HeaderMap, name: http::header::HeaderName) -> Option<&str> {
+    headers.get(name).and_then(|value| value.to_str().ok())
+}
+
+fn truncate_to_secs(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+fn is_not_modified(headers: &HeaderMap, etag: &HeaderValue, modified: Option<SystemTime>) -> bool {
+    // `If-None-Match` takes precedence over `If-Modified-Since`
+    if let Some(if_none_match) = header_str(headers, IF_NONE_MATCH) {
+        let etag = weak_tag(etag.to_str().unwrap_or_default());
+
+        return if_none_match
+            .split(',')
+            .map(str::trim)
+            .any(|tag| tag == "*" || weak_tag(tag) == etag);
+    }
+
+    let (Some(since), Some(modified)) = (header_str(headers, IF_MODIFIED_SINCE), modified) else {
+        return false;
+    };
+
+    match httpdate::parse_http_date(since) {
+        Ok(since) => truncate_to_secs(modified) <= truncate_to_secs(since),
+        Err(_) => false,
+    }
+}
+
+fn weak_tag(tag: &str) -> &str {
+    tag.strip_prefix("W/").unwrap_or(tag)
+}
+
+fn if_range_matches(headers: &HeaderMap, etag: &HeaderValue, modified: Option<SystemTime>) -> bool {
+    let Some(if_range) = header_str(headers, IF_RANGE) else {
+        return true;
+    };
+
+    let if_range = if_range.trim();
+
+    if if_range.starts_with('"') || if_range.starts_with("W/") {
+        // `If-Range` requires a strong comparison, weak entity tags never match
+        return if_range == etag.to_str().unwrap_or_default();
+    }
+
+    match (httpdate::parse_http_date(if_range), modified) {
+        (Ok(date), Some(modified)) => truncate_to_secs(date) == truncate_to_secs(modified),
+        _ => false,
+    }
+}
+
+/// Parses a `Range` header value.
+///
+/// Returns `None` if the header is malformed or has more than [`MAX_RANGES`] ranges and
+/// should be ignored, `Some(None)` if none of the ranges can be satisfied, and
+/// `Some(Some(ranges))` with the satisfiable ranges otherwise, sorted and with overlapping
+/// or adjacent ranges merged, so no byte is sent twice.
+fn parse_range(range: &str, size: u64) -> Option<Option<Vec<Range<u64>>>> {
+    let specs = range.trim().strip_prefix("bytes=")?;
+
+    let specs = specs
+        .split(',')
+        .map(str::trim)
+        .filter(|spec| !spec.is_empty())
+        .collect::<Vec<_>>();
+
+    if specs.len() > MAX_RANGES {
+        return None;
+    }
+
+    let mut ranges = Vec::new();
+
+    for spec in specs {
+        let (first, last) = spec.split_once('-')?;
+        let (first, last) = (first.trim(), last.trim());
+
+        if first.is_empty() {
+            let suffix = last.parse::<u64>().ok()?;
+
+            if suffix > 0 && size > 0 {
+                ranges.push(size.saturating_sub(suffix)..size);
+            }
+
+            continue;
+        }
+
+        let first = first.parse::<u64>().ok()?;
+
+        let last = if last.is_empty() {
+            None
+        } else {
+            let last = last.parse::<u64>().ok()?;
+
+            if last < first {
+                return None;
+            }
+
+            Some(last)
+        };
+
+        if first < size {
+            let end = last.map_or(size, |last| last.saturating_add(1).min(size));
+            ranges.push(first..end);
+        }
+    }
+
+    if ranges.is_empty() {
+        return Some(None);
+    }
+
+    ranges.sort_by_key(|range| range.start);
+
+    let mut merged: Vec<Range<u64>> = Vec::with_capacity(ranges.len());
+
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+
+    Some(Some(merged))
+}
+
+fn content_range(range: &Range<u64>, size: u64) -> String {
+    format!("bytes {}-{}/{}", range.start, range.end - 1, size)
+}
+
+fn create_boundary() -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.subsec_nanos())
+        .unwrap_or_default();
+
+    format!(
+        "predawn-{:08x}{:016x}",
+        nanos,
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
+#[derive(Debug)]
+enum Part {
+    Bytes(Bytes),
+    File(Range<u64>),
+}
+
+fn create_body(file: tokio::fs::File, parts: VecDeque<Part>) -> ResponseBody {
+    let stream = stream::try_unfold((file, parts), |(mut file, mut parts)| async move {
+        let Some(part) = parts.pop_front() else {
+            return Ok(None);
+        };
+
+        match part {
+            Part::Bytes(bytes) => Ok(Some((bytes, (file, parts)))),
+            Part::File(range) => {
+                let chunk_size = (range.end - range.start).min(CHUNK_SIZE as u64) as usize;
+
+                let mut buf = BytesMut::with_capacity(chunk_size);
+
+                file.seek(SeekFrom::Start(range.start)).await?;
+
+                while buf.len() < chunk_size {
+                    let n = (&mut file)
+                        .take((chunk_size - buf.len()) as u64)
+                        .read_buf(&mut buf)
+                        .await?;
+
+                    if n == 0 {
+                        return Err(io::Error::from(io::ErrorKind::UnexpectedEof));
+                    }
+                }
+
+                let next = range.start + chunk_size as u64;
+
+                if next < range.end {
+                    parts.push_front(Part::File(next..range.end));
+                }
+
+                Ok(Some((buf.freeze(), (file, parts))))
+            }
+        }
+    });
+
+    ResponseBody::from_stream(stream)
+}
+
+impl IntoResponse for File {
+    type Error = FileError;
+
+    fn into_response(self) -> Result<Response, Self::Error> {
+        let File {
+            file,
+            size,
+            etag,
+            last_modified,
+            content_type,
+            disposition,
+            plan,
+        } = self;
+
+        let content_disposition = disposition
+            .map(|(ty, file_name)| {
+                let value = ty.content_disposition(&file_name);
+
+                HeaderValue::from_str(&value).map_err(|_| FileError::InvalidContentDisposition {
+                    value: value.into(),
+                })
+            })
+            .transpose()?;
+
+        let content_type = HeaderValue::from_str(content_type.as_ref())
+            .unwrap_or_else(|_| HeaderValue::from_static(APPLICATION_OCTET_STREAM.as_ref()));
+
+        let mut headers = HeaderMap::new();
+
+        headers.insert(ACCEPT_RANGES, HeaderValue::from_static("bytes"));
+        headers.insert(ETAG, etag);
+
+        if let Some(last_modified) = last_modified {
+            headers.insert(LAST_MODIFIED, last_modified);
+        }
+
+        let (status, body) = match plan {
+            Plan::NotModified => (StatusCode::NOT_MODIFIED, ResponseBody::empty()),
+            Plan::Full => {
+                headers.insert(CONTENT_TYPE, content_type);
+                headers.insert(CONTENT_LENGTH, HeaderValue::from(size));
+
+                let parts = VecDeque::from([Part::File(0..size)]);
+                (StatusCode::OK, create_body(file, parts))
+            }
+            Plan::Single(range) => {
+                headers.insert(CONTENT_TYPE, content_type);
+                headers.insert(CONTENT_LENGTH, HeaderValue::from(range.end - range.start));
+                headers.insert(
+                    CONTENT_RANGE,
+                    HeaderValue::try_from(content_range(&range, size)).unwrap(),
+                );
+
+                let parts = VecDeque::from([Part::File(range)]);
+                (StatusCode::PARTIAL_CONTENT, create_body(file, parts))
+            }
+            Plan::Multi(ranges) => {
+                let boundary = create_boundary();
+
+                let mut parts = VecDeque::with_capacity(ranges.len() * 2 + 1);
+                let mut content_length = 0;
+
+                for range in ranges {
+                    let part_head = format!(
+                        "\r\n--{}\r\n{}: {}\r\n{}: {}\r\n\r\n",
+                        boundary,
+                        CONTENT_TYPE,
+                        content_type.to_str().unwrap_or_default(),
+                        CONTENT_RANGE,
+                        content_range(&range, size),
+                    );
+
+                    content_length += part_head.len() as u64 + (range.end - range.start);
+
+                    parts.push_back(Part::Bytes(part_head.into()));
+                    parts.push_back(Part::File(range));
+                }
+
+                let tail = format!("\r\n--{}--\r\n", boundary);
+                content_length += tail.len() as u64;
+                parts.push_back(Part::Bytes(tail.into()));
+
+                headers.insert(
+                    CONTENT_TYPE,
+                    HeaderValue::try_from(format!("multipart/byteranges; boundary={}", boundary))
+                        .unwrap(),
+                );
+                headers.insert(CONTENT_LENGTH, HeaderValue::from(content_length));
+
+                (StatusCode::PARTIAL_CONTENT, create_body(file, parts))
+            }
+        };
+
+        if let Some(content_disposition) = content_disposition {
+            headers.insert(CONTENT_DISPOSITION, content_disposition);
+        }
+
+        let mut response = Response::new(body);
+        *response.status_mut() = status;
+        *response.headers_mut() = headers;
+
+        Ok(response)
+    }
+}
+
+impl ApiResponse for File {
+    fn responses(
+        schemas: &mut BTreeMap<String, Schema>,
+    ) -> Option<BTreeMap<StatusCode, openapi::Response>> {
+        let mut content = |media_type: &str| {
+            let mut map = indexmap::IndexMap::with_capacity(1);
+
+            map.insert(
+                media_type.to_string(),
+                openapi::MediaType {
+                    schema: Some(<File as ToSchema>::schema_ref(schemas)),
+                    ..Default::default()
+                },
+            );
+
+            map
+        };
+
+        let ok = openapi::Response {
+            description: "The whole file".to_string(),
+            content: content(APPLICATION_OCTET_STREAM.as_ref()),
+            ..Default::default()
+        };
+
+        let mut partial_content = openapi::Response {
+            description: "One or more ranges of the file".to_string(),
+            content: content(APPLICATION_OCTET_STREAM.as_ref()),
+            ..Default::default()
+        };
+        partial_content
+            .content
+            .extend(content("multipart/byteranges"));
+
+        let not_modified = openapi::Response {
+            description: "The file has not been modified".to_string(),
+            ..Default::default()
+        };
+
+        let mut map = BTreeMap::new();
+        map.insert(StatusCode::OK, ok);
+        map.insert(StatusCode::PARTIAL_CONTENT, partial_content);
+        map.insert(StatusCode::NOT_MODIFIED, not_modified);
+
+        Some(map)
+    }
+}
+
+impl ToSchema for File {
+    fn schema(_: &mut BTreeMap<String, Schema>) -> openapi::Schema {
+        crate::util::binary_schema("File")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[allow(clippy::single_range_in_vec_init)]
+    fn test_parse_range() {
+        assert_eq!(parse_range("bytes=0-499", 1000), Some(Some(vec![0..500])));
+        assert_eq!(parse_range("bytes=500-", 1000), Some(Some(vec![500..1000])));
+        assert_eq!(parse_range("bytes=-200", 1000), Some(Some(vec![800..1000])));
+        assert_eq!(parse_range("bytes=-2000", 1000), Some(Some(vec![0..1000])));
+        assert_eq!(
+            parse_range("bytes=900-2000", 1000),
+            Some(Some(vec![900..1000]))
+        );
+        assert_eq!(
+            parse_range("bytes=0-9, 20-29", 1000),
+            Some(Some(vec![0..10, 20..30]))
+        );
+
+        // unsatisfiable ranges are dropped
+        assert_eq!(
+            parse_range("bytes=0-9, 2000-3000", 1000),
+            Some(Some(vec![0..10]))
+        );
+        assert_eq!(parse_range("bytes=1000-", 1000), Some(None));
+        assert_eq!(parse_range("bytes=-0", 1000), Some(None));
+        assert_eq!(parse_range("bytes=0-", 0), Some(None));
+
+        // malformed ranges are ignored
+        assert_eq!(parse_range("items=0-9", 1000), None);
+        assert_eq!(parse_range("bytes=9-0", 1000), None);
+        assert_eq!(parse_range("bytes=a-b", 1000), None);
+        assert_eq!(parse_range("bytes=10", 1000), None);
+
+        assert_eq!(
+            parse_range("bytes=0-18446744073709551615", 1000),
+            Some(Some(vec![0..1000]))
+        );
+
+        // overlapping and adjacent ranges are merged
+        assert_eq!(
+            parse_range("bytes=20-29, 0-9, 5-14, 15-16", 1000),
+            Some(Some(vec![0..17, 20..30]))
+        );
+        assert_eq!(
+            parse_range("bytes=0-, 0-, 0-", 1000),
+            Some(Some(vec![0..1000]))
+        );
+
+        // too many ranges are ignored
+        let many = vec!["0-0"; MAX_RANGES + 1].join(",");
+        assert_eq!(parse_range(&format!("bytes={many}"), 1000), None);
+    }
+
+    #[test]
+    fn test_content_disposition() {
+        assert_eq!(
+            DownloadType::Attachment.content_disposition("a.txt"),
+            r#"attachment; filename="a.txt""#
+        );
+        assert_eq!(
+            DownloadType::Inline.content_disposition(r#"a"b\c.txt"#),
+            r#"inline; filename="a\"b\\c.txt""#
+        );
+        assert_eq!(
+            DownloadType::Attachment.content_disposition("résumé.pdf"),
+            r#"attachment; filename="r_sum_.pdf"; filename*=UTF-8''r%C3%A9sum%C3%A9.pdf"#
+        );
+    }
+
+    #[test]
+    fn test_content_range() {
+        assert_eq!(content_range(&(0..500), 1000), "bytes 0-499/1000");
+        assert_eq!(content_range(&(999..1000), 1000), "bytes 999-999/1000");
+    }
+}
@@ -1,8 +1,9 @@
 mod download;
+mod file;
 mod to_header_value;
 
 pub use predawn_core::response::Response;
 
 #[doc(hidden)]
 pub use self::to_header_value::{panic_on_err, panic_on_none};
-pub use self::{download::Download, to_header_value::ToHeaderValue};
+pub use self::{download::Download, file::File, to_header_value::ToHeaderValue};
@@ -1,16 +1,36 @@
-use std::{collections::HashSet, error::Error, fmt, str::Utf8Error, sync::Arc};
+use std::{
+    collections::{BTreeMap, HashSet},
+    error::Error,
+    fmt, io,
+    net::IpAddr,
+    str::Utf8Error,
+    sync::Arc,
+    time::Duration,
+};
 
 use http::{
-    header::{CONTENT_DISPOSITION, CONTENT_TYPE},
-    StatusCode,
+    header::{
+        ACCEPT_ENCODING, CONTENT_DISPOSITION, CONTENT_RANGE, CONTENT_TYPE, RETRY_AFTER, SET_COOKIE,
+        WWW_AUTHENTICATE,
+    },
+    HeaderName, HeaderValue, StatusCode,
 };
 use http_body_util::LengthLimitError;
-use predawn_core::media_type::MediaType;
+use ipnet::IpNet;
 pub use predawn_core::response_error::*;
+use predawn_core::{
+    error::BoxError,
+    media_type::{MediaType, MultiResponseMediaType},
+    openapi::{self, merge_responses, Header, ParameterSchemaOrContent, ReferenceOr, Schema},
+    response::Response,
+};
+use predawn_schema::ToSchema;
 
 use crate::{
     extract::multipart::Multipart,
+    middleware::{insert_rate_limit_headers, whole_seconds, RATE_LIMIT_HEADERS},
     payload::{Form, Json},
+    validate::ValidationErrors,
 };
 
 #[derive(Debug, thiserror::Error)]
@@ -57,6 +77,53 @@ impl ResponseError for QueryError {
     }
 }
 
+#[derive(Debug, thiserror::Error)]
+#[error("failed to deserialize header data: {0}")]
+pub struct HeaderError(#[from] pub serde_html_form::de::Error);
+
+impl ResponseError for HeaderError {
+    fn as_status(&self) -> StatusCode {
+        StatusCode::BAD_REQUEST
+    }
+
+    fn status_codes() -> HashSet<StatusCode> {
+        [StatusCode::BAD_REQUEST].into()
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("failed to deserialize cookie data: {0}")]
+pub struct CookieError(#[from] pub serde_html_form::de::Error);
+
+impl ResponseError for CookieError {
+    fn as_status(&self) -> StatusCode {
+        StatusCode::BAD_REQUEST
+    }
+
+    fn status_codes() -> HashSet<StatusCode> {
+        [StatusCode::BAD_REQUEST].into()
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum TypedHeaderError {
+    #[error("missing `{name}` header")]
+    Missing { name: HeaderName },
+
+    #[error("invalid `{name}` header")]
+    Invalid { name: HeaderName },
+}
+
+impl ResponseError for TypedHeaderError {
+    fn as_status(&self) -> StatusCode {
+        StatusCode::BAD_REQUEST
+    }
+
+    fn status_codes() -> HashSet<StatusCode> {
+        [StatusCode::BAD_REQUEST].into()
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum PathError {
     #[error("no paths parameters found for matched route")]
@@ -385,3 +452,603 @@ impl<T: ResponseError> ResponseError for DownloadError<T> {
         status_codes
     }
 }
+
+#[derive(Debug, thiserror::Error)]
+pub enum FileError {
+    #[error("failed to open file: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("not a file")]
+    NotAFile,
+
+    #[error("range not satisfiable for file of {size} bytes")]
+    RangeNotSatisfiable { size: u64 },
+
+    #[error("invalid `{}` header value: `{value}`", CONTENT_DISPOSITION)]
+    InvalidContentDisposition { value: Box<str> },
+}
+
+impl ResponseError for FileError {
+    fn as_status(&self) -> StatusCode {
+        match self {
+            FileError::Io(e) => match e.kind() {
+                io::ErrorKind::NotFound => StatusCode::NOT_FOUND,
+                io::ErrorKind::PermissionDenied => StatusCode::FORBIDDEN,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            },
+            FileError::NotAFile => StatusCode::NOT_FOUND,
+            FileError::RangeNotSatisfiable { .. } => StatusCode::RANGE_NOT_SATISFIABLE,
+            FileError::InvalidContentDisposition { .. } => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn status_codes() -> HashSet<StatusCode> {
+        [
+            StatusCode::NOT_FOUND,
+            StatusCode::FORBIDDEN,
+            StatusCode::RANGE_NOT_SATISFIABLE,
+            StatusCode::INTERNAL_SERVER_ERROR,
+        ]
+        .into()
+    }
+
+    fn as_response(&self) -> Response {
+        let mut response = Response::builder()
+            .status(self.as_status())
+            .header(
+                CONTENT_TYPE,
+                HeaderValue::from_static(mime::TEXT_PLAIN_UTF_8.as_ref()),
+            )
+            .body(self.to_string().into())
+            .unwrap();
+
+        if let FileError::RangeNotSatisfiable { size } = self {
+            response.headers_mut().insert(
+                CONTENT_RANGE,
+                HeaderValue::try_from(format!("bytes */{}", size)).unwrap(),
+            );
+        }
+
+        response
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("unauthorized")]
+pub struct UnauthorizedError {
+    /// The value of the `WWW-Authenticate` header.
+    pub challenge: HeaderValue,
+}
+
+impl ResponseError for UnauthorizedError {
+    fn as_status(&self) -> StatusCode {
+        StatusCode::UNAUTHORIZED
+    }
+
+    fn status_codes() -> HashSet<StatusCode> {
+        [StatusCode::UNAUTHORIZED].into()
+    }
+
+    fn as_response(&self) -> Response {
+        Response::builder()
+            .status(self.as_status())
+            .header(
+                CONTENT_TYPE,
+                HeaderValue::from_static(mime::TEXT_PLAIN_UTF_8.as_ref()),
+            )
+            .header(WWW_AUTHENTICATE, self.challenge.clone())
+            .body(self.to_string().into())
+            .unwrap()
+    }
+}
+
+/// The principal of the request lacks the permissions required by the handler.
+#[derive(Debug, thiserror::Error)]
+#[error("forbidden")]
+pub struct ForbiddenError {
+    pub permissions: &'static [&'static str],
+}
+
+impl ResponseError for ForbiddenError {
+    fn as_status(&self) -> StatusCode {
+        StatusCode::FORBIDDEN
+    }
+
+    fn status_codes() -> HashSet<StatusCode> {
+        [StatusCode::FORBIDDEN].into()
+    }
+}
+
+/// Why a bearer token is rejected by [`JwtAuth`](crate::middleware::JwtAuth).
+#[derive(Debug, thiserror::Error)]
+pub enum JwtError {
+    #[error("missing bearer token")]
+    Missing,
+    #[error("malformed token")]
+    Malformed,
+    #[error("unsupported algorithm `{0}`")]
+    UnsupportedAlgorithm(String),
+    #[error("no key matches the token")]
+    UnknownKey,
+    #[error("invalid signature")]
+    InvalidSignature,
+    #[error("missing `{0}` claim")]
+    MissingClaim(&'static str),
+    #[error("invalid `{0}` claim")]
+    InvalidClaim(&'static str),
+    #[error("token expired")]
+    Expired,
+    #[error("token not yet valid")]
+    NotYetValid,
+    #[error("invalid issuer")]
+    InvalidIssuer,
+    #[error("invalid audience")]
+    InvalidAudience,
+    #[error("insufficient scope")]
+    InsufficientScope,
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("{error}")]
+pub struct JwtAuthError {
+    pub error: JwtError,
+    /// The value of the `WWW-Authenticate` header.
+    pub challenge: HeaderValue,
+}
+
+impl ResponseError for JwtAuthError {
+    fn as_status(&self) -> StatusCode {
+        match self.error {
+            JwtError::InsufficientScope => StatusCode::FORBIDDEN,
+            _ => StatusCode::UNAUTHORIZED,
+        }
+    }
+
+    fn status_codes() -> HashSet<StatusCode> {
+        [StatusCode::UNAUTHORIZED, StatusCode::FORBIDDEN].into()
+    }
+
+    fn as_response(&self) -> Response {
+        Response::builder()
+            .status(self.as_status())
+            .header(
+                CONTENT_TYPE,
+                HeaderValue::from_static(mime::TEXT_PLAIN_UTF_8.as_ref()),
+            )
+            .header(WWW_AUTHENTICATE, self.challenge.clone())
+            .body(self.to_string().into())
+            .unwrap()
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ClaimsError {
+    /// The request is not authenticated by [`JwtAuth`](crate::middleware::JwtAuth).
+    #[error("unauthorized")]
+    Missing {
+        /// The value of the `WWW-Authenticate` header.
+        challenge: HeaderValue,
+    },
+    #[error("failed to deserialize claims: {0}")]
+    Invalid(#[from] serde_json::Error),
+}
+
+impl ResponseError for ClaimsError {
+    fn as_status(&self) -> StatusCode {
+        match self {
+            ClaimsError::Missing { .. } => StatusCode::UNAUTHORIZED,
+            ClaimsError::Invalid(_) => StatusCode::FORBIDDEN,
+        }
+    }
+
+    fn status_codes() -> HashSet<StatusCode> {
+        [StatusCode::UNAUTHORIZED, StatusCode::FORBIDDEN].into()
+    }
+
+    fn as_response(&self) -> Response {
+        let mut response = Response::builder()
+            .status(self.as_status())
+            .header(
+                CONTENT_TYPE,
+                HeaderValue::from_static(mime::TEXT_PLAIN_UTF_8.as_ref()),
+            )
+            .body(self.to_string().into())
+            .unwrap();
+
+        if let ClaimsError::Missing { challenge } = self {
+            response
+                .headers_mut()
+                .insert(WWW_AUTHENTICATE, challenge.clone());
+        }
+
+        response
+    }
+}
+
+/// The `Content-Encoding` of a request is not supported by `Decompression`.
+#[derive(Debug, thiserror::Error)]
+#[error("unsupported content encoding `{encoding}`")]
+pub struct UnsupportedContentEncodingError {
+    pub encoding: String,
+    /// The value of the `Accept-Encoding` header, listing the supported encodings.
+    pub accepted: HeaderValue,
+}
+
+impl ResponseError for UnsupportedContentEncodingError {
+    fn as_status(&self) -> StatusCode {
+        StatusCode::UNSUPPORTED_MEDIA_TYPE
+    }
+
+    fn status_codes() -> HashSet<StatusCode> {
+        [StatusCode::UNSUPPORTED_MEDIA_TYPE].into()
+    }
+
+    fn as_response(&self) -> Response {
+        Response::builder()
+            .status(self.as_status())
+            .header(
+                CONTENT_TYPE,
+                HeaderValue::from_static(mime::TEXT_PLAIN_UTF_8.as_ref()),
+            )
+            .header(ACCEPT_ENCODING, self.accepted.clone())
+            .body(self.to_string().into())
+            .unwrap()
+    }
+}
+
+/// [`CspNonce`](crate::extract::CspNonce) is extracted from a request which is not handled by
+/// [`SecurityHeaders`](crate::middleware::SecurityHeaders), or whose policy has no `{nonce}`.
+#[derive(Debug, thiserror::Error)]
+#[error("missing CSP nonce")]
+pub struct MissingCspNonceError;
+
+impl ResponseError for MissingCspNonceError {
+    fn as_status(&self) -> StatusCode {
+        StatusCode::INTERNAL_SERVER_ERROR
+    }
+
+    fn status_codes() -> HashSet<StatusCode> {
+        [StatusCode::INTERNAL_SERVER_ERROR].into()
+    }
+}
+
+/// Returned by [`SignedCookieJar`](crate::cookie::SignedCookieJar) and
+/// [`PrivateCookieJar`](crate::cookie::PrivateCookieJar) when no
+/// [`Key`](crate::cookie::Key) is registered, e.g. `cookie.key` is not set.
+#[derive(Debug, thiserror::Error)]
+#[error("missing cookie key")]
+pub struct MissingCookieKeyError;
+
+impl ResponseError for MissingCookieKeyError {
+    fn as_status(&self) -> StatusCode {
+        StatusCode::INTERNAL_SERVER_ERROR
+    }
+
+    fn status_codes() -> HashSet<StatusCode> {
+        [StatusCode::INTERNAL_SERVER_ERROR].into()
+    }
+}
+
+#[derive(Debug)]
+pub enum SetCookieError<T> {
+    Inner(T),
+    InvalidCookie { name: Box<str> },
+}
+
+impl<T: fmt::Display> fmt::Display for SetCookieError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SetCookieError::Inner(e) => fmt::Display::fmt(e, f),
+            SetCookieError::InvalidCookie { name } => {
+                write!(f, "invalid `{}` header of cookie `{}`", SET_COOKIE, name)
+            }
+        }
+    }
+}
+
+impl<T: Error + 'static> Error for SetCookieError<T> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SetCookieError::Inner(e) => Some(e),
+            SetCookieError::InvalidCookie { .. } => None,
+        }
+    }
+}
+
+impl<T: ResponseError> ResponseError for SetCookieError<T> {
+    fn as_status(&self) -> StatusCode {
+        match self {
+            SetCookieError::Inner(e) => e.as_status(),
+            SetCookieError::InvalidCookie { .. } => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn status_codes() -> HashSet<StatusCode> {
+        let mut status_codes = T::status_codes();
+        status_codes.insert(StatusCode::INTERNAL_SERVER_ERROR);
+        status_codes
+    }
+}
+
+/// Returned by [`Session`](crate::session::Session) when the request is not handled by
+/// [`SessionMiddleware`](crate::middleware::SessionMiddleware).
+#[derive(Debug, thiserror::Error)]
+#[error("missing session")]
+pub struct MissingSessionError;
+
+impl ResponseError for MissingSessionError {
+    fn as_status(&self) -> StatusCode {
+        StatusCode::INTERNAL_SERVER_ERROR
+    }
+
+    fn status_codes() -> HashSet<StatusCode> {
+        [StatusCode::INTERNAL_SERVER_ERROR].into()
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SessionError {
+    #[error("failed to access the session store: {0}")]
+    Store(BoxError),
+
+    #[error("failed to convert a session value: {0}")]
+    Value(#[from] serde_json::Error),
+}
+
+impl ResponseError for SessionError {
+    fn as_status(&self) -> StatusCode {
+        StatusCode::INTERNAL_SERVER_ERROR
+    }
+
+    fn status_codes() -> HashSet<StatusCode> {
+        [StatusCode::INTERNAL_SERVER_ERROR].into()
+    }
+}
+
+/// Returned by [`CsrfToken`](crate::extract::CsrfToken) when the request is not handled by
+/// [`Csrf`](crate::middleware::Csrf).
+#[derive(Debug, thiserror::Error)]
+#[error("missing CSRF token")]
+pub struct MissingCsrfTokenError;
+
+impl ResponseError for MissingCsrfTokenError {
+    fn as_status(&self) -> StatusCode {
+        StatusCode::INTERNAL_SERVER_ERROR
+    }
+
+    fn status_codes() -> HashSet<StatusCode> {
+        [StatusCode::INTERNAL_SERVER_ERROR].into()
+    }
+}
+
+/// Why a request is rejected by [`Csrf`](crate::middleware::Csrf).
+#[derive(Debug, thiserror::Error)]
+pub enum CsrfError {
+    #[error("missing CSRF cookie")]
+    MissingCookie,
+    #[error("missing CSRF token")]
+    MissingToken,
+    #[error("CSRF token mismatch")]
+    Mismatch,
+}
+
+impl ResponseError for CsrfError {
+    fn as_status(&self) -> StatusCode {
+        StatusCode::FORBIDDEN
+    }
+
+    fn status_codes() -> HashSet<StatusCode> {
+        [StatusCode::FORBIDDEN].into()
+    }
+}
+
+/// The request exceeds the limit of [`RateLimit`](crate::middleware::RateLimit).
+#[derive(Debug, thiserror::Error)]
+#[error("too many requests")]
+pub struct RateLimitError {
+    /// The number of requests allowed at once.
+    pub limit: u32,
+    /// How long until the next request is allowed.
+    pub retry_after: Duration,
+    /// How long until the limit is fully restored.
+    pub reset: Duration,
+}
+
+impl ResponseError for RateLimitError {
+    fn as_status(&self) -> StatusCode {
+        StatusCode::TOO_MANY_REQUESTS
+    }
+
+    fn status_codes() -> HashSet<StatusCode> {
+        [StatusCode::TOO_MANY_REQUESTS].into()
+    }
+
+    fn as_response(&self) -> Response {
+        let mut response = Response::builder()
+            .status(self.as_status())
+            .header(
+                CONTENT_TYPE,
+                HeaderValue::from_static(mime::TEXT_PLAIN_UTF_8.as_ref()),
+            )
+            .header(RETRY_AFTER, whole_seconds(self.retry_after))
+            .body(self.to_string().into())
+            .unwrap();
+
+        insert_rate_limit_headers(response.headers_mut(), self.limit, 0, self.reset);
+
+        response
+    }
+
+    fn responses(
+        schemas: &mut BTreeMap<String, Schema>,
+    ) -> BTreeMap<StatusCode, openapi::Response> {
+        let status = StatusCode::TOO_MANY_REQUESTS;
+
+        let mut response = openapi::Response {
+            description: status.canonical_reason().unwrap_or_default().to_string(),
+            content: <String as MultiResponseMediaType>::content(schemas),
+            ..Default::default()
+        };
+
+        let mut header = |description: &str| {
+            ReferenceOr::Item(Header {
+                description: Some(description.to_string()),
+                style: Default::default(),
+                required: true,
+                deprecated: Default::default(),
+                format: ParameterSchemaOrContent::Schema(<u64 as ToSchema>::schema_ref(schemas)),
+                example: Default::default(),
+                examples: Default::default(),
+                extensions: Default::default(),
+            })
+        };
+
+        response.headers.insert(
+            RETRY_AFTER.to_string(),
+            header("The seconds until the next request is allowed."),
+        );
+
+        for (name, description) in RATE_LIMIT_HEADERS {
+            response
+                .headers
+                .insert(name.to_string(), header(description));
+        }
+
+        [(status, response)].into()
+    }
+}
+
+/// The client of the request is rejected by [`IpFilter`](crate::middleware::IpFilter),
+/// the rule is only logged.
+#[derive(Debug, thiserror::Error)]
+#[error("forbidden")]
+pub struct IpFilterError {
+    pub ip: IpAddr,
+    /// The matched deny rule, or `None` if no allow rule matches.
+    pub rule: Option<IpNet>,
+}
+
+impl ResponseError for IpFilterError {
+    fn as_status(&self) -> StatusCode {
+        StatusCode::FORBIDDEN
+    }
+
+    fn status_codes() -> HashSet<StatusCode> {
+        [StatusCode::FORBIDDEN].into()
+    }
+}
+
+/// Why a CORS preflight request is rejected by [`Cors`](crate::middleware::Cors).
+#[derive(Debug, thiserror::Error)]
+pub enum CorsError {
+    #[error("origin `{0}` is not allowed")]
+    OriginNotAllowed(String),
+    #[error("method `{0}` is not allowed")]
+    MethodNotAllowed(String),
+    #[error("header `{0}` is not allowed")]
+    HeaderNotAllowed(String),
+}
+
+impl ResponseError for CorsError {
+    fn as_status(&self) -> StatusCode {
+        StatusCode::FORBIDDEN
+    }
+
+    fn status_codes() -> HashSet<StatusCode> {
+        [StatusCode::FORBIDDEN].into()
+    }
+}
+
+#[derive(Debug)]
+pub enum ValidError<T> {
+    Inner(T),
+    Invalid(ValidationErrors),
+}
+
+impl<T: fmt::Display> fmt::Display for ValidError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidError::Inner(e) => fmt::Display::fmt(e, f),
+            ValidError::Invalid(e) => fmt::Display::fmt(e, f),
+        }
+    }
+}
+
+impl<T: Error + 'static> Error for ValidError<T> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ValidError::Inner(e) => Some(e),
+            ValidError::Invalid(e) => Some(e),
+        }
+    }
+}
+
+impl<T: ResponseError> ResponseError for ValidError<T> {
+    fn as_status(&self) -> StatusCode {
+        match self {
+            ValidError::Inner(e) => e.as_status(),
+            ValidError::Invalid(_) => StatusCode::UNPROCESSABLE_ENTITY,
+        }
+    }
+
+    fn status_codes() -> HashSet<StatusCode> {
+        let mut status_codes = T::status_codes();
+        status_codes.insert(StatusCode::UNPROCESSABLE_ENTITY);
+        status_codes
+    }
+
+    fn as_response(&self) -> Response {
+        match self {
+            ValidError::Inner(e) => e.as_response(),
+            ValidError::Invalid(e) => Response::builder()
+                .status(self.as_status())
+                .header(
+                    CONTENT_TYPE,
+                    HeaderValue::from_static(mime::APPLICATION_JSON.as_ref()),
+                )
+                .body(serde_json::to_vec(e).unwrap_or_default().into())
+                .unwrap(),
+        }
+    }
+
+    fn responses(
+        schemas: &mut BTreeMap<String, Schema>,
+    ) -> BTreeMap<StatusCode, openapi::Response> {
+        let mut responses = T::responses(schemas);
+
+        merge_responses(
+            &mut responses,
+            [(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                openapi::Response {
+                    description: StatusCode::UNPROCESSABLE_ENTITY
+                        .canonical_reason()
+                        .unwrap_or_default()
+                        .to_string(),
+                    content: <Json<ValidationErrors> as MultiResponseMediaType>::content(schemas),
+                    ..Default::default()
+                },
+            )]
+            .into(),
+        );
+
+        responses
+    }
+
+    #[doc(hidden)]
+    fn inner(self) -> BoxError {
+        match self {
+            ValidError::Inner(e) => e.inner(),
+            ValidError::Invalid(e) => Box::new(e),
+        }
+    }
+
+    #[doc(hidden)]
+    fn wrappers(&self, type_names: &mut Vec<&'static str>) {
+        type_names.push(std::any::type_name::<Self>());
+
+        if let ValidError::Inner(e) = self {
+            e.wrappers(type_names);
+        }
+    }
+}
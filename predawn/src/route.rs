@@ -1,31 +1,77 @@
+use std::sync::Arc;
+
 use futures_util::{future::Either, Future, FutureExt};
-use http::Method;
+use http::{header, Method};
 use indexmap::IndexMap;
 use matchit::{InsertError, Match};
-use predawn_core::{error::Error, request::Request, response::Response};
+use predawn_core::{
+    error::Error,
+    request::{Head, Request},
+    response::Response,
+};
 
 use crate::{
     handler::{DynHandler, Handler},
+    middleware::CorsHandler,
     path_params::PathParams,
     response_error::{MatchError, MethodNotAllowedError},
 };
 
+/// The route matched by the [`Router`], such as `/users/{id}`, which is inserted into
+/// the extensions of the request.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MatchedRoute(Arc<str>);
+
+impl MatchedRoute {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
 #[derive(Default)]
 pub struct MethodRouter {
     methods: IndexMap<Method, DynHandler>,
+    route: Option<MatchedRoute>,
 }
 
 impl From<IndexMap<Method, DynHandler>> for MethodRouter {
     fn from(methods: IndexMap<Method, DynHandler>) -> Self {
-        Self { methods }
+        Self {
+            methods,
+            route: None,
+        }
+    }
+}
+
+impl MethodRouter {
+    /// The handler of the requested method of a CORS preflight request,
+    /// if it answers preflight requests itself, i.e. its outermost middleware is `Cors`.
+    fn preflight_handler(&self, head: &Head) -> Option<&DynHandler> {
+        if head.method != Method::OPTIONS || !head.headers.contains_key(header::ORIGIN) {
+            return None;
+        }
+
+        let method = head
+            .headers
+            .get(header::ACCESS_CONTROL_REQUEST_METHOD)
+            .and_then(|method| Method::from_bytes(method.as_bytes()).ok())?;
+
+        self.methods
+            .get(&method)
+            .filter(|handler| handler.is::<CorsHandler>())
     }
 }
 
 impl Handler for MethodRouter {
     fn call(&self, mut req: Request) -> impl Future<Output = Result<Response, Error>> + Send {
+        let handler = self
+            .methods
+            .get(&req.head.method)
+            .or_else(|| self.preflight_handler(&req.head));
+
         let method = &mut req.head.method;
 
-        match self.methods.get(method) {
+        match handler {
             Some(handler) => Either::Left(handler.call(req)),
             None => Either::Right(
                 if *method == Method::HEAD {
@@ -61,9 +107,10 @@ impl Router {
         fn inner_insert(
             router: &mut Router,
             route: String,
-            method_router: MethodRouter,
+            mut method_router: MethodRouter,
         ) -> Result<(), InsertError> {
             let methods = method_router.methods.keys().cloned().collect();
+            method_router.route = Some(MatchedRoute(route.as_str().into()));
 
             router.router.insert(route.clone(), method_router)?;
             router.routes.push((route.into(), methods));
@@ -96,6 +143,10 @@ impl Handler for Router {
             .get_or_insert_default::<PathParams>()
             .insert(matched.params);
 
+        if let Some(route) = &matched.value.route {
+            head.extensions.insert(route.clone());
+        }
+
         matched.value.call(req).await
     }
 }
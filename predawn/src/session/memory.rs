@@ -0,0 +1,65 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+use predawn_core::error::BoxError;
+
+use super::{SessionId, SessionRecord, SessionStore};
+
+/// Keeps the sessions in memory, they are lost on restart and not shared between instances.
+///
+/// Expired sessions are removed when they are loaded or by [`MemoryStore::purge_expired`].
+#[derive(Debug, Clone, Default)]
+pub struct MemoryStore {
+    sessions: Arc<RwLock<HashMap<SessionId, SessionRecord>>>,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.sessions.read().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn purge_expired(&self) {
+        self.sessions
+            .write()
+            .unwrap()
+            .retain(|_, record| !record.is_expired());
+    }
+}
+
+impl SessionStore for MemoryStore {
+    async fn load(&self, id: &SessionId) -> Result<Option<SessionRecord>, BoxError> {
+        let record = self.sessions.read().unwrap().get(id).cloned();
+
+        match record {
+            Some(record) if record.is_expired() => {
+                self.sessions.write().unwrap().remove(id);
+                Ok(None)
+            }
+            record => Ok(record),
+        }
+    }
+
+    async fn save(&self, record: &SessionRecord) -> Result<(), BoxError> {
+        self.sessions
+            .write()
+            .unwrap()
+            .insert(record.id.clone(), record.clone());
+
+        Ok(())
+    }
+
+    async fn delete(&self, id: &SessionId) -> Result<(), BoxError> {
+        self.sessions.write().unwrap().remove(id);
+        Ok(())
+    }
+}
@@ -0,0 +1,596 @@
+//! Server-side sessions, whose ids travel in a cookie signed with the cookie [`Key`].
+//!
+//! [`SessionMiddleware`](crate::middleware::SessionMiddleware) puts a [`Session`] into the
+//! request, which is loaded from the [`SessionStore`] when it is first accessed and saved
+//! after the handler if it was accessed, so requests which do not touch it cost nothing.
+
+mod memory;
+
+use std::{
+    collections::BTreeMap,
+    fmt,
+    future::Future,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use futures_util::future::BoxFuture;
+use predawn_core::{
+    api_request::ApiRequestHead,
+    error::BoxError,
+    from_request::FromRequestHead,
+    openapi::{Parameter, Schema},
+    request::Head,
+};
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{Map, Value};
+use tokio::sync::Mutex;
+
+pub use self::memory::MemoryStore;
+use crate::{
+    cookie::{Key, SetCookie, SignedCookieJar},
+    response_error::{MissingSessionError, SessionError},
+};
+
+/// Where the sessions are kept.
+///
+/// ```rust
+/// use predawn::{
+///     error::BoxError,
+///     session::{SessionId, SessionRecord, SessionStore},
+/// };
+///
+/// struct NoopStore;
+///
+/// impl SessionStore for NoopStore {
+///     async fn load(&self, _: &SessionId) -> Result<Option<SessionRecord>, BoxError> {
+///         Ok(None)
+///     }
+///
+///     async fn save(&self, _: &SessionRecord) -> Result<(), BoxError> {
+///         Ok(())
+///     }
+///
+///     async fn delete(&self, _: &SessionId) -> Result<(), BoxError> {
+///         Ok(())
+///     }
+/// }
+/// ```
+pub trait SessionStore: Send + Sync + 'static {
+    /// Returns the session, expired sessions may be returned, they are ignored.
+    fn load(
+        &self,
+        id: &SessionId,
+    ) -> impl Future<Output = Result<Option<SessionRecord>, BoxError>> + Send;
+
+    /// Inserts or replaces the session.
+    fn save(&self, record: &SessionRecord) -> impl Future<Output = Result<(), BoxError>> + Send;
+
+    fn delete(&self, id: &SessionId) -> impl Future<Output = Result<(), BoxError>> + Send;
+}
+
+pub(crate) trait DynSessionStore: Send + Sync {
+    fn load<'a>(
+        &'a self,
+        id: &'a SessionId,
+    ) -> BoxFuture<'a, Result<Option<SessionRecord>, BoxError>>;
+
+    fn save<'a>(&'a self, record: &'a SessionRecord) -> BoxFuture<'a, Result<(), BoxError>>;
+
+    fn delete<'a>(&'a self, id: &'a SessionId) -> BoxFuture<'a, Result<(), BoxError>>;
+}
+
+impl<S: SessionStore> DynSessionStore for S {
+    fn load<'a>(
+        &'a self,
+        id: &'a SessionId,
+    ) -> BoxFuture<'a, Result<Option<SessionRecord>, BoxError>> {
+        Box::pin(SessionStore::load(self, id))
+    }
+
+    fn save<'a>(&'a self, record: &'a SessionRecord) -> BoxFuture<'a, Result<(), BoxError>> {
+        Box::pin(SessionStore::save(self, record))
+    }
+
+    fn delete<'a>(&'a self, id: &'a SessionId) -> BoxFuture<'a, Result<(), BoxError>> {
+        Box::pin(SessionStore::delete(self, id))
+    }
+}
+
+/// A random id of 256 bits.
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SessionId(Box<str>);
+
+impl SessionId {
+    pub fn new<T: Into<Box<str>>>(id: T) -> Self {
+        Self(id.into())
+    }
+
+    pub fn generate() -> Self {
+        let mut bytes = [0u8; 32];
+
+        SystemRandom::new()
+            .fill(&mut bytes)
+            .expect("failed to generate a session id");
+
+        Self(URL_SAFE_NO_PAD.encode(bytes).into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Debug for SessionId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // the id is a credential, only a prefix of it is shown
+        let prefix = self.0.get(..6).unwrap_or_default();
+        write!(f, "SessionId({prefix}..)")
+    }
+}
+
+impl fmt::Display for SessionId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// A session as it is kept in a [`SessionStore`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionRecord {
+    pub id: SessionId,
+    pub data: Map<String, Value>,
+    pub created_at: SystemTime,
+    /// When the session expires, it is pushed back by the idle timeout when the session
+    /// is accessed after half of it, up to the absolute timeout since `created_at`.
+    pub expires_at: SystemTime,
+}
+
+impl SessionRecord {
+    pub fn is_expired(&self) -> bool {
+        self.expires_at <= SystemTime::now()
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct SessionSettings {
+    pub(crate) store: Arc<dyn DynSessionStore>,
+    pub(crate) key: Key,
+    /// The attributes of the cookie, its value is ignored.
+    pub(crate) cookie: SetCookie,
+    pub(crate) idle_timeout: Duration,
+    pub(crate) absolute_timeout: Duration,
+}
+
+impl fmt::Debug for SessionSettings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SessionSettings")
+            .field("cookie", &self.cookie)
+            .field("idle_timeout", &self.idle_timeout)
+            .field("absolute_timeout", &self.absolute_timeout)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The session of the request, put into it by
+/// [`SessionMiddleware`](crate::middleware::SessionMiddleware).
+///
+/// It is loaded when it is first accessed, a new one is created when a value is inserted
+/// into it and there is none.
+#[derive(Clone)]
+pub struct Session(Arc<SessionInner>);
+
+struct SessionInner {
+    settings: Arc<SessionSettings>,
+    /// The id in the cookie of the request.
+    cookie_id: Option<SessionId>,
+    state: Mutex<SessionState>,
+}
+
+#[derive(Default)]
+struct SessionState {
+    loaded: bool,
+    record: Option<SessionRecord>,
+    /// Whether `record` is not in the store yet.
+    new: bool,
+    /// Whether the data of `record` differs from the one in the store.
+    changed: bool,
+    /// The ids to delete from the store, replaced by a rotation or destroyed.
+    deleted: Vec<SessionId>,
+}
+
+impl Session {
+    pub(crate) fn new(settings: Arc<SessionSettings>, headers: &http::HeaderMap) -> Self {
+        let name = settings.cookie.name();
+
+        let cookie_id = SignedCookieJar::from_headers(headers, settings.key.clone())
+            .get(name)
+            .map(SessionId::new);
+
+        Self(Arc::new(SessionInner {
+            settings,
+            cookie_id,
+            state: Mutex::new(SessionState::default()),
+        }))
+    }
+
+    async fn state(&self) -> Result<tokio::sync::MutexGuard<'_, SessionState>, SessionError> {
+        let mut state = self.0.state.lock().await;
+
+        if state.loaded {
+            return Ok(state);
+        }
+
+        if let Some(id) = &self.0.cookie_id {
+            let store = &self.0.settings.store;
+
+            match store.load(id).await.map_err(SessionError::Store)? {
+                Some(record) if record.is_expired() => {
+                    state.deleted.push(record.id);
+                }
+                record => state.record = record,
+            }
+        }
+
+        state.loaded = true;
+
+        Ok(state)
+    }
+
+    fn new_record(&self) -> SessionRecord {
+        let now = SystemTime::now();
+
+        SessionRecord {
+            id: SessionId::generate(),
+            data: Map::new(),
+            created_at: now,
+            expires_at: now + self.0.settings.idle_timeout,
+        }
+    }
+
+    /// The id of the session, `None` if there is no session.
+    pub async fn id(&self) -> Result<Option<SessionId>, SessionError> {
+        let state = self.state().await?;
+        Ok(state.record.as_ref().map(|record| record.id.clone()))
+    }
+
+    pub async fn get<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>, SessionError> {
+        let state = self.state().await?;
+
+        let Some(value) = state
+            .record
+            .as_ref()
+            .and_then(|record| record.data.get(key))
+        else {
+            return Ok(None);
+        };
+
+        Ok(Some(T::deserialize(value)?))
+    }
+
+    /// Inserts a value, creates the session if there is none.
+    pub async fn insert<T: Serialize>(&self, key: &str, value: T) -> Result<(), SessionError> {
+        let value = serde_json::to_value(value)?;
+
+        let mut state = self.state().await?;
+
+        if state.record.is_none() {
+            state.record = Some(self.new_record());
+            state.new = true;
+        }
+
+        if let Some(record) = &mut state.record {
+            record.data.insert(key.to_string(), value);
+        }
+
+        state.changed = true;
+
+        Ok(())
+    }
+
+    pub async fn remove(&self, key: &str) -> Result<(), SessionError> {
+        let mut state = self.state().await?;
+        let state = &mut *state;
+
+        if let Some(record) = &mut state.record {
+            state.changed |= record.data.remove(key).is_some();
+        }
+
+        Ok(())
+    }
+
+    /// Removes all values but keeps the session.
+    pub async fn clear(&self) -> Result<(), SessionError> {
+        let mut state = self.state().await?;
+        let state = &mut *state;
+
+        if let Some(record) = &mut state.record {
+            state.changed |= !record.data.is_empty();
+            record.data.clear();
+        }
+
+        Ok(())
+    }
+
+    /// Gives the session a new id, which should be done when the privileges change,
+    /// e.g. on login, to prevent session fixation.
+    pub async fn rotate_id(&self) -> Result<(), SessionError> {
+        let mut state = self.state().await?;
+        let state = &mut *state;
+
+        if let Some(record) = &mut state.record {
+            let old = std::mem::replace(&mut record.id, SessionId::generate());
+
+            if !state.new {
+                state.deleted.push(old);
+            }
+
+            state.new = true;
+        }
+
+        Ok(())
+    }
+
+    /// Deletes the session and its cookie, e.g. on logout.
+    pub async fn destroy(&self) -> Result<(), SessionError> {
+        let mut state = self.state().await?;
+
+        if let Some(record) = state.record.take() {
+            if !state.new {
+                state.deleted.push(record.id);
+            }
+        }
+
+        state.new = false;
+
+        Ok(())
+    }
+
+    /// Writes the session back to the store if it changed or its idle timeout is due
+    /// to be pushed back, returns the cookie to send if the id changed.
+    pub(crate) async fn save(&self) -> Result<Option<SetCookie>, SessionError> {
+        let mut state = self.0.state.lock().await;
+
+        if !state.loaded {
+            return Ok(None);
+        }
+
+        let settings = &self.0.settings;
+
+        for id in state.deleted.drain(..) {
+            settings
+                .store
+                .delete(&id)
+                .await
+                .map_err(SessionError::Store)?;
+        }
+
+        let state = &mut *state;
+
+        let Some(record) = &mut state.record else {
+            return Ok(self.0.cookie_id.is_some().then(|| {
+                SignedCookieJar::new(settings.key.clone())
+                    .remove(settings.cookie.clone())
+                    .changes()[0]
+                    .clone()
+            }));
+        };
+
+        let now = SystemTime::now();
+        let deadline = record.created_at + settings.absolute_timeout;
+        let expires_at = (now + settings.idle_timeout).min(deadline);
+
+        // a read-only access only writes once half of the idle timeout has passed
+        let refresh =
+            expires_at > record.expires_at && record.expires_at < now + settings.idle_timeout / 2;
+
+        if state.new || state.changed || refresh {
+            record.expires_at = expires_at;
+
+            settings
+                .store
+                .save(record)
+                .await
+                .map_err(SessionError::Store)?;
+
+            state.new = false;
+            state.changed = false;
+        }
+
+        if self.0.cookie_id.as_ref() == Some(&record.id) {
+            return Ok(None);
+        }
+
+        let cookie = settings
+            .cookie
+            .clone()
+            .with_value(record.id.as_str().to_string());
+
+        Ok(Some(
+            SignedCookieJar::new(settings.key.clone())
+                .insert(cookie)
+                .changes()[0]
+                .clone(),
+        ))
+    }
+}
+
+impl fmt::Debug for Session {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Session")
+            .field("cookie_id", &self.0.cookie_id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'a> FromRequestHead<'a> for Session {
+    type Error = MissingSessionError;
+
+    async fn from_request_head(head: &'a Head) -> Result<Self, Self::Error> {
+        head.extensions
+            .get::<Session>()
+            .cloned()
+            .ok_or(MissingSessionError)
+    }
+}
+
+impl ApiRequestHead for Session {
+    fn parameters(_: &mut BTreeMap<String, Schema>) -> Option<Vec<Parameter>> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::{header::COOKIE, HeaderMap, HeaderValue};
+
+    use super::*;
+
+    fn settings(store: MemoryStore) -> Arc<SessionSettings> {
+        Arc::new(SessionSettings {
+            store: Arc::new(store),
+            key: Key::generate(),
+            cookie: SetCookie::new("session", "").path("/").http_only(true),
+            idle_timeout: Duration::from_secs(60),
+            absolute_timeout: Duration::from_secs(3600),
+        })
+    }
+
+    fn headers(cookie: &SetCookie) -> HeaderMap {
+        let value = format!("{}={}", cookie.name(), cookie.value());
+
+        let mut headers = HeaderMap::new();
+        headers.insert(COOKIE, HeaderValue::try_from(value).unwrap());
+        headers
+    }
+
+    #[tokio::test]
+    async fn lifecycle() {
+        let store = MemoryStore::new();
+        let settings = settings(store.clone());
+
+        // untouched sessions are neither loaded nor saved
+        let session = Session::new(settings.clone(), &HeaderMap::new());
+        assert_eq!(session.save().await.unwrap(), None);
+        assert!(store.is_empty());
+
+        let session = Session::new(settings.clone(), &HeaderMap::new());
+        session.insert("user", "alice").await.unwrap();
+        let cookie = session.save().await.unwrap().unwrap();
+        assert_eq!(store.len(), 1);
+
+        let session = Session::new(settings.clone(), &headers(&cookie));
+        assert_eq!(
+            session.get::<String>("user").await.unwrap().as_deref(),
+            Some("alice")
+        );
+        assert_eq!(session.save().await.unwrap(), None);
+
+        let session = Session::new(settings.clone(), &headers(&cookie));
+        let old = session.id().await.unwrap().unwrap();
+        session.rotate_id().await.unwrap();
+        let rotated = session.save().await.unwrap().unwrap();
+        assert_ne!(rotated.value(), cookie.value());
+        assert_eq!(store.len(), 1);
+        assert!(SessionStore::load(&store, &old).await.unwrap().is_none());
+
+        let session = Session::new(settings.clone(), &headers(&rotated));
+        session.destroy().await.unwrap();
+        let removal = session.save().await.unwrap().unwrap();
+        assert_eq!(removal.value(), "");
+        assert!(store.is_empty());
+
+        // a forged id is ignored
+        let forged = SetCookie::new("session", old.as_str());
+        let session = Session::new(settings, &headers(&forged));
+        assert_eq!(session.id().await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn expiry() {
+        let store = MemoryStore::new();
+        let settings = settings(store.clone());
+
+        let session = Session::new(settings.clone(), &HeaderMap::new());
+        session.insert("user", "alice").await.unwrap();
+        let cookie = session.save().await.unwrap().unwrap();
+
+        let id = session.id().await.unwrap().unwrap();
+        let mut record = SessionStore::load(&store, &id).await.unwrap().unwrap();
+        let idle = record.expires_at.duration_since(record.created_at).unwrap();
+        assert!(idle >= settings.idle_timeout && idle < settings.idle_timeout * 2);
+
+        record.expires_at = SystemTime::now() - Duration::from_secs(1);
+        SessionStore::save(&store, &record).await.unwrap();
+
+        let session = Session::new(settings.clone(), &headers(&cookie));
+        assert_eq!(session.get::<String>("user").await.unwrap(), None);
+        assert_eq!(session.save().await.unwrap().unwrap().value(), "");
+        assert!(store.is_empty());
+
+        // the idle timeout does not extend the session past the absolute timeout
+        let session = Session::new(settings.clone(), &HeaderMap::new());
+        session.insert("user", "alice").await.unwrap();
+        session.save().await.unwrap();
+
+        let id = session.id().await.unwrap().unwrap();
+        let mut record = SessionStore::load(&store, &id).await.unwrap().unwrap();
+        record.created_at -= Duration::from_secs(3590);
+        record.expires_at = SystemTime::now() + Duration::from_secs(5);
+        SessionStore::save(&store, &record).await.unwrap();
+
+        let cookie = SetCookie::new("session", settings.key.sign("session", id.as_str()));
+        let session = Session::new(settings.clone(), &headers(&cookie));
+        session.get::<String>("user").await.unwrap();
+        session.save().await.unwrap();
+
+        let record = SessionStore::load(&store, &id).await.unwrap().unwrap();
+        assert_eq!(
+            record.expires_at,
+            record.created_at + settings.absolute_timeout
+        );
+    }
+
+    #[tokio::test]
+    async fn refresh() {
+        let store = MemoryStore::new();
+        let settings = settings(store.clone());
+
+        let session = Session::new(settings.clone(), &HeaderMap::new());
+        session.insert("user", "alice").await.unwrap();
+        let cookie = session.save().await.unwrap().unwrap();
+
+        let id = session.id().await.unwrap().unwrap();
+        let saved = SessionStore::load(&store, &id).await.unwrap().unwrap();
+
+        // a read-only access within half of the idle timeout is not written
+        let session = Session::new(settings.clone(), &headers(&cookie));
+        session.get::<String>("user").await.unwrap();
+        session.remove("missing").await.unwrap();
+        session.save().await.unwrap();
+        assert_eq!(
+            SessionStore::load(&store, &id).await.unwrap().unwrap(),
+            saved
+        );
+
+        // a change is written
+        let session = Session::new(settings.clone(), &headers(&cookie));
+        session.insert("user", "bob").await.unwrap();
+        session.save().await.unwrap();
+        let changed = SessionStore::load(&store, &id).await.unwrap().unwrap();
+        assert_eq!(changed.data["user"], "bob");
+
+        // a read-only access after half of the idle timeout pushes it back
+        let mut record = changed;
+        record.expires_at = SystemTime::now() + Duration::from_secs(20);
+        SessionStore::save(&store, &record).await.unwrap();
+
+        let session = Session::new(settings.clone(), &headers(&cookie));
+        session.get::<String>("user").await.unwrap();
+        session.save().await.unwrap();
+        let refreshed = SessionStore::load(&store, &id).await.unwrap().unwrap();
+        assert!(refreshed.expires_at > record.expires_at + Duration::from_secs(30));
+    }
+}
@@ -1,6 +1,6 @@
 use std::net::SocketAddr;
 
-use reqwest::{redirect::Policy, Client, RequestBuilder};
+use reqwest::{redirect::Policy, Client, Method, RequestBuilder};
 use tokio::net::TcpListener;
 
 use crate::{
@@ -27,6 +27,11 @@ pub struct TestClient {
 impl TestClient {
     impl_request_methods![get, post, put, delete, head, patch];
 
+    pub fn request(&self, method: Method, url: &str) -> RequestBuilder {
+        self.client
+            .request(method, format!("http://{}{}", self.addr, url))
+    }
+
     pub async fn new<H: Hooks>() -> Self {
         let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
         let addr = listener.local_addr().unwrap();
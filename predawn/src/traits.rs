@@ -17,5 +17,8 @@ pub trait Tag {
 pub trait SecurityScheme {
     const NAME: &'static str;
 
+    /// The scopes declared by the scheme, `None` if they are not known ahead of time.
+    const SCOPES: Option<&'static [&'static str]> = None;
+
     fn create() -> openapi::SecurityScheme;
 }
@@ -0,0 +1,92 @@
+use http::{header::ACCEPT_LANGUAGE, HeaderName, HeaderValue};
+
+use super::{split_comma, string_header_schema, HeaderType};
+
+/// The `Accept-Language` header, the language ranges ordered by their quality values.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AcceptLanguage {
+    ranges: Vec<(String, f32)>,
+}
+
+impl AcceptLanguage {
+    /// The language ranges and their quality values, the most preferred first.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, f32)> {
+        self.ranges
+            .iter()
+            .map(|(range, quality)| (range.as_str(), *quality))
+    }
+
+    /// Picks the most preferred language from `available`, a range like `en` matches `en-US`,
+    /// and `*` matches the first available language.
+    pub fn negotiate<'a>(&self, available: &[&'a str]) -> Option<&'a str> {
+        self.iter()
+            .filter(|(_, quality)| *quality > 0.0)
+            .find_map(|(range, _)| {
+                if range == "*" {
+                    return available.first().copied();
+                }
+
+                available.iter().copied().find(|language| {
+                    language.eq_ignore_ascii_case(range)
+                        || language
+                            .get(..range.len())
+                            .is_some_and(|prefix| prefix.eq_ignore_ascii_case(range))
+                            && language.as_bytes().get(range.len()) == Some(&b'-')
+                })
+            })
+    }
+}
+
+impl HeaderType for AcceptLanguage {
+    const NAME: HeaderName = ACCEPT_LANGUAGE;
+
+    fn decode<'a, I>(values: I) -> Option<Self>
+    where
+        I: Iterator<Item = &'a HeaderValue>,
+    {
+        let mut ranges = split_comma(values)?
+            .into_iter()
+            .map(|item| {
+                let mut parts = item.split(';').map(str::trim);
+
+                let range = parts.next().filter(|range| {
+                    !range.is_empty()
+                        && range
+                            .bytes()
+                            .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'*')
+                })?;
+
+                let quality = match parts.find_map(|param| param.strip_prefix("q=")) {
+                    Some(q) => q.parse::<f32>().ok().filter(|q| (0.0..=1.0).contains(q))?,
+                    None => 1.0,
+                };
+
+                Some((range.to_string(), quality))
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        // stable, so ranges with the same quality keep their order
+        ranges.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        Some(Self { ranges })
+    }
+
+    fn encode(&self) -> HeaderValue {
+        let value = self
+            .ranges
+            .iter()
+            .map(|(range, quality)| {
+                if *quality == 1.0 {
+                    range.clone()
+                } else {
+                    format!("{range};q={quality}")
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        HeaderValue::try_from(value).expect("checked by `AcceptLanguage::decode`")
+    }
+}
+
+string_header_schema!(AcceptLanguage, "AcceptLanguage");
@@ -0,0 +1,76 @@
+use http::{header::AUTHORIZATION, HeaderName, HeaderValue};
+
+use super::{single, string_header_schema, HeaderType};
+
+/// The `Authorization` header, split into the authentication scheme and the credentials.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Authorization {
+    scheme: String,
+    credentials: String,
+}
+
+impl Authorization {
+    /// Returns `None` if the scheme is not a token or the credentials are not a valid header value.
+    pub fn new<S, C>(scheme: S, credentials: C) -> Option<Self>
+    where
+        S: Into<String>,
+        C: Into<String>,
+    {
+        let scheme = scheme.into();
+        let credentials = credentials.into();
+
+        if scheme.is_empty() || !scheme.bytes().all(|b| b.is_ascii_graphic()) {
+            return None;
+        }
+
+        HeaderValue::from_str(&credentials).ok()?;
+
+        Some(Self {
+            scheme,
+            credentials,
+        })
+    }
+
+    pub fn scheme(&self) -> &str {
+        &self.scheme
+    }
+
+    /// Whether the scheme is `scheme`, which is case-insensitive.
+    pub fn is_scheme(&self, scheme: &str) -> bool {
+        self.scheme.eq_ignore_ascii_case(scheme)
+    }
+
+    pub fn credentials(&self) -> &str {
+        &self.credentials
+    }
+}
+
+impl HeaderType for Authorization {
+    const NAME: HeaderName = AUTHORIZATION;
+
+    fn decode<'a, I>(values: I) -> Option<Self>
+    where
+        I: Iterator<Item = &'a HeaderValue>,
+    {
+        let value = single(values)?.to_str().ok()?.trim();
+
+        match value.split_once(' ') {
+            Some((scheme, credentials)) => Self::new(scheme, credentials.trim_start()),
+            None => Self::new(value, ""),
+        }
+    }
+
+    fn encode(&self) -> HeaderValue {
+        let value = if self.credentials.is_empty() {
+            self.scheme.clone()
+        } else {
+            format!("{} {}", self.scheme, self.credentials)
+        };
+
+        let mut value = HeaderValue::try_from(value).expect("checked by `Authorization::new`");
+        value.set_sensitive(true);
+        value
+    }
+}
+
+string_header_schema!(Authorization, "Authorization");
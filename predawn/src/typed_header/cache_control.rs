@@ -0,0 +1,120 @@
+use std::time::Duration;
+
+use http::{header::CACHE_CONTROL, HeaderName, HeaderValue};
+
+use super::{split_comma, string_header_schema, HeaderType};
+
+/// The `Cache-Control` header, unknown directives are ignored.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CacheControl {
+    pub no_cache: bool,
+    pub no_store: bool,
+    pub no_transform: bool,
+    pub only_if_cached: bool,
+    pub must_revalidate: bool,
+    pub proxy_revalidate: bool,
+    pub must_understand: bool,
+    pub public: bool,
+    pub private: bool,
+    pub immutable: bool,
+    pub max_age: Option<Duration>,
+    pub s_max_age: Option<Duration>,
+    pub max_stale: Option<Duration>,
+    pub min_fresh: Option<Duration>,
+    pub stale_while_revalidate: Option<Duration>,
+    pub stale_if_error: Option<Duration>,
+}
+
+impl CacheControl {
+    fn flags(&mut self) -> [(&'static str, &mut bool); 10] {
+        [
+            ("no-cache", &mut self.no_cache),
+            ("no-store", &mut self.no_store),
+            ("no-transform", &mut self.no_transform),
+            ("only-if-cached", &mut self.only_if_cached),
+            ("must-revalidate", &mut self.must_revalidate),
+            ("proxy-revalidate", &mut self.proxy_revalidate),
+            ("must-understand", &mut self.must_understand),
+            ("public", &mut self.public),
+            ("private", &mut self.private),
+            ("immutable", &mut self.immutable),
+        ]
+    }
+
+    fn durations(&mut self) -> [(&'static str, &mut Option<Duration>); 6] {
+        [
+            ("max-age", &mut self.max_age),
+            ("s-maxage", &mut self.s_max_age),
+            ("max-stale", &mut self.max_stale),
+            ("min-fresh", &mut self.min_fresh),
+            ("stale-while-revalidate", &mut self.stale_while_revalidate),
+            ("stale-if-error", &mut self.stale_if_error),
+        ]
+    }
+}
+
+impl HeaderType for CacheControl {
+    const NAME: HeaderName = CACHE_CONTROL;
+
+    fn decode<'a, I>(values: I) -> Option<Self>
+    where
+        I: Iterator<Item = &'a HeaderValue>,
+    {
+        let mut cache_control = CacheControl::default();
+
+        for directive in split_comma(values)? {
+            let (name, value) = match directive.split_once('=') {
+                Some((name, value)) => (name.trim(), Some(value.trim().trim_matches('"'))),
+                None => (directive, None),
+            };
+
+            if let Some((_, flag)) = cache_control
+                .flags()
+                .into_iter()
+                .find(|(flag_name, _)| flag_name.eq_ignore_ascii_case(name))
+            {
+                // `private` and `no-cache` may list field names, which are ignored
+                *flag = true;
+                continue;
+            }
+
+            if let Some((_, duration)) = cache_control
+                .durations()
+                .into_iter()
+                .find(|(duration_name, _)| duration_name.eq_ignore_ascii_case(name))
+            {
+                let seconds = match value {
+                    Some(value) => value.parse::<u64>().ok()?,
+                    // `max-stale` without a value accepts a response of any staleness
+                    None if name.eq_ignore_ascii_case("max-stale") => u64::MAX,
+                    None => return None,
+                };
+
+                *duration = Some(Duration::from_secs(seconds));
+            }
+        }
+
+        Some(cache_control)
+    }
+
+    fn encode(&self) -> HeaderValue {
+        let mut this = self.clone();
+        let mut directives = Vec::new();
+
+        for (name, flag) in this.flags() {
+            if *flag {
+                directives.push(name.to_string());
+            }
+        }
+
+        for (name, duration) in this.durations() {
+            if let Some(duration) = duration {
+                directives.push(format!("{name}={}", duration.as_secs()));
+            }
+        }
+
+        HeaderValue::try_from(directives.join(", ")).expect("directives are valid header values")
+    }
+}
+
+string_header_schema!(CacheControl, "CacheControl");
@@ -0,0 +1,106 @@
+use std::{fmt, str::FromStr};
+
+use http::{header::ETAG, HeaderName, HeaderValue};
+
+use super::{single, string_header_schema, HeaderType};
+
+/// The `ETag` header, an entity tag which is either strong or weak.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ETag {
+    weak: bool,
+    tag: String,
+}
+
+impl ETag {
+    /// Returns `None` if the tag contains a `"` or a character which is not visible ASCII.
+    pub fn strong<T: Into<String>>(tag: T) -> Option<Self> {
+        Self::new(false, tag.into())
+    }
+
+    /// Returns `None` if the tag contains a `"` or a character which is not visible ASCII.
+    pub fn weak<T: Into<String>>(tag: T) -> Option<Self> {
+        Self::new(true, tag.into())
+    }
+
+    fn new(weak: bool, tag: String) -> Option<Self> {
+        if tag.bytes().all(|b| b.is_ascii_graphic() && b != b'"') {
+            Some(Self { weak, tag })
+        } else {
+            None
+        }
+    }
+
+    pub fn is_weak(&self) -> bool {
+        self.weak
+    }
+
+    pub fn tag(&self) -> &str {
+        &self.tag
+    }
+
+    /// Both tags are strong and equal.
+    pub fn strong_eq(&self, other: &ETag) -> bool {
+        !self.weak && !other.weak && self.tag == other.tag
+    }
+
+    /// Both tags are equal, regardless of whether they are weak.
+    pub fn weak_eq(&self, other: &ETag) -> bool {
+        self.tag == other.tag
+    }
+}
+
+impl fmt::Display for ETag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.weak {
+            f.write_str("W/")?;
+        }
+
+        write!(f, "\"{}\"", self.tag)
+    }
+}
+
+impl FromStr for ETag {
+    type Err = InvalidETag;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (weak, quoted) = match s.strip_prefix("W/") {
+            Some(quoted) => (true, quoted),
+            None => (false, s),
+        };
+
+        let tag = quoted
+            .strip_prefix('"')
+            .and_then(|quoted| quoted.strip_suffix('"'))
+            .ok_or(InvalidETag)?;
+
+        Self::new(weak, tag.to_string()).ok_or(InvalidETag)
+    }
+}
+
+#[derive(Debug)]
+pub struct InvalidETag;
+
+impl fmt::Display for InvalidETag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid entity tag")
+    }
+}
+
+impl std::error::Error for InvalidETag {}
+
+impl HeaderType for ETag {
+    const NAME: HeaderName = ETAG;
+
+    fn decode<'a, I>(values: I) -> Option<Self>
+    where
+        I: Iterator<Item = &'a HeaderValue>,
+    {
+        single(values)?.to_str().ok()?.trim().parse().ok()
+    }
+
+    fn encode(&self) -> HeaderValue {
+        HeaderValue::try_from(self.to_string()).expect("checked by `ETag::new`")
+    }
+}
+
+string_header_schema!(ETag, "ETag");
@@ -0,0 +1,104 @@
+use http::{header::IF_NONE_MATCH, HeaderName, HeaderValue};
+
+use super::{string_header_schema, ETag, HeaderType};
+
+/// The `If-None-Match` header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IfNoneMatch {
+    /// `*`, which matches any current representation.
+    Any,
+    Tags(Vec<ETag>),
+}
+
+impl IfNoneMatch {
+    /// Whether the representation with `etag` matches, using the weak comparison,
+    /// in which case a `GET` or `HEAD` request should be answered with `304 Not Modified`.
+    pub fn matches(&self, etag: &ETag) -> bool {
+        match self {
+            IfNoneMatch::Any => true,
+            IfNoneMatch::Tags(tags) => tags.iter().any(|tag| tag.weak_eq(etag)),
+        }
+    }
+}
+
+impl HeaderType for IfNoneMatch {
+    const NAME: HeaderName = IF_NONE_MATCH;
+
+    fn decode<'a, I>(values: I) -> Option<Self>
+    where
+        I: Iterator<Item = &'a HeaderValue>,
+    {
+        let mut items = Vec::new();
+
+        for value in values {
+            let value = value.to_str().ok()?;
+
+            if value.trim() == "*" {
+                items.push(value.trim());
+            } else {
+                items.extend(split_entity_tags(value)?);
+            }
+        }
+
+        if items == ["*"] {
+            return Some(IfNoneMatch::Any);
+        }
+
+        if items.is_empty() {
+            return None;
+        }
+
+        items
+            .into_iter()
+            .map(|item| item.parse().ok())
+            .collect::<Option<_>>()
+            .map(IfNoneMatch::Tags)
+    }
+
+    fn encode(&self) -> HeaderValue {
+        match self {
+            IfNoneMatch::Any => HeaderValue::from_static("*"),
+            IfNoneMatch::Tags(tags) => {
+                let value = tags
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                HeaderValue::try_from(value).expect("entity tags are valid header values")
+            }
+        }
+    }
+}
+
+/// Splits a list of entity-tags, which cannot be split on every comma as
+/// a comma is allowed in the quoted tag, e.g. `"a,b"`.
+fn split_entity_tags(value: &str) -> Option<Vec<&str>> {
+    let mut tags = Vec::new();
+    let mut rest = value;
+
+    loop {
+        rest = rest.trim_start_matches([' ', '\t', ',']);
+
+        if rest.is_empty() {
+            return Some(tags);
+        }
+
+        let opening = if rest.starts_with("W/\"") { 2 } else { 0 };
+
+        if !rest[opening..].starts_with('"') {
+            return None;
+        }
+
+        let end = opening + 1 + rest[opening + 1..].find('"')? + 1;
+        tags.push(&rest[..end]);
+
+        rest = rest[end..].trim_start_matches([' ', '\t']);
+
+        if !rest.is_empty() && !rest.starts_with(',') {
+            return None;
+        }
+    }
+}
+
+string_header_schema!(IfNoneMatch, "IfNoneMatch");
@@ -0,0 +1,25 @@
+use http::{header::LOCATION, HeaderName, HeaderValue, Uri};
+
+use super::{single, string_header_schema, HeaderType};
+
+/// The `Location` header, an absolute URI or a relative reference.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Location(pub Uri);
+
+impl HeaderType for Location {
+    const NAME: HeaderName = LOCATION;
+
+    fn decode<'a, I>(values: I) -> Option<Self>
+    where
+        I: Iterator<Item = &'a HeaderValue>,
+    {
+        let uri = Uri::try_from(single(values)?.as_bytes()).ok()?;
+        Some(Self(uri))
+    }
+
+    fn encode(&self) -> HeaderValue {
+        HeaderValue::try_from(self.0.to_string()).expect("a URI is always a valid header value")
+    }
+}
+
+string_header_schema!(Location, "Location");
@@ -0,0 +1,164 @@
+//! Standard headers which can be extracted with [`TypedHeader`](crate::extract::TypedHeader)
+//! or used as response headers in `#[derive(SingleResponse)]`.
+
+mod accept_language;
+mod authorization;
+mod cache_control;
+mod etag;
+mod if_none_match;
+mod location;
+mod user_agent;
+
+use http::{HeaderName, HeaderValue};
+use predawn_schema::ToSchema;
+
+pub use self::{
+    accept_language::AcceptLanguage, authorization::Authorization, cache_control::CacheControl,
+    etag::ETag, if_none_match::IfNoneMatch, location::Location, user_agent::UserAgent,
+};
+
+/// A header with a known name, which can be decoded from and encoded into header values.
+pub trait HeaderType: ToSchema + Sized {
+    const NAME: HeaderName;
+
+    /// Decodes all values of the header, returns `None` if they are invalid.
+    fn decode<'a, I>(values: I) -> Option<Self>
+    where
+        I: Iterator<Item = &'a HeaderValue>;
+
+    fn encode(&self) -> HeaderValue;
+}
+
+macro_rules! string_header_schema {
+    ($ty:ty, $title:literal) => {
+        impl predawn_schema::ToSchema for $ty {
+            fn name() -> String {
+                concat!("predawn.typed_header.", $title).to_string()
+            }
+
+            fn schema(
+                _: &mut std::collections::BTreeMap<String, predawn_core::openapi::Schema>,
+            ) -> predawn_core::openapi::Schema {
+                predawn_core::openapi::Schema {
+                    schema_data: predawn_core::openapi::SchemaData {
+                        title: Some($title.to_string()),
+                        ..Default::default()
+                    },
+                    schema_kind: predawn_core::openapi::SchemaKind::Type(
+                        predawn_core::openapi::Type::String(Default::default()),
+                    ),
+                }
+            }
+        }
+
+        impl $crate::response::ToHeaderValue for $ty {
+            fn to_header_value(
+                &self,
+            ) -> Option<Result<http::HeaderValue, http::header::InvalidHeaderValue>> {
+                Some(Ok($crate::typed_header::HeaderType::encode(self)))
+            }
+        }
+    };
+}
+
+pub(crate) use string_header_schema;
+
+/// Returns the only value of a header which must not be repeated.
+fn single<'a, I>(mut values: I) -> Option<&'a HeaderValue>
+where
+    I: Iterator<Item = &'a HeaderValue>,
+{
+    let value = values.next()?;
+
+    match values.next() {
+        Some(_) => None,
+        None => Some(value),
+    }
+}
+
+/// Splits the comma separated items of all values of a header, skipping empty items.
+fn split_comma<'a, I>(values: I) -> Option<Vec<&'a str>>
+where
+    I: Iterator<Item = &'a HeaderValue>,
+{
+    let mut items = Vec::new();
+
+    for value in values {
+        let value = value.to_str().ok()?;
+
+        items.extend(
+            value
+                .split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty()),
+        );
+    }
+
+    Some(items)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn decode<H: HeaderType>(values: &[&'static str]) -> Option<H> {
+        let values = values
+            .iter()
+            .map(|value| HeaderValue::from_static(value))
+            .collect::<Vec<_>>();
+
+        H::decode(values.iter())
+    }
+
+    #[test]
+    fn test_decode_and_encode() {
+        let authorization = decode::<Authorization>(&["Bearer  abc.def"]).unwrap();
+        assert!(authorization.is_scheme("bearer"));
+        assert_eq!(authorization.credentials(), "abc.def");
+        assert_eq!(authorization.encode(), "Bearer abc.def");
+        assert!(decode::<Authorization>(&["Bearer a", "Basic b"]).is_none());
+
+        let etag = decode::<ETag>(&["W/\"v1\""]).unwrap();
+        assert!(etag.is_weak());
+        assert_eq!(etag.encode(), "W/\"v1\"");
+        assert!(decode::<ETag>(&["v1"]).is_none());
+
+        let if_none_match = decode::<IfNoneMatch>(&["\"v1\", W/\"v2\"", "\"v3\""]).unwrap();
+        assert!(if_none_match.matches(&ETag::strong("v2").unwrap()));
+        assert!(!if_none_match.matches(&ETag::strong("v4").unwrap()));
+        assert_eq!(if_none_match.encode(), "\"v1\", W/\"v2\", \"v3\"");
+        assert_eq!(decode::<IfNoneMatch>(&["*"]), Some(IfNoneMatch::Any));
+
+        let if_none_match = decode::<IfNoneMatch>(&["\"a,b\", W/\"c\""]).unwrap();
+        assert!(if_none_match.matches(&ETag::strong("a,b").unwrap()));
+        assert_eq!(if_none_match.encode(), "\"a,b\", W/\"c\"");
+        assert!(decode::<IfNoneMatch>(&["\"a\" \"b\""]).is_none());
+        assert!(decode::<IfNoneMatch>(&["\"a"]).is_none());
+
+        let accept_language = decode::<AcceptLanguage>(&["fr;q=0.5, en-US, de;q=0.8"]).unwrap();
+        assert_eq!(
+            accept_language
+                .iter()
+                .map(|(range, _)| range)
+                .collect::<Vec<_>>(),
+            ["en-US", "de", "fr"]
+        );
+        assert_eq!(accept_language.negotiate(&["fr", "de-CH"]), Some("de-CH"));
+        assert_eq!(accept_language.encode(), "en-US, de;q=0.8, fr;q=0.5");
+        assert!(decode::<AcceptLanguage>(&["en;q=2"]).is_none());
+
+        let cache_control = decode::<CacheControl>(&["public, max-age=60", "immutable"]).unwrap();
+        assert!(cache_control.public && cache_control.immutable);
+        assert_eq!(cache_control.max_age, Some(Duration::from_secs(60)));
+        assert_eq!(cache_control.encode(), "public, immutable, max-age=60");
+        assert!(decode::<CacheControl>(&["max-age=soon"]).is_none());
+
+        let location = decode::<Location>(&["/users/1"]).unwrap();
+        assert_eq!(location.encode(), "/users/1");
+
+        let user_agent = decode::<UserAgent>(&["curl/8.0"]).unwrap();
+        assert_eq!(user_agent.as_str(), "curl/8.0");
+    }
+}
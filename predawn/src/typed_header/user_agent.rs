@@ -0,0 +1,36 @@
+use http::{header::USER_AGENT, HeaderName, HeaderValue};
+
+use super::{single, string_header_schema, HeaderType};
+
+/// The `User-Agent` header.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct UserAgent(HeaderValue);
+
+impl UserAgent {
+    pub fn from_static(user_agent: &'static str) -> Self {
+        Self(HeaderValue::from_static(user_agent))
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.0.to_str().unwrap_or_default()
+    }
+}
+
+impl HeaderType for UserAgent {
+    const NAME: HeaderName = USER_AGENT;
+
+    fn decode<'a, I>(values: I) -> Option<Self>
+    where
+        I: Iterator<Item = &'a HeaderValue>,
+    {
+        let value = single(values)?;
+        value.to_str().ok()?;
+        Some(Self(value.clone()))
+    }
+
+    fn encode(&self) -> HeaderValue {
+        self.0.clone()
+    }
+}
+
+string_header_schema!(UserAgent, "UserAgent");
@@ -1,7 +1,11 @@
+use std::cmp::Ordering;
+
+use http::{HeaderMap, HeaderName};
+use percent_encoding::{percent_encode, NON_ALPHANUMERIC};
 use predawn_core::openapi::{
     Schema, SchemaData, SchemaKind, StringFormat, StringType, Type, VariantOrUnknownOrEmpty,
 };
-use serde::Deserialize;
+use serde::{de::DeserializeOwned, Deserialize};
 use serde_json::error::Category;
 
 use crate::response_error::DeserializeJsonError;
@@ -30,6 +34,27 @@ where
     })
 }
 
+/// Deserializes name-value pairs, such as headers or cookies, the same way as a query string.
+pub(crate) fn from_pairs<'a, I, T>(pairs: I) -> Result<T, serde_html_form::de::Error>
+where
+    I: IntoIterator<Item = (&'a [u8], &'a [u8])>,
+    T: DeserializeOwned,
+{
+    let mut encoded = String::new();
+
+    pairs.into_iter().for_each(|(name, value)| {
+        if !encoded.is_empty() {
+            encoded.push('&');
+        }
+
+        encoded.extend(percent_encode(name, NON_ALPHANUMERIC));
+        encoded.push('=');
+        encoded.extend(percent_encode(value, NON_ALPHANUMERIC));
+    });
+
+    serde_html_form::from_str(&encoded)
+}
+
 pub(crate) fn binary_schema(title: &'static str) -> Schema {
     let ty = StringType {
         format: VariantOrUnknownOrEmpty::Item(StringFormat::Binary),
@@ -44,3 +69,46 @@ pub(crate) fn binary_schema(title: &'static str) -> Schema {
         schema_kind: SchemaKind::Type(Type::String(ty)),
     }
 }
+
+/// Returns the quality value of the most specific items in a header like `Accept` or
+/// `Accept-Encoding`, or `None` if no item matches.
+///
+/// `specificity` returns `None` if an item does not match, otherwise how specific the match is,
+/// e.g. `*` is less specific than `gzip`.
+pub(crate) fn header_quality<F>(
+    headers: &HeaderMap,
+    name: HeaderName,
+    specificity: F,
+) -> Option<f32>
+where
+    F: Fn(&str) -> Option<u8>,
+{
+    headers
+        .get_all(name)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .filter_map(|item| {
+            let mut parts = item.split(';').map(str::trim);
+
+            let name = parts.next()?;
+
+            if name.is_empty() {
+                return None;
+            }
+
+            let specificity = specificity(name)?;
+
+            let quality = parts
+                .find_map(|param| param.strip_prefix("q="))
+                .map_or(1.0, |q| q.parse::<f32>().unwrap_or(0.0));
+
+            Some((specificity, quality))
+        })
+        .reduce(|a, b| match a.0.cmp(&b.0) {
+            Ordering::Less => b,
+            Ordering::Greater => a,
+            Ordering::Equal => (a.0, a.1.max(b.1)),
+        })
+        .map(|(_, quality)| quality)
+}
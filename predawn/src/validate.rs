@@ -0,0 +1,534 @@
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
+    fmt::{self, Write},
+    net::{Ipv4Addr, Ipv6Addr},
+    rc::Rc,
+    sync::{Arc, RwLock},
+};
+
+use once_cell::sync::Lazy;
+use predawn_core::openapi::{
+    ArrayType, ObjectType, ReferenceOr, Schema, SchemaData, SchemaKind, Type,
+};
+use predawn_schema::ToSchema;
+use regex::Regex;
+use serde::Serialize;
+
+use crate::{
+    extract::{Cookie, Header, Path, Query},
+    openapi::Constraints,
+    payload::{Form, Json},
+};
+
+/// Validates a value after it has been deserialized.
+///
+/// Usually derived with `#[derive(Validate)]`, which enforces the same `#[schema(...)]` keywords
+/// that are written into the OpenAPI document.
+pub trait Validate {
+    fn validate(&self, errors: &mut ValidationErrors);
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PathSegment {
+    Field(Cow<'static, str>),
+    Index(usize),
+}
+
+/// A constraint violated by the value at `path`, e.g. `tags[1].name`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Violation {
+    pub path: String,
+    pub message: String,
+}
+
+/// Collects the violations of a value and the path of the value which is being validated.
+#[derive(Debug, Default, Serialize)]
+pub struct ValidationErrors {
+    #[serde(skip)]
+    path: Vec<PathSegment>,
+    errors: Vec<Violation>,
+}
+
+impl ValidationErrors {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a violation of the value at the current path.
+    pub fn add<M: Into<String>>(&mut self, message: M) {
+        let mut path = String::new();
+
+        for segment in &self.path {
+            match segment {
+                PathSegment::Field(name) => {
+                    if !path.is_empty() {
+                        path.push('.');
+                    }
+
+                    path.push_str(name);
+                }
+                PathSegment::Index(index) => {
+                    let _ = write!(path, "[{index}]");
+                }
+            }
+        }
+
+        self.errors.push(Violation {
+            path,
+            message: message.into(),
+        });
+    }
+
+    /// Validates the field `name` of the current value.
+    pub fn field<N, F>(&mut self, name: N, f: F)
+    where
+        N: Into<Cow<'static, str>>,
+        F: FnOnce(&mut Self),
+    {
+        self.path.push(PathSegment::Field(name.into()));
+        f(self);
+        self.path.pop();
+    }
+
+    /// Validates the element at `index` of the current value.
+    pub fn index<F>(&mut self, index: usize, f: F)
+    where
+        F: FnOnce(&mut Self),
+    {
+        self.path.push(PathSegment::Index(index));
+        f(self);
+        self.path.pop();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    pub fn violations(&self) -> &[Violation] {
+        &self.errors
+    }
+
+    pub fn into_violations(self) -> Vec<Violation> {
+        self.errors
+    }
+}
+
+impl fmt::Display for ValidationErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("validation failed")?;
+
+        for (i, Violation { path, message }) in self.errors.iter().enumerate() {
+            let separator = if i == 0 { ": " } else { ", " };
+
+            if path.is_empty() {
+                write!(f, "{separator}{message}")?;
+            } else {
+                write!(f, "{separator}`{path}` {message}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for ValidationErrors {}
+
+impl ToSchema for ValidationErrors {
+    fn schema(schemas: &mut BTreeMap<String, Schema>) -> Schema {
+        let mut string = || ReferenceOr::Item(Box::new(String::schema(schemas)));
+
+        let violation = ObjectType {
+            properties: [
+                ("path".to_string(), string()),
+                ("message".to_string(), string()),
+            ]
+            .into(),
+            required: vec!["path".to_string(), "message".to_string()],
+            ..Default::default()
+        };
+
+        let errors = ArrayType {
+            items: Some(ReferenceOr::Item(Box::new(Schema {
+                schema_data: SchemaData {
+                    title: Some("Violation".to_string()),
+                    ..Default::default()
+                },
+                schema_kind: SchemaKind::Type(Type::Object(violation)),
+            }))),
+            min_items: None,
+            max_items: None,
+            unique_items: false,
+        };
+
+        let ty = ObjectType {
+            properties: [(
+                "errors".to_string(),
+                ReferenceOr::Item(Box::new(Schema {
+                    schema_data: Default::default(),
+                    schema_kind: SchemaKind::Type(Type::Array(errors)),
+                })),
+            )]
+            .into(),
+            required: vec!["errors".to_string()],
+            ..Default::default()
+        };
+
+        Schema {
+            schema_data: SchemaData {
+                title: Some("ValidationErrors".to_string()),
+                ..Default::default()
+            },
+            schema_kind: SchemaKind::Type(Type::Object(ty)),
+        }
+    }
+}
+
+/// Checks the `#[schema(...)]` keywords against a value, used by `#[derive(Validate)]`.
+#[doc(hidden)]
+pub trait Constrained {
+    fn check(&self, constraints: &Constraints, errors: &mut ValidationErrors);
+}
+
+fn check_length(length: usize, constraints: &Constraints, errors: &mut ValidationErrors) {
+    if let Some(min_length) = constraints.min_length {
+        if length < min_length {
+            errors.add(format!("length must be at least {min_length}"));
+        }
+    }
+
+    if let Some(max_length) = constraints.max_length {
+        if length > max_length {
+            errors.add(format!("length must be at most {max_length}"));
+        }
+    }
+}
+
+fn check_range(value: f64, constraints: &Constraints, errors: &mut ValidationErrors) {
+    if let Some(minimum) = constraints.minimum {
+        if value < minimum {
+            errors.add(format!("must be greater than or equal to {minimum}"));
+        }
+    }
+
+    if let Some(maximum) = constraints.maximum {
+        if value > maximum {
+            errors.add(format!("must be less than or equal to {maximum}"));
+        }
+    }
+}
+
+static PATTERNS: Lazy<RwLock<HashMap<&'static str, Regex>>> = Lazy::new(Default::default);
+
+#[track_caller]
+fn matches_pattern(pattern: &'static str, value: &str) -> bool {
+    if let Some(regex) = PATTERNS.read().unwrap().get(pattern) {
+        return regex.is_match(value);
+    }
+
+    let regex = Regex::new(pattern)
+        .unwrap_or_else(|e| panic!("invalid `pattern` in `#[schema(...)]`: {e}"));
+
+    let is_match = regex.is_match(value);
+
+    PATTERNS.write().unwrap().insert(pattern, regex);
+
+    is_match
+}
+
+/// Returns `None` if the format is not enforced, only documented.
+fn matches_format(format: &str, value: &str) -> Option<bool> {
+    let is_match = match format {
+        "email" => is_email(value),
+        "uri" | "url" => url::Url::parse(value).is_ok(),
+        "uuid" => is_uuid(value),
+        "ipv4" => value.parse::<Ipv4Addr>().is_ok(),
+        "ipv6" => value.parse::<Ipv6Addr>().is_ok(),
+        _ => return None,
+    };
+
+    Some(is_match)
+}
+
+fn is_email(value: &str) -> bool {
+    let Some((local, domain)) = value.rsplit_once('@') else {
+        return false;
+    };
+
+    if local.is_empty() || local.len() > 64 || domain.len() > 255 {
+        return false;
+    }
+
+    if value.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        return false;
+    }
+
+    if local.contains('@') {
+        return false;
+    }
+
+    let mut labels = domain.split('.');
+
+    labels.clone().count() >= 2
+        && labels.all(|label| {
+            !label.is_empty()
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_alphanumeric() || c == '-')
+        })
+}
+
+fn is_uuid(value: &str) -> bool {
+    value.len() == 36
+        && value.char_indices().all(|(i, c)| match i {
+            8 | 13 | 18 | 23 => c == '-',
+            _ => c.is_ascii_hexdigit(),
+        })
+}
+
+impl Constrained for str {
+    fn check(&self, constraints: &Constraints, errors: &mut ValidationErrors) {
+        check_length(self.chars().count(), constraints, errors);
+
+        if let Some(pattern) = constraints.pattern {
+            if !matches_pattern(pattern, self) {
+                errors.add(format!("must match the pattern `{pattern}`"));
+            }
+        }
+
+        if let Some(format) = constraints.format {
+            if matches_format(format, self) == Some(false) {
+                errors.add(format!("must be a valid {format}"));
+            }
+        }
+    }
+}
+
+impl Constrained for String {
+    fn check(&self, constraints: &Constraints, errors: &mut ValidationErrors) {
+        self.as_str().check(constraints, errors)
+    }
+}
+
+impl Constrained for Cow<'_, str> {
+    fn check(&self, constraints: &Constraints, errors: &mut ValidationErrors) {
+        self.as_ref().check(constraints, errors)
+    }
+}
+
+macro_rules! number_impl {
+    ($($ty:ty),+ $(,)?) => {
+        $(
+            impl Constrained for $ty {
+                fn check(&self, constraints: &Constraints, errors: &mut ValidationErrors) {
+                    check_range(*self as f64, constraints, errors)
+                }
+            }
+        )+
+    };
+}
+
+number_impl![i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64];
+
+macro_rules! collection_impl {
+    ($($desc:tt)+) => {
+        impl $($desc)+ {
+            fn check(&self, constraints: &Constraints, errors: &mut ValidationErrors) {
+                check_length(self.len(), constraints, errors)
+            }
+        }
+    };
+}
+
+collection_impl!(<T> Constrained for [T]);
+collection_impl!(<T> Constrained for Vec<T>);
+collection_impl!(<T> Constrained for VecDeque<T>);
+collection_impl!(<T, S> Constrained for HashSet<T, S>);
+collection_impl!(<T> Constrained for BTreeSet<T>);
+collection_impl!(<K, V, S> Constrained for HashMap<K, V, S>);
+collection_impl!(<K, V> Constrained for BTreeMap<K, V>);
+
+impl<T: Constrained> Constrained for Option<T> {
+    fn check(&self, constraints: &Constraints, errors: &mut ValidationErrors) {
+        if let Some(value) = self {
+            value.check(constraints, errors)
+        }
+    }
+}
+
+macro_rules! forward_constrained_impl {
+    ($($desc:tt)+) => {
+        impl $($desc)+ {
+            fn check(&self, constraints: &Constraints, errors: &mut ValidationErrors) {
+                T::check(self, constraints, errors)
+            }
+        }
+    };
+}
+
+forward_constrained_impl!(<T: Constrained + ?Sized> Constrained for &T);
+forward_constrained_impl!(<T: Constrained + ?Sized> Constrained for Box<T>);
+forward_constrained_impl!(<T: Constrained + ?Sized> Constrained for Rc<T>);
+forward_constrained_impl!(<T: Constrained + ?Sized> Constrained for Arc<T>);
+
+impl<T: Validate> Validate for Option<T> {
+    fn validate(&self, errors: &mut ValidationErrors) {
+        if let Some(value) = self {
+            value.validate(errors)
+        }
+    }
+}
+
+macro_rules! seq_validate_impl {
+    ($($desc:tt)+) => {
+        impl $($desc)+ {
+            fn validate(&self, errors: &mut ValidationErrors) {
+                self.iter()
+                    .enumerate()
+                    .for_each(|(index, value)| errors.index(index, |errors| value.validate(errors)))
+            }
+        }
+    };
+}
+
+seq_validate_impl!(<T: Validate> Validate for [T]);
+seq_validate_impl!(<T: Validate> Validate for Vec<T>);
+seq_validate_impl!(<T: Validate> Validate for VecDeque<T>);
+
+macro_rules! map_validate_impl {
+    ($($desc:tt)+) => {
+        impl $($desc)+ {
+            fn validate(&self, errors: &mut ValidationErrors) {
+                self.iter().for_each(|(key, value)| {
+                    errors.field(key.to_string(), |errors| value.validate(errors))
+                })
+            }
+        }
+    };
+}
+
+map_validate_impl!(<K: fmt::Display, V: Validate, S> Validate for HashMap<K, V, S>);
+map_validate_impl!(<K: fmt::Display, V: Validate> Validate for BTreeMap<K, V>);
+
+macro_rules! forward_validate_impl {
+    ($($desc:tt)+) => {
+        impl $($desc)+ {
+            fn validate(&self, errors: &mut ValidationErrors) {
+                T::validate(self, errors)
+            }
+        }
+    };
+}
+
+forward_validate_impl!(<T: Validate + ?Sized> Validate for &T);
+forward_validate_impl!(<T: Validate + ?Sized> Validate for Box<T>);
+forward_validate_impl!(<T: Validate + ?Sized> Validate for Rc<T>);
+forward_validate_impl!(<T: Validate + ?Sized> Validate for Arc<T>);
+
+macro_rules! extractor_validate_impl {
+    ($($ty:ident),+ $(,)?) => {
+        $(
+            impl<T: Validate> Validate for $ty<T> {
+                fn validate(&self, errors: &mut ValidationErrors) {
+                    self.0.validate(errors)
+                }
+            }
+        )+
+    };
+}
+
+extractor_validate_impl![Json, Form, Query, Path, Header, Cookie];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn violations<T: Constrained + ?Sized>(value: &T, constraints: Constraints) -> Vec<String> {
+        let mut errors = ValidationErrors::new();
+        errors.field("a", |errors| value.check(&constraints, errors));
+
+        errors
+            .into_violations()
+            .into_iter()
+            .map(|Violation { path, message }| format!("{path}: {message}"))
+            .collect()
+    }
+
+    #[test]
+    fn test_constraints() {
+        let length = Constraints {
+            min_length: Some(2),
+            max_length: Some(3),
+            ..Default::default()
+        };
+
+        assert!(violations("ab", length).is_empty());
+        assert!(violations("äöü", length).is_empty());
+        assert_eq!(violations("a", length), ["a: length must be at least 2"]);
+        assert_eq!(
+            violations(&vec![1, 2, 3, 4], length),
+            ["a: length must be at most 3"]
+        );
+        assert!(violations(&None::<String>, length).is_empty());
+
+        let range = Constraints {
+            minimum: Some(0.0),
+            maximum: Some(1.5),
+            ..Default::default()
+        };
+
+        assert!(violations(&1, range).is_empty());
+        assert_eq!(
+            violations(&-1, range),
+            ["a: must be greater than or equal to 0"]
+        );
+        assert_eq!(
+            violations(&2.0, range),
+            ["a: must be less than or equal to 1.5"]
+        );
+
+        let pattern = Constraints {
+            pattern: Some("^[a-z]+$"),
+            ..Default::default()
+        };
+
+        assert!(violations("abc", pattern).is_empty());
+        assert_eq!(
+            violations("aB", pattern),
+            ["a: must match the pattern `^[a-z]+$`"]
+        );
+
+        let format = |format| Constraints {
+            format: Some(format),
+            ..Default::default()
+        };
+
+        assert!(violations("john@example.com", format("email")).is_empty());
+        assert!(!violations("john@example", format("email")).is_empty());
+        assert!(!violations("john doe@example.com", format("email")).is_empty());
+        assert!(violations("https://example.com/a?b", format("uri")).is_empty());
+        assert!(!violations("example.com", format("url")).is_empty());
+        assert!(violations("67e55044-10b1-426f-9247-bb680e5fe0c8", format("uuid")).is_empty());
+        assert!(!violations("67e55044-10b1-426f-9247-bb680e5fe0c", format("uuid")).is_empty());
+        assert!(violations("::1", format("ipv6")).is_empty());
+        assert!(!violations("256.0.0.1", format("ipv4")).is_empty());
+        assert!(violations("anything", format("password")).is_empty());
+    }
+
+    #[test]
+    fn test_path() {
+        let mut errors = ValidationErrors::new();
+
+        errors.add("root");
+        errors.field("tags", |errors| {
+            errors.index(1, |errors| {
+                errors.field("name", |errors| errors.add("nested"))
+            })
+        });
+
+        assert_eq!(
+            errors.to_string(),
+            "validation failed: root, `tags[1].name` nested"
+        );
+    }
+}